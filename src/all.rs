@@ -0,0 +1,111 @@
+use std::sync::mpsc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use advent_of_code_2022::config::Config;
+use advent_of_code_2022::input::InputSource;
+use advent_of_code_2022::solution::{Answer, Params};
+use advent_of_code_2022::utils::{Error, Part};
+use crate::report::{solve_part, to_json, to_markdown, Format, PartResult};
+
+pub(crate) struct AllArgs {
+    pub(crate) input: InputSource,
+    pub(crate) config: Option<String>,
+    /// Defaults to one thread per core.
+    pub(crate) threads: Option<usize>,
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) format: Format
+}
+
+/// A thread solving one part.
+struct Worker {
+    day: u8,
+    part: Part,
+    start: Instant,
+    timed_out: bool,
+    handle: JoinHandle<()>
+}
+
+/// Solves every part of every day, running up to `threads` parts at once, and returns the results in day order.
+/// Parts that exceed the timeout are reported as failed. Their threads cannot be stopped, so they keep counting
+/// towards `threads` until they finish, but they are not waited for once every other part is done.
+pub(crate) fn solve_all(args: &AllArgs, config: &Config) -> Vec<PartResult> {
+    let threads = args.threads.unwrap_or_else(|| thread::available_parallelism().map_or(1, |n| n.get())).max(1);
+    let mut pending = (1..=25u8).flat_map(|day| [(day, Part::One), (day, Part::Two)]).peekable();
+    let mut running: Vec<Worker> = Vec::new();
+    let mut results: Vec<PartResult> = Vec::new();
+    let (sender, receiver) = mpsc::channel();
+    loop {
+        while running.len() < threads {
+            let Some((day, part)) = pending.next() else { break };
+            let sender = sender.clone();
+            let input = args.input.clone();
+            let params = Params::new(day, &input, config);
+            let handle = thread::spawn(move || { let _ = sender.send(solve_part(day, part, &input, &params)); });
+            running.push(Worker { day, part, start: Instant::now(), timed_out: false, handle });
+        }
+        let first_start = running.iter().filter(|worker| !worker.timed_out).map(|worker| worker.start).min();
+        let next = match (first_start, args.timeout) {
+            (None, _) if pending.peek().is_none() => break,
+            (Some(first_start), Some(timeout)) => receiver.recv_timeout((first_start + timeout).saturating_duration_since(Instant::now())).ok(),
+            // Either no timeout, or every thread is taken by parts that timed out
+            _ => receiver.recv().ok()
+        };
+        if let Some(result) = next {
+            if let Some(i) = running.iter().position(|worker| worker.day == result.day && worker.part == result.part) {
+                let worker = running.remove(i);
+                // The thread is done once it sent the result, so its slot is only free after it exited
+                let _ = worker.handle.join();
+                if !worker.timed_out { results.push(result) }
+            }
+        }
+        if let Some(timeout) = args.timeout {
+            for worker in running.iter_mut().filter(|worker| !worker.timed_out && worker.start.elapsed() >= timeout) {
+                results.push(PartResult { day: worker.day, part: worker.part, answer: Err(format!("Timed out after {timeout:.0?}")), duration: timeout });
+                worker.timed_out = true;
+            }
+        }
+    }
+    results.sort_by_key(|r| (r.day, r.part == Part::Two));
+    results
+}
+
+fn print_table(results: &[PartResult]) {
+    let cell = |result: &PartResult| match &result.answer {
+        Ok(Answer::Image(_)) => "(see below)".to_string(),
+        Ok(answer) => answer.to_string(),
+        Err(err) => format!("FAILED: {}", err.lines().next().unwrap_or_default())
+    };
+    let width = results.iter().map(|r| cell(r).len()).max().unwrap_or(0).max("Answer".len());
+    println!("{:>3}  {:>4}  {:<width$}  {:>12}", "Day", "Part", "Answer", "Time");
+    for result in results {
+        println!("{:>3}  {:>4}  {:<width$}  {:>12.2?}", result.day, result.part, cell(result), result.duration);
+    }
+}
+
+impl AllArgs {
+    pub(crate) fn execute(&self) -> Result<(), Error> {
+        let start = Instant::now();
+        let results = solve_all(self, &Config::load(self.config.as_deref())?);
+        match self.format {
+            Format::Text => {
+                print_table(&results);
+                println!("Total: {:.2?}", start.elapsed());
+                for result in &results {
+                    match &result.answer {
+                        Ok(Answer::Image(rows)) => println!("\nDay {} part {}:\n{}", result.day, result.part, rows.join("\n")),
+                        Err(err) if err.contains('\n') => println!("\nDay {} part {} failed:\n{err}", result.day, result.part),
+                        _ => {}
+                    }
+                }
+            },
+            Format::Json => println!("{}", to_json(&results)),
+            Format::Markdown => print!("{}", to_markdown(&results))
+        }
+        let failed = results.iter().filter(|r| r.answer.is_err()).count();
+        if failed > 0 {
+            eprintln!("{failed} parts failed");
+            std::process::exit(1);
+        }
+        Ok(())
+    }
+}
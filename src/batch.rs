@@ -0,0 +1,81 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use advent_of_code_2022::config::{Config, CONFIG_FILE};
+use advent_of_code_2022::input::InputSource;
+use advent_of_code_2022::solution::{Answer, Params};
+use advent_of_code_2022::utils::{Error, Part};
+use crate::report::{solve_part, PartResult};
+
+pub(crate) struct BatchArgs {
+    pub(crate) day: u8,
+    /// Every file in here is one input, typically named after whoever it belongs to.
+    pub(crate) dir: String,
+    /// Defaults to the [CONFIG_FILE] in `dir`, so that inputs can bring their settings along.
+    pub(crate) config: Option<String>
+}
+
+/// The files in `dir`, sorted by name. Hidden files and the config are skipped.
+fn input_files(dir: &str) -> Result<Vec<PathBuf>, Error> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).map_err(|err| Error::from(err).in_file(dir))? {
+        let path = entry?.path();
+        let skipped = path.file_name().and_then(|name| name.to_str()).is_none_or(|name| name.starts_with('.') || name == CONFIG_FILE);
+        if path.is_file() && !skipped { files.push(path) }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn cell(result: &PartResult) -> String {
+    match &result.answer {
+        Ok(Answer::Image(rows)) => format!("({} rows, see below)", rows.len()),
+        Ok(answer) => answer.to_string(),
+        Err(err) => format!("FAILED: {}", err.lines().next().unwrap_or_default())
+    }
+}
+
+impl BatchArgs {
+    pub(crate) fn execute(&self) -> Result<(), Error> {
+        let files = input_files(&self.dir)?;
+        if files.is_empty() {
+            return Err(Error::invalid(format!("There are no inputs in {}", self.dir)))
+        }
+        let dir_config = format!("{}/{CONFIG_FILE}", self.dir);
+        let config = match &self.config {
+            None if Path::new(&dir_config).exists() => Config::read(&dir_config)?,
+            config => Config::load(config.as_deref())?
+        };
+        let rows: Vec<(String, [PartResult; 2])> = files.iter().map(|file| {
+            let input = InputSource::Path(file.to_string_lossy().to_string());
+            let params = Params::new(self.day, &input, &config);
+            let name = file.file_name().map_or_else(String::new, |name| name.to_string_lossy().to_string());
+            (name, [Part::One, Part::Two].map(|part| solve_part(self.day, part, &input, &params)))
+        }).collect();
+
+        let name_width = rows.iter().map(|(name, _)| name.len()).max().unwrap_or(0).max("Input".len());
+        let width = |part: usize| rows.iter().map(|(_, results)| cell(&results[part]).len()).max().unwrap_or(0).max("Part 1".len());
+        let (width1, width2) = (width(0), width(1));
+        println!("{:<name_width$}  {:<width1$}  {:<width2$}  {:>12}", "Input", "Part 1", "Part 2", "Time");
+        for (name, [part1, part2]) in &rows {
+            println!("{name:<name_width$}  {:<width1$}  {:<width2$}  {:>12.2?}",
+                     cell(part1), cell(part2), part1.duration + part2.duration);
+        }
+        for (name, results) in &rows {
+            // A broken input usually fails both parts the same way, once is enough to see why
+            let same_error = matches!((&results[0].answer, &results[1].answer), (Err(err1), Err(err2)) if err1 == err2);
+            for result in &results[..if same_error { 1 } else { 2 }] {
+                match &result.answer {
+                    Ok(Answer::Image(rows)) => println!("\n{name} part {}:\n{}", result.part, rows.join("\n")),
+                    Err(err) if err.contains('\n') => println!("\n{name} part {} failed:\n{err}", result.part),
+                    _ => {}
+                }
+            }
+        }
+        let failed = rows.iter().filter(|(_, results)| results.iter().any(|r| r.answer.is_err())).count();
+        if failed > 0 {
+            eprintln!("{failed} of {} inputs failed", rows.len());
+            std::process::exit(1);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+use std::fs;
+use std::time::{Duration, Instant};
+use advent_of_code_2022::config::Config;
+use advent_of_code_2022::input::InputSource;
+use advent_of_code_2022::solution::{solution_for, Params};
+use advent_of_code_2022::utils::{parse_num, read_input, Error, Part};
+
+pub(crate) struct BenchArgs {
+    pub(crate) days: Vec<u8>,
+    pub(crate) runs: usize,
+    pub(crate) input: InputSource,
+    pub(crate) config: Option<String>,
+    /// File to write the median of every phase to, for later use as baseline.
+    pub(crate) save: Option<String>,
+    pub(crate) baseline: Option<String>,
+    /// How many percent a median may be slower than its baseline before it counts as a regression.
+    pub(crate) tolerance: f64
+}
+
+const PHASES: [&str; 3] = ["parse", "part1", "part2"];
+
+struct Timing {
+    day: u8,
+    phase: &'static str,
+    min: Duration,
+    median: Duration,
+    max: Duration
+}
+
+/// Times parsing and both parts separately, each on a freshly parsed input.
+fn time_day(day: u8, args: &BenchArgs, config: &Config) -> Result<Vec<Timing>, Error> {
+    let solution = solution_for(day, &Params::new(day, &args.input, config))?;
+    let mut durations: [Vec<Duration>; 3] = [Vec::new(), Vec::new(), Vec::new()];
+    for _ in 0..args.runs {
+        for (i, part) in [Part::One, Part::Two].into_iter().enumerate() {
+            let path = args.input.path(day, part)?;
+            let text = args.input.read(day, part)?;
+            let start = Instant::now();
+            let input = solution.parse_any(&text).map_err(|err| err.in_file(&path))?;
+            let parsed = Instant::now();
+            solution.solve_any(input.as_ref(), part)?;
+            if part == Part::One { durations[0].push(parsed - start) }
+            durations[i + 1].push(parsed.elapsed());
+        }
+    }
+    Ok(durations.into_iter().zip(PHASES).map(|(mut durations, phase)| {
+        durations.sort();
+        Timing {
+            day,
+            phase,
+            min: durations[0],
+            median: durations[durations.len() / 2],
+            max: durations[durations.len() - 1]
+        }
+    }).collect())
+}
+
+/// Baselines are stored as one `day phase nanoseconds` line per median.
+fn read_baseline(path: &str) -> Result<HashMap<(u8, String), Duration>, Error> {
+    read_input(path)?.lines().enumerate().map(|(i, line)| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        match fields[..] {
+            [day, phase, nanos] => Ok(((parse_num(day)?, phase.to_string()), Duration::from_nanos(parse_num(nanos)?))),
+            _ => Err(Error::parse("Expected `day phase nanoseconds`"))
+        }.map_err(|err: Error| err.on_line(i, line).in_file(path))
+    }).collect()
+}
+
+fn write_baseline(path: &str, timings: &[Timing]) -> Result<(), Error> {
+    let lines: Vec<String> = timings.iter()
+        .map(|t| format!("{:02} {} {}", t.day, t.phase, t.median.as_nanos()))
+        .collect();
+    Ok(fs::write(path, lines.join("\n") + "\n")?)
+}
+
+impl BenchArgs {
+    pub(crate) fn execute(&self) -> Result<(), Error> {
+        if self.runs == 0 {
+            return Err(Error::invalid("Need at least one run"))
+        }
+        let baseline = self.baseline.as_deref().map(read_baseline).transpose()?;
+        let config = Config::load(self.config.as_deref())?;
+        let mut timings = Vec::new();
+        let mut regressions = 0;
+        println!("{:>3}  {:<6} {:>12} {:>12} {:>12}{}", "Day", "Phase", "Min", "Median", "Max",
+                 if baseline.is_some() { format!(" {:>12} {:>9}", "Baseline", "Change") } else { String::new() });
+        for &day in &self.days {
+            for timing in time_day(day, self, &config)? {
+                let mut line = format!("{:>3}  {:<6} {:>12.2?} {:>12.2?} {:>12.2?}",
+                                       timing.day, timing.phase, timing.min, timing.median, timing.max);
+                if let Some(before) = baseline.as_ref().and_then(|b| b.get(&(day, timing.phase.to_string()))) {
+                    let change = 100.0 * (timing.median.as_secs_f64() / before.as_secs_f64() - 1.0);
+                    line += &format!(" {:>12.2?} {:>+8.1}%", before, change);
+                    if change > self.tolerance {
+                        regressions += 1;
+                        line += "  REGRESSION";
+                    }
+                }
+                println!("{line}");
+                timings.push(timing);
+            }
+        }
+        if let Some(path) = &self.save {
+            write_baseline(path, &timings)?;
+        }
+        if regressions > 0 {
+            eprintln!("{regressions} phases got more than {}% slower", self.tolerance);
+            std::process::exit(1);
+        }
+        Ok(())
+    }
+}
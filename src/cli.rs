@@ -0,0 +1,393 @@
+use std::any::Any;
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::time::Duration;
+use crate::all::AllArgs;
+use crate::batch::BatchArgs;
+use crate::bench::BenchArgs;
+use crate::guide::GuideArgs;
+use crate::report::{solve_part, to_json, to_markdown, Format};
+use crate::rucksacks::RucksackArgs;
+use advent_of_code_2022::config::Config;
+use advent_of_code_2022::generate::generate;
+use advent_of_code_2022::input::InputSource;
+use advent_of_code_2022::log::Level;
+use advent_of_code_2022::solution::{solution_for, Answer, Params};
+use advent_of_code_2022::utils::{Error, Part};
+use advent_of_code_2022::visualize::{Output, Visualize};
+
+pub(crate) const USAGE: &str = "\
+Usage: aoc run <day> [options]
+       aoc bench [<day>...] [bench options]
+       aoc all [--sample|--actual] [--threads <n>] [--timeout <seconds>] [--format <format>] [--config <path>]
+       aoc batch <day> <dir> [--config <path>]
+       aoc generate <day> [--seed <n>] [--size <n>] [--output <path>]
+       aoc guide [--sample|--actual|--input <path>] [--config <path>] [--set <key>=<value>] [--rounds <n>]
+       aoc rucksacks [--sample|--actual|--input <path>] [--config <path>] [--set <key>=<value>]
+       aoc help
+
+Every command accepts -v, -vv or -vvv to log more to stderr, or --log <error|info|debug|trace>.
+
+Options:
+    --part <1|2>                 Only solve the given part
+    --sample                     Use input/dayNN_sample.txt
+    --actual                     Use input/dayNN.txt (default)
+    --input <path>               Use an arbitrary input file, or stdin for -. Files named like
+                                 dayNN_sample.txt get the sample defaults of --row, --max and --cube-layout
+    --marker-len <n>             Day 6: length of the marker (default: 4 for part 1, 14 for part 2)
+    --row <n>                    Day 15: row to count blocked positions in
+    --max <n>                    Day 15: upper bound of the distress beacon search area
+    --cube-layout <sample|actual>  Day 22: which cube net the input is folded with
+    --set <key>=<value>          Any other setting of the day, like --set rounds_part2=500 for day 11
+    --config <path>              Read settings from this file instead of params.ini in the input directory
+    --format <text|json|markdown>  How to print the answers (default: text)
+    --visualize <output>         Days 14, 17, 23 and 24: record the simulation as ppm:<dir> or text:<dir>
+                                 with one file per frame, or animate it with terminal[:<millis per frame>]
+    --every <n>                  Only record every n-th frame (default: 1)
+    --scale <n>                  Pixels per cell in images (default: 4)
+    --stream                     Read the input line by line in constant memory, for the days that can (only day 1).
+                                 Day 1 also prints how many elves there are with their mean and median calories
+
+Bench options (benchmarks all days if none are given):
+    --runs <n>                   How often every day is solved (default: 5)
+    --sample, --actual, --input  Same as for run
+    --config <path>              Same as for run
+    --save <path>                Store the medians as a baseline for later comparison
+    --baseline <path>            Compare against a previously saved baseline
+    --tolerance <percent>        Allowed slowdown before a phase counts as regression (default: 10)
+
+All solves every day in parallel and prints a summary:
+    --threads <n>                Number of worker threads (default: one per core)
+    --timeout <seconds>          Give up on parts that take longer than this
+
+Batch solves a day for every file in a directory, like the inputs of several people, and prints a table.
+Inputs that fail are flagged without stopping the others. A params.ini in the directory is used as config.
+
+Generate makes up a random input for a day, as big as the actual one unless --size says otherwise.
+The same seed (default: 0) always gives the same input. It is printed unless written to --output.
+
+Guide scores the strategy guide of day 2 under every reading of its second column, as symbols or as outcomes,
+and ranks them. The first --rounds (default: 10) rounds of the best one are broken down.
+
+Rucksacks names the item the compartments of every rucksack of day 3 share, and the badge of every group.
+
+Inputs are looked up in the directory named by AOC_INPUT_DIR, or else in ./input.
+
+Settings are read from an INI file with a [dayNN] section per day, and [dayNN <file name>] sections
+for single inputs. Command line options win over the sections of the input, which win over those of the day:
+    [day01]    top
+    [day02]    symbols (like Rock Paper Scissors), outcome_scores (like 0 3 6), first_column, second_column, outcome_column
+    [day03]    compartments, group_size, priorities (the items from lowest to highest, like a-z A-Z 0-9)
+    [day06]    marker_len
+    [day07]    disk_size, needed_space
+    [day11]    rounds_part1, rounds_part2, relief
+    [day15]    row, max
+    [day16]    minutes_part1, minutes_part2
+    [day17]    rocks_part1, rocks_part2
+    [day22]    cube_layout, face_len, face_locations (like 1,0 2,0 ...), edges_part1, edges_part2 (like 1/0 2/0 3/2 5/1; ...)";
+
+/// The days that can record their simulation.
+const SIMULATIONS: [u8; 4] = [14, 17, 23, 24];
+
+pub(crate) enum Command {
+    Run(RunArgs),
+    Bench(BenchArgs),
+    All(AllArgs),
+    Batch(BatchArgs),
+    Generate(GenerateArgs),
+    Guide(GuideArgs),
+    Rucksacks(RucksackArgs),
+    Help
+}
+
+pub(crate) struct RunArgs {
+    pub(crate) day: u8,
+    pub(crate) part: Option<Part>,
+    pub(crate) input: InputSource,
+    /// Settings given as options, as `(key, value)`.
+    pub(crate) settings: Vec<(String, String)>,
+    pub(crate) config: Option<String>,
+    pub(crate) format: Format,
+    pub(crate) visualize: Option<Output>,
+    pub(crate) every: usize,
+    pub(crate) scale: usize,
+    /// Solves day 1 without reading all of the input into memory.
+    pub(crate) stream: bool
+}
+
+pub(crate) struct GenerateArgs {
+    pub(crate) day: u8,
+    pub(crate) seed: u64,
+    /// What the size counts depends on the day, like elves for day 1 or rows of the map for day 12.
+    pub(crate) size: Option<usize>,
+    pub(crate) output: Option<String>
+}
+
+fn value_of(flag: &str, args: &mut impl Iterator<Item=String>) -> Result<String, Error> {
+    args.next().ok_or(Error::invalid(format!("Missing value for {flag}")))
+}
+
+fn parse_day(value: String) -> Result<u8, Error> {
+    let day = parse_num::<u8>("day", value)?;
+    if !(1..=25).contains(&day) {
+        return Err(Error::invalid(format!("There is no day {day}")))
+    }
+    Ok(day)
+}
+
+fn parse_num<T: std::str::FromStr>(flag: &str, value: String) -> Result<T, Error> {
+    value.parse::<T>().map_err(|_| Error::invalid(format!("Invalid value for {flag}: {value}")))
+}
+
+/// Splits the value of `--set` into key and value.
+fn parse_setting(setting: String) -> Result<(String, String), Error> {
+    let (key, value) = setting.split_once('=').ok_or(Error::invalid(format!("Expected --set <key>=<value>, found {setting}")))?;
+    Ok((key.trim().to_string(), value.trim().to_string()))
+}
+
+/// Removes the verbosity options, which may appear anywhere, and returns the log level they ask for.
+pub(crate) fn take_log_level(args: &mut Vec<String>) -> Result<Level, Error> {
+    let mut level = Level::Error;
+    let mut i = 0;
+    while i < args.len() {
+        let arg = &args[i];
+        if arg.len() > 1 && arg.strip_prefix('-').is_some_and(|vs| vs.chars().all(|c| c == 'v')) {
+            level = Level::from_verbosity(arg.len() - 1);
+            args.remove(i);
+        } else if arg == "--log" {
+            args.remove(i);
+            if i == args.len() { return Err(Error::invalid("Missing value for --log")) }
+            level = args.remove(i).parse()?;
+        } else {
+            i += 1;
+        }
+    }
+    Ok(level)
+}
+
+pub(crate) fn parse_args(mut args: impl Iterator<Item=String>) -> Result<Command, Error> {
+    match args.next().as_deref() {
+        None | Some("help") | Some("--help") | Some("-h") => Ok(Command::Help),
+        Some("run") => {
+            let day = parse_day(args.next().ok_or(Error::invalid("Missing day"))?)?;
+            let mut run_args = RunArgs {
+                day,
+                part: None,
+                input: InputSource::Actual,
+                settings: Vec::new(),
+                config: None,
+                format: Format::Text,
+                visualize: None,
+                every: 1,
+                scale: 4,
+                stream: false
+            };
+            while let Some(flag) = args.next() {
+                match flag.as_str() {
+                    "--part" => run_args.part = Some(match value_of(&flag, &mut args)?.as_str() {
+                        "1" => Ok(Part::One),
+                        "2" => Ok(Part::Two),
+                        other => Err(Error::invalid(format!("There is no part {other}")))
+                    }?),
+                    "--sample" => run_args.input = InputSource::Sample,
+                    "--actual" => run_args.input = InputSource::Actual,
+                    "--input" => run_args.input = InputSource::from_arg(value_of(&flag, &mut args)?),
+                    "--marker-len" | "--row" | "--max" | "--cube-layout" => {
+                        let key = flag.trim_start_matches("--").replace('-', "_");
+                        run_args.settings.push((key, value_of(&flag, &mut args)?))
+                    },
+                    "--set" => run_args.settings.push(parse_setting(value_of(&flag, &mut args)?)?),
+                    "--config" => run_args.config = Some(value_of(&flag, &mut args)?),
+                    "--format" => run_args.format = value_of(&flag, &mut args)?.parse()?,
+                    "--visualize" => {
+                        if !SIMULATIONS.contains(&day) {
+                            return Err(Error::invalid(format!("Day {day} has no simulation to visualize")))
+                        }
+                        run_args.visualize = Some(value_of(&flag, &mut args)?.parse()?)
+                    },
+                    "--every" => run_args.every = parse_num(&flag, value_of(&flag, &mut args)?)?,
+                    "--scale" => run_args.scale = parse_num(&flag, value_of(&flag, &mut args)?)?,
+                    "--stream" => run_args.stream = true,
+                    other => return Err(Error::invalid(format!("Unknown option {other}")))
+                }
+            }
+            Ok(Command::Run(run_args))
+        },
+        Some("bench") => {
+            let mut bench_args = BenchArgs {
+                days: Vec::new(),
+                runs: 5,
+                input: InputSource::Actual,
+                config: None,
+                save: None,
+                baseline: None,
+                tolerance: 10.0
+            };
+            while let Some(flag) = args.next() {
+                match flag.as_str() {
+                    "--runs" => bench_args.runs = parse_num(&flag, value_of(&flag, &mut args)?)?,
+                    "--sample" => bench_args.input = InputSource::Sample,
+                    "--actual" => bench_args.input = InputSource::Actual,
+                    "--input" => bench_args.input = InputSource::from_arg(value_of(&flag, &mut args)?),
+                    "--config" => bench_args.config = Some(value_of(&flag, &mut args)?),
+                    "--save" => bench_args.save = Some(value_of(&flag, &mut args)?),
+                    "--baseline" => bench_args.baseline = Some(value_of(&flag, &mut args)?),
+                    "--tolerance" => bench_args.tolerance = parse_num(&flag, value_of(&flag, &mut args)?)?,
+                    other if other.starts_with("--") => return Err(Error::invalid(format!("Unknown option {other}"))),
+                    _ => bench_args.days.push(parse_day(flag)?)
+                }
+            }
+            if bench_args.days.is_empty() {
+                bench_args.days = (1..=25).collect();
+            }
+            Ok(Command::Bench(bench_args))
+        },
+        Some("all") => {
+            let mut all_args = AllArgs { input: InputSource::Actual, config: None, threads: None, timeout: None, format: Format::Text };
+            while let Some(flag) = args.next() {
+                match flag.as_str() {
+                    "--sample" => all_args.input = InputSource::Sample,
+                    "--actual" => all_args.input = InputSource::Actual,
+                    "--config" => all_args.config = Some(value_of(&flag, &mut args)?),
+                    "--threads" => all_args.threads = Some(parse_num(&flag, value_of(&flag, &mut args)?)?),
+                    "--format" => all_args.format = value_of(&flag, &mut args)?.parse()?,
+                    "--timeout" => all_args.timeout = Some(Duration::from_secs_f64(parse_num(&flag, value_of(&flag, &mut args)?)?)),
+                    other => return Err(Error::invalid(format!("Unknown option {other}")))
+                }
+            }
+            Ok(Command::All(all_args))
+        },
+        Some("batch") => {
+            let day = parse_day(args.next().ok_or(Error::invalid("Missing day"))?)?;
+            let dir = args.next().ok_or(Error::invalid("Missing input directory"))?;
+            let mut batch_args = BatchArgs { day, dir, config: None };
+            while let Some(flag) = args.next() {
+                match flag.as_str() {
+                    "--config" => batch_args.config = Some(value_of(&flag, &mut args)?),
+                    other => return Err(Error::invalid(format!("Unknown option {other}")))
+                }
+            }
+            Ok(Command::Batch(batch_args))
+        },
+        Some("generate") => {
+            let day = parse_day(args.next().ok_or(Error::invalid("Missing day"))?)?;
+            let mut generate_args = GenerateArgs { day, seed: 0, size: None, output: None };
+            while let Some(flag) = args.next() {
+                match flag.as_str() {
+                    "--seed" => generate_args.seed = parse_num(&flag, value_of(&flag, &mut args)?)?,
+                    "--size" => generate_args.size = Some(parse_num(&flag, value_of(&flag, &mut args)?)?),
+                    "--output" => generate_args.output = Some(value_of(&flag, &mut args)?),
+                    other => return Err(Error::invalid(format!("Unknown option {other}")))
+                }
+            }
+            Ok(Command::Generate(generate_args))
+        },
+        Some("guide") => {
+            let mut guide_args = GuideArgs { input: InputSource::Actual, settings: Vec::new(), config: None, rounds: 10 };
+            while let Some(flag) = args.next() {
+                match flag.as_str() {
+                    "--sample" => guide_args.input = InputSource::Sample,
+                    "--actual" => guide_args.input = InputSource::Actual,
+                    "--input" => guide_args.input = InputSource::from_arg(value_of(&flag, &mut args)?),
+                    "--config" => guide_args.config = Some(value_of(&flag, &mut args)?),
+                    "--set" => guide_args.settings.push(parse_setting(value_of(&flag, &mut args)?)?),
+                    "--rounds" => guide_args.rounds = parse_num(&flag, value_of(&flag, &mut args)?)?,
+                    other => return Err(Error::invalid(format!("Unknown option {other}")))
+                }
+            }
+            Ok(Command::Guide(guide_args))
+        },
+        Some("rucksacks") => {
+            let mut rucksack_args = RucksackArgs { input: InputSource::Actual, settings: Vec::new(), config: None };
+            while let Some(flag) = args.next() {
+                match flag.as_str() {
+                    "--sample" => rucksack_args.input = InputSource::Sample,
+                    "--actual" => rucksack_args.input = InputSource::Actual,
+                    "--input" => rucksack_args.input = InputSource::from_arg(value_of(&flag, &mut args)?),
+                    "--config" => rucksack_args.config = Some(value_of(&flag, &mut args)?),
+                    "--set" => rucksack_args.settings.push(parse_setting(value_of(&flag, &mut args)?)?),
+                    other => return Err(Error::invalid(format!("Unknown option {other}")))
+                }
+            }
+            Ok(Command::Rucksacks(rucksack_args))
+        },
+        Some(other) => Err(Error::invalid(format!("Unknown command {other}")))
+    }
+}
+
+impl RunArgs {
+    /// The parts to solve, in order. Defaults to both if none was requested.
+    fn parts(&self) -> Vec<Part> {
+        self.part.map_or(vec![Part::One, Part::Two], |p| vec![p])
+    }
+
+    pub(crate) fn execute(&self) -> Result<(), Error> {
+        let params = Params {
+            overrides: self.settings.clone(),
+            visualize: self.visualize.clone().map(|output| Visualize { output, every: self.every, scale: self.scale }),
+            ..Params::new(self.day, &self.input, &Config::load(self.config.as_deref())?)
+        };
+        if self.stream {
+            if self.format != Format::Text {
+                return Err(Error::invalid("Streamed inputs are only printed as text"))
+            }
+            return self.execute_stream(&params)
+        }
+        if self.format != Format::Text {
+            let results: Vec<_> = self.parts().into_iter().map(|part| solve_part(self.day, part, &self.input, &params)).collect();
+            if self.format == Format::Json { println!("{}", to_json(&results)) } else { print!("{}", to_markdown(&results)) }
+            if results.iter().any(|r| r.answer.is_err()) { std::process::exit(1) }
+            return Ok(())
+        }
+        let solution = solution_for(self.day, &params)?;
+        let mut parsed: Option<(String, Box<dyn Any>)> = None;
+        for part in self.parts() {
+            let path = self.input.path(self.day, part)?;
+            let input = match parsed {
+                Some((ref parsed_path, ref input)) if *parsed_path == path => input,
+                _ => {
+                    let input = solution.parse_any(&self.input.read(self.day, part)?).map_err(|err| err.in_file(&path))?;
+                    &parsed.insert((path, input)).1
+                }
+            };
+            match solution.solve_any(input.as_ref(), part)? {
+                Answer::Image(rows) => println!("Day {} part {part}:\n{}", self.day, rows.join("\n")),
+                answer => println!("Day {} part {part}: {answer}", self.day)
+            }
+        }
+        if let Some((_, input)) = &parsed {
+            for line in solution.report_any(input.as_ref())? {
+                println!("Day {} {line}", self.day);
+            }
+        }
+        Ok(())
+    }
+
+    /// Solves the day while reading the input line by line, so that it never has to be in memory at once.
+    fn execute_stream(&self, params: &Params) -> Result<(), Error> {
+        let solution = solution_for(self.day, params)?;
+        let path = self.input.path(self.day, Part::One)?;
+        let streamed = match self.input {
+            InputSource::Stdin => solution.stream_any(&mut io::stdin().lock()),
+            _ => solution.stream_any(&mut BufReader::new(File::open(&path).map_err(|err| Error::from(err).in_file(&path))?))
+        }.ok_or_else(|| Error::invalid(format!("Day {} cannot be streamed", self.day)))?.map_err(|err| err.in_file(&path))?;
+        for part in self.parts() {
+            println!("Day {} part {part}: {}", self.day, streamed.solve(part)?);
+        }
+        for line in streamed.report() {
+            println!("Day {} {line}", self.day);
+        }
+        Ok(())
+    }
+}
+
+impl GenerateArgs {
+    pub(crate) fn execute(&self) -> Result<(), Error> {
+        let input = generate(self.day, self.seed, self.size)?;
+        match &self.output {
+            Some(path) => std::fs::write(path, input).map_err(|err| Error::from(err).in_file(path))?,
+            None => print!("{input}")
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+use std::path::Path;
+use crate::input::input_dir;
+use crate::utils::{read_input, Error, Field, Pattern};
+
+/// The file in the input directory that is read if no config is given explicitly.
+pub const CONFIG_FILE: &str = "params.ini";
+
+lazy_static! {
+    static ref DAY_SECTION: Pattern = Pattern::new("[day{u8}]");
+    static ref INPUT_SECTION: Pattern = Pattern::new("[day{u8} {}]");
+    static ref SETTING: Pattern = Pattern::new("{id}{_}={}");
+}
+
+/// Where a setting was found, so that invalid values can be pointed at.
+#[derive(Clone, Debug)]
+struct Setting {
+    value: String,
+    line: usize,
+    text: String,
+    offset: usize
+}
+
+/// Parameters of the days, read from an INI file like
+///
+/// ```ini
+/// [day15]
+/// row = 2000000
+///
+/// # Only for the input file named alice.txt
+/// [day15 alice.txt]
+/// row = 10
+/// ```
+///
+/// Lines starting with `#` or `;` are comments.
+#[derive(Clone, Default, Debug)]
+pub struct Config {
+    file: Option<String>,
+    /// Keyed by day and input file name, where the name is `None` for the section applying to every input.
+    sections: HashMap<(u8, Option<String>), HashMap<String, Setting>>
+}
+
+impl Config {
+    pub fn parse(text: &str) -> Result<Config, Error> {
+        let mut sections: HashMap<(u8, Option<String>), HashMap<String, Setting>> = HashMap::new();
+        let mut section = None;
+        for (i, line) in text.lines().enumerate() {
+            let trimmed = line.trim_end();
+            if trimmed.trim_start().is_empty() || trimmed.starts_with('#') || trimmed.starts_with(';') { continue }
+            let result = if trimmed.starts_with('[') {
+                if trimmed.contains(' ') {
+                    INPUT_SECTION.parse::<(u8, String)>(trimmed).map(|(day, input)| (day, Some(input)))
+                } else {
+                    DAY_SECTION.parse::<(u8,)>(trimmed).map(|(day,)| (day, None))
+                }.map(|key| {
+                    sections.entry(key.clone()).or_default();
+                    section = Some(key);
+                })
+            } else {
+                let indent = trimmed.len() - trimmed.trim_start().len();
+                SETTING.parse(trimmed.trim_start()).and_then(|(key, value): (String, Field)| {
+                    let settings = sections.get_mut(section.as_ref().ok_or(Error::parse("Settings have to be in a section like [day15]"))?)
+                        .expect("Sections are created when their header is read");
+                    let value_start = indent + value.offset + value.text.len() - value.text.trim_start().len();
+                    let setting = Setting { value: value.text.trim().to_string(), line: i, text: trimmed.to_string(), offset: value_start };
+                    if settings.insert(key.clone(), setting).is_some() {
+                        return Err(Error::parse_at(format!("{key} is set twice in this section"), 0, key.len()))
+                    }
+                    Ok(())
+                }).map_err(|err| err.shifted(indent))
+            };
+            result.map_err(|err| err.on_line(i, trimmed))?;
+        }
+        Ok(Config { file: None, sections })
+    }
+
+    pub fn read(path: &str) -> Result<Config, Error> {
+        let mut config = Config::parse(&read_input(path)?).map_err(|err| err.in_file(path))?;
+        config.file = Some(path.to_string());
+        Ok(config)
+    }
+
+    /// Reads `path` if given, otherwise [CONFIG_FILE] in the input directory if there is one.
+    pub fn load(path: Option<&str>) -> Result<Config, Error> {
+        match path {
+            Some(path) => Config::read(path),
+            None => {
+                // Without an input directory there is no default config either, which is fine for inputs given by path
+                let Ok(dir) = input_dir() else { return Ok(Config::default()) };
+                let default = format!("{dir}/{CONFIG_FILE}");
+                if Path::new(&default).exists() { Config::read(&default) } else { Ok(Config::default()) }
+            }
+        }
+    }
+
+    /// Looks up a setting of `day`, preferring the section of the input file named `input` over the one of the day.
+    /// Errors of `parse` are reported at the value in the config file.
+    pub fn get<T>(&self, day: u8, input: Option<&str>, key: &str, parse: impl Fn(&str) -> Result<T, Error>) -> Result<Option<T>, Error> {
+        let setting = input.and_then(|input| self.sections.get(&(day, Some(input.to_string()))))
+            .and_then(|section| section.get(key))
+            .or_else(|| self.sections.get(&(day, None)).and_then(|section| section.get(key)));
+        let Some(setting) = setting else { return Ok(None) };
+        parse(&setting.value).map(Some).map_err(|err| {
+            // Values that parse but make no sense are still mistakes in this file
+            let err = match err {
+                Error::InvalidInput(message) => Error::parse(message),
+                err => err
+            };
+            self.at(err.shifted(setting.offset).located(setting.offset, setting.value.len()), setting)
+        })
+    }
+
+    /// Fails if any section of `day` has a setting that is not in `known`, which is most likely a typo.
+    pub fn check_keys(&self, day: u8, known: &[&str]) -> Result<(), Error> {
+        for ((section_day, _), settings) in &self.sections {
+            if *section_day != day { continue }
+            if let Some((key, setting)) = settings.iter().find(|(key, _)| !known.contains(&key.as_str())) {
+                let expected = if known.is_empty() { "none".to_string() } else { known.join(", ") };
+                return Err(self.at(Error::parse_at(format!("Day {day} has no setting {key}, known are: {expected}"), 0, key.len()), setting))
+            }
+        }
+        Ok(())
+    }
+
+    fn at(&self, err: Error, setting: &Setting) -> Error {
+        let err = err.on_line(setting.line, &setting.text);
+        match &self.file {
+            Some(file) => err.in_file(file),
+            None => err
+        }
+    }
+}
@@ -1,45 +1,315 @@
-use std::cmp;
-use std::error::Error;
-use crate::utils;
-
-pub fn run_sample() {
-    run("input/day01_sample.txt");
-}
-
-pub fn run_actual() {
-    run("input/day01.txt");
-}
-
-fn run(path: &str) {
-    if let Ok(lines) = utils::read_lines(path) {
-        let (acc, last) = lines.map(|elem| Ok(elem?.parse::<i32>()?))
-            .fold((Stack::Nil, 0), |(accum, current), elem: Result<i32, Box<dyn Error>>|
-                return if let Ok(num) = elem { (accum, current + num) }
-                else { (Stack::Cons(current, Box::from(accum)), 0) });
-        let all_resources = Stack::Cons(last, Box::from(acc));
-        println!("Max: {}", all_resources.max());
-        let (a, b, c) = all_resources.max3();
-        println!("Max3: {}", a + b + c)
-    } else { println!("Failed to read file") }
-}
-
-enum Stack {
-    Nil,
-    Cons(i32, Box<Stack>)
-}
-
-impl Stack {
-    pub fn max(&self) -> i32 { match self {
-        Stack::Nil => -1,
-        Stack::Cons(head, tail) => cmp::max(*head, tail.max())
-    } }
-    pub fn max3(&self) -> (i32, i32, i32) { match self {
-        Stack::Nil => (-1, -1, -1),
-        Stack::Cons(head, tail) => match tail.max3() {
-            (a, b, _) if a < *head => (*head, a, b),
-            (a, b, _) if b < *head => (a, *head, b),
-            (a, b, c) if c < *head => (a, b, *head),
-            (a, b, c) => (a, b, c)
-        }
-    } }
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt::{Display, Formatter};
+use std::io::BufRead;
+use crate::solution::{Answer, Params, Solution, Streamed};
+use crate::utils::{parse_num, records, Error, Part};
+use crate::generate::Rng;
+
+/// The snacks one elf carries, by their calories.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Elf {
+    /// Where the elf is in the input, counting from 0.
+    pub index: usize,
+    pub items: Vec<u32>,
+    pub total: u64
+}
+
+/// Every elf of the input, in order.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Inventory {
+    elves: Vec<Elf>
+}
+
+impl Inventory {
+    /// Parses blank line separated groups of calories, one group per elf. Anything but a number is an error.
+    pub fn parse(input: &str) -> Result<Inventory, Error> {
+        let elves = records(input).iter().enumerate().map(|(index, record)| {
+            let items = record.parse_lines(parse_num::<u32>)?;
+            let total = items.iter().map(|&calories| calories as u64).sum();
+            Ok(Elf { index, items, total })
+        }).collect::<Result<Vec<Elf>, Error>>()?;
+        Ok(Inventory { elves })
+    }
+
+    pub fn elves(&self) -> &[Elf] { &self.elves }
+
+    /// The `n` elves carrying the most calories, most first. Of elves carrying the same, the earlier one wins.
+    /// Keeps only the best `n` on a heap, which takes O(elves · log n).
+    pub fn top(&self, n: usize) -> Vec<&Elf> {
+        let mut best = Best::new(n);
+        for elf in &self.elves {
+            best.push(elf.index, elf.total);
+        }
+        best.ranked().into_iter().map(|(index, _)| &self.elves[index]).collect()
+    }
+
+    /// The calories of the `n` elves carrying the most, which fails if there are fewer elves.
+    pub fn top_total(&self, n: usize) -> Result<u64, Error> {
+        let top = self.top(n);
+        if top.len() < n {
+            return Err(Error::invalid(format!("There are only {} elves, cannot add up the top {n}", top.len())))
+        }
+        Ok(top.iter().map(|elf| elf.total).sum())
+    }
+}
+
+/// The `n` largest totals pushed so far, on a min-heap so that the smallest of them is the one dropped.
+struct Best {
+    n: usize,
+    heap: BinaryHeap<Reverse<(u64, Reverse<usize>)>>
+}
+
+impl Best {
+    fn new(n: usize) -> Best {
+        Best { n, heap: BinaryHeap::with_capacity(n + 1) }
+    }
+
+    fn push(&mut self, index: usize, total: u64) {
+        if self.n == 0 { return }
+        self.heap.push(Reverse((total, Reverse(index))));
+        if self.heap.len() > self.n { self.heap.pop(); }
+    }
+
+    /// The indices and totals, most first. Of equal totals, the earlier index wins.
+    fn ranked(&self) -> Vec<(usize, u64)> {
+        self.heap.clone().into_sorted_vec().into_iter().map(|Reverse((total, Reverse(index)))| (index, total)).collect()
+    }
+}
+
+/// Estimates the median of a stream in constant memory with the P² algorithm (Jain and Chlamtac), which keeps
+/// five markers at the minimum, the quartiles, the median and the maximum, and moves them along a parabola.
+#[derive(Clone, Debug, Default)]
+struct MedianEstimate {
+    count: usize,
+    heights: [f64; 5],
+    positions: [f64; 5],
+    desired: [f64; 5]
+}
+
+impl MedianEstimate {
+    /// How far the desired positions of the markers move with every value.
+    const INCREMENTS: [f64; 5] = [0.0, 0.25, 0.5, 0.75, 1.0];
+
+    fn push(&mut self, x: f64) {
+        if self.count < 5 {
+            self.heights[self.count] = x;
+            self.count += 1;
+            if self.count == 5 {
+                self.heights.sort_by(f64::total_cmp);
+                self.positions = [1.0, 2.0, 3.0, 4.0, 5.0];
+                self.desired = self.positions;
+            }
+            return
+        }
+        self.count += 1;
+        let (h, p) = (&mut self.heights, &mut self.positions);
+        // The cell of the markers x falls into, widening the outer ones if needed
+        let cell = if x < h[0] {
+            h[0] = x;
+            0
+        } else if x >= h[4] {
+            h[4] = x;
+            3
+        } else {
+            (0..4).find(|&i| x < h[i + 1]).unwrap_or(3)
+        };
+        for position in &mut p[cell + 1..] { *position += 1.0 }
+        for (desired, increment) in self.desired.iter_mut().zip(Self::INCREMENTS) { *desired += increment }
+        for i in 1..4 {
+            let off = self.desired[i] - p[i];
+            if (off >= 1.0 && p[i + 1] - p[i] > 1.0) || (off <= -1.0 && p[i - 1] - p[i] < -1.0) {
+                let d = off.signum();
+                let parabolic = h[i] + d / (p[i + 1] - p[i - 1]) * (
+                    (p[i] - p[i - 1] + d) * (h[i + 1] - h[i]) / (p[i + 1] - p[i]) +
+                    (p[i + 1] - p[i] - d) * (h[i] - h[i - 1]) / (p[i] - p[i - 1]));
+                h[i] = if h[i - 1] < parabolic && parabolic < h[i + 1] {
+                    parabolic
+                } else {
+                    let j = if d > 0.0 { i + 1 } else { i - 1 };
+                    h[i] + d * (h[j] - h[i]) / (p[j] - p[i])
+                };
+                p[i] += d;
+            }
+        }
+    }
+
+    /// Exact up to five values.
+    fn estimate(&self) -> Option<f64> {
+        match self.count {
+            0 => None,
+            count if count < 5 => {
+                let mut values = self.heights[..count].to_vec();
+                values.sort_by(f64::total_cmp);
+                Some((values[(count - 1) / 2] + values[count / 2]) / 2.0)
+            },
+            _ => Some(self.heights[2])
+        }
+    }
+}
+
+/// What the elves read so far carry.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Stats {
+    pub count: usize,
+    pub mean: f64,
+    /// Estimated once there are more than five elves.
+    pub median: f64
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} elves carrying {:.1} calories on average, about {:.0} in the middle", self.count, self.mean, self.median)
+    }
+}
+
+/// Ranks elves while their lines are read, for inventories too big to keep around like [Inventory] does.
+/// Only the top `n` elves and a few numbers for the [Stats] are kept, however many elves there are.
+pub struct Tally {
+    best: Best,
+    /// The calories of the elf whose lines are being read.
+    current: Option<u64>,
+    lines: usize,
+    count: usize,
+    sum: u128,
+    median: MedianEstimate
+}
+
+impl Tally {
+    /// How many elves are read between logging the stats.
+    const LOG_EVERY: usize = 1 << 20;
+
+    pub fn new(n: usize) -> Tally {
+        Tally { best: Best::new(n), current: None, lines: 0, count: 0, sum: 0, median: MedianEstimate::default() }
+    }
+
+    /// Reads every line of `reader`, following the same rules as [Inventory::parse].
+    pub fn read(reader: impl BufRead, n: usize) -> Result<Tally, Error> {
+        let mut tally = Tally::new(n);
+        for line in reader.lines() {
+            let count = tally.count;
+            tally.push_line(&line?)?;
+            if tally.count > count && tally.count.is_multiple_of(Self::LOG_EVERY) {
+                info!("Read {}", tally.stats().expect("Some elves were read"));
+            }
+        }
+        tally.finish_elf();
+        Ok(tally)
+    }
+
+    /// Adds a line with the calories of one snack, or ends the current elf on a blank line.
+    pub fn push_line(&mut self, line: &str) -> Result<(), Error> {
+        let index = self.lines;
+        self.lines += 1;
+        let line = line.trim_end();
+        if line.is_empty() {
+            self.finish_elf();
+        } else {
+            let calories = parse_num::<u32>(line).map_err(|err| err.on_line(index, line))?;
+            *self.current.get_or_insert(0) += calories as u64;
+        }
+        Ok(())
+    }
+
+    /// Ends the elf being read, if any. Reading the next line starts another one.
+    pub fn finish_elf(&mut self) {
+        let Some(total) = self.current.take() else { return };
+        self.best.push(self.count, total);
+        self.count += 1;
+        self.sum += total as u128;
+        self.median.push(total as f64);
+    }
+
+    /// The indices and calories of the top elves among the finished ones, most first.
+    pub fn top(&self) -> Vec<(usize, u64)> {
+        self.best.ranked()
+    }
+
+    /// The calories of the `n` elves carrying the most, like [Inventory::top_total]. Only as many elves as the tally
+    /// was made for are kept, so asking for more fails too.
+    pub fn top_total(&self, n: usize) -> Result<u64, Error> {
+        if n > self.best.n {
+            return Err(Error::invalid(format!("Only the top {} elves were kept, cannot add up the top {n}", self.best.n)))
+        }
+        let top = self.top();
+        if top.len() < n {
+            return Err(Error::invalid(format!("There are only {} elves, cannot add up the top {n}", top.len())))
+        }
+        Ok(top[..n].iter().map(|&(_, total)| total).sum())
+    }
+
+    /// Of the finished elves, if there are any.
+    pub fn stats(&self) -> Option<Stats> {
+        let median = self.median.estimate()?;
+        Some(Stats { count: self.count, mean: self.sum as f64 / self.count as f64, median })
+    }
+}
+
+/// Lists elves with their calories like `elf 4 (24000), elf 3 (11000)`, numbering them from 1 like the puzzle.
+pub fn describe_elves(elves: &[(usize, u64)]) -> String {
+    elves.iter().map(|(index, total)| format!("elf {} ({total})", index + 1)).collect::<Vec<_>>().join(", ")
+}
+
+/// `top` is how many of the elves carrying the most calories are added up in part 2.
+pub struct Day01 {
+    pub top: usize
+}
+
+impl Day01 {
+    pub fn from_params(params: &Params) -> Result<Day01, Error> {
+        Ok(Day01 { top: params.get_positive(1, "top")?.unwrap_or(3) })
+    }
+
+}
+
+/// The elves of a streamed input, ranked by a [Tally] that kept enough of them for both parts.
+pub struct StreamedElves {
+    pub tally: Tally,
+    pub top: usize
+}
+
+impl Streamed for StreamedElves {
+    fn solve(&self, part: Part) -> Result<Answer, Error> {
+        Ok(self.tally.top_total(if part == Part::One { 1 } else { self.top })?.into())
+    }
+
+    fn report(&self) -> Vec<String> {
+        let top = self.tally.top();
+        self.tally.stats().map(|stats| format!("stats: {stats}")).into_iter()
+            .chain([format!("top {}: {}", top.len(), describe_elves(&top))])
+            .collect()
+    }
+}
+
+impl Solution for Day01 {
+    type Input = Inventory;
+
+    fn parse(&self, input: &str) -> Result<Inventory, Error> {
+        Inventory::parse(input)
+    }
+
+    fn part1(&self, inventory: &Inventory) -> Result<Answer, Error> {
+        Ok(inventory.top_total(1)?.into())
+    }
+
+    fn part2(&self, inventory: &Inventory) -> Result<Answer, Error> {
+        Ok(inventory.top_total(self.top)?.into())
+    }
+
+    fn report(&self, inventory: &Inventory) -> Vec<String> {
+        let top: Vec<(usize, u64)> = inventory.top(self.top).iter().map(|elf| (elf.index, elf.total)).collect();
+        vec![format!("top {}: {}", top.len(), describe_elves(&top))]
+    }
+
+    fn stream(&self, reader: &mut dyn BufRead) -> Option<Result<Box<dyn Streamed>, Error>> {
+        Some(Tally::read(reader, self.top.max(1)).map(|tally| Box::new(StreamedElves { tally, top: self.top }) as Box<dyn Streamed>))
+    }
+}
+
+/// Generates `size` elves carrying 1 to 10 snacks each.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size.max(1)).map(|_| {
+        let snacks = rng.range(1..=10);
+        (0..snacks).map(|_| rng.range(1000..=60000).to_string()).collect::<Vec<_>>().join("\n")
+    }).collect::<Vec<_>>().join("\n\n")
 }
\ No newline at end of file
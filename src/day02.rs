@@ -1,30 +1,22 @@
-use std::collections::HashMap;
-use std::error::Error;
 use std::fmt::{Display, Formatter};
-use std::io;
-use std::iter::Map;
+use std::str::FromStr;
+use itertools::Itertools;
 use crate::day02::Outcome::{Win, Draw, Loss};
-use crate::day02::Symbol::{Rock, Paper, Scissors};
-use crate::utils;
-use regex::Regex;
+use crate::solution::{Answer, Params, Solution};
+use crate::utils::{Error, Field, Pattern};
+use crate::generate::Rng;
 
-pub fn run_sample(part2: bool) {
-    run("input/day02_sample.txt", part2);
+lazy_static! {
+    static ref GAME: Pattern = Pattern::new("{char} {char}");
 }
 
-pub fn run_actual(part2: bool) {
-    run("input/day02.txt", part2);
-}
-
-enum Outcome {
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Outcome {
     Win, Draw, Loss
 }
 impl Outcome {
-    fn score(&self) -> i32 { match self {
-        Win => 6,
-        Draw => 3,
-        Loss => 0
-    }}
+    /// In the order the strategy guide encodes them.
+    pub const ALL: [Outcome; 3] = [Loss, Draw, Win];
 }
 impl Display for Outcome {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
@@ -35,96 +27,304 @@ impl Display for Outcome {
         })
     }
 }
-#[derive(Clone, Copy, Eq, Hash)]
-enum Symbol {
-    Rock, Paper, Scissors
+
+/// One of the symbols of a [Game], by its place in the cycle.
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub struct Symbol(pub usize);
+
+/// A hand game with an odd number of symbols in a cycle, where every symbol beats the half of the others right before
+/// it and loses to the half right after it. Rock, Paper, Scissors is the smallest such game, and
+/// Rock, Spock, Paper, Lizard, Scissors the next one.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Game {
+    names: Vec<String>,
+    /// What a loss, a draw and a win score, on top of the symbol played, which scores its place in the cycle counting from 1.
+    pub outcome_scores: [i32; 3]
+}
+
+impl Game {
+    pub fn new(names: Vec<String>) -> Result<Game, Error> {
+        if names.len() < 3 || names.len().is_multiple_of(2) {
+            return Err(Error::invalid(format!("A cyclic game needs an odd number of at least 3 symbols, not {}", names.len())))
+        }
+        if let Some((_, name)) = names.iter().enumerate().find(|(i, name)| names[..*i].contains(name)) {
+            return Err(Error::invalid(format!("{name} is in the game twice")))
+        }
+        Ok(Game { names, outcome_scores: [0, 3, 6] })
+    }
+
+    pub fn rock_paper_scissors() -> Game {
+        Game::new(vec!["Rock".to_string(), "Paper".to_string(), "Scissors".to_string()]).expect("Three symbols make a game")
+    }
+
+    /// Parses the scores of a loss, a draw and a win, like `0 3 6`.
+    pub fn parse_outcome_scores(s: &str) -> Result<[i32; 3], Error> {
+        let scores = s.split_whitespace().map(|score| score.parse::<i32>()).collect::<Result<Vec<i32>, _>>()?;
+        scores.try_into().map_err(|_| Error::invalid(format!("Expected the scores of a loss, a draw and a win, found {s}")))
+    }
+
+    pub fn size(&self) -> usize { self.names.len() }
+
+    pub fn symbols(&self) -> impl Iterator<Item=Symbol> { (0..self.size()).map(Symbol) }
+
+    pub fn name(&self, symbol: Symbol) -> &str { &self.names[symbol.0] }
+
+    /// How a round ends for whoever plays `ours` against `theirs`.
+    pub fn play(&self, theirs: Symbol, ours: Symbol) -> Outcome {
+        let n = self.size();
+        match (ours.0 + n - theirs.0) % n {
+            0 => Draw,
+            step if step <= n / 2 => Win,
+            _ => Loss
+        }
+    }
+
+    /// What to play against `theirs` for `outcome`. With more than three symbols several would do,
+    /// of which the closest to `theirs` in the cycle is picked.
+    pub fn inverse_play(&self, theirs: Symbol, outcome: Outcome) -> Symbol {
+        let n = self.size();
+        Symbol(match outcome {
+            Draw => theirs.0,
+            Win => (theirs.0 + 1) % n,
+            Loss => (theirs.0 + n - 1) % n
+        })
+    }
+
+    pub fn outcome_score(&self, outcome: Outcome) -> i32 {
+        self.outcome_scores[Outcome::ALL.iter().position(|&o| o == outcome).expect("Every outcome is in ALL")]
+    }
+
+    /// What a round scores for whoever plays `ours`.
+    pub fn score(&self, theirs: Symbol, ours: Symbol) -> i32 {
+        ours.0 as i32 + 1 + self.outcome_score(self.play(theirs, ours))
+    }
 }
-impl PartialEq for Symbol {
-    fn eq(&self, other: &Self) -> bool {
-        self == other
+
+impl FromStr for Game {
+    type Err = Error;
+    /// The symbols in the order of the cycle, separated by whitespace.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Game::new(s.split_whitespace().map(str::to_string).collect())
     }
 }
-impl Display for Symbol {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", match self {
-            Rock => "Rock",
-            Paper => "Paper",
-            Scissors => "Scissors"
+
+/// Which characters of the strategy guide stand for what.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Encoding {
+    /// Their symbols, in the order of the cycle.
+    pub first: Vec<char>,
+    /// Our symbols, as part 1 reads the second column.
+    pub second: Vec<char>,
+    /// A loss, a draw and a win, as part 2 reads the second column.
+    pub outcomes: Vec<char>
+}
+
+impl Encoding {
+    /// Takes the characters of every column in order, ignoring whitespace.
+    pub fn new(game: &Game, first: &str, second: &str, outcomes: &str) -> Result<Encoding, Error> {
+        let column = |name: &str, chars: &str, expected: usize| {
+            let chars: Vec<char> = chars.chars().filter(|c| !c.is_whitespace()).collect();
+            if chars.len() != expected {
+                return Err(Error::invalid(format!("The {name} column needs {expected} characters, found {}", chars.len())))
+            }
+            if let Some((_, c)) = chars.iter().enumerate().find(|(i, c)| chars[..*i].contains(c)) {
+                return Err(Error::invalid(format!("{c} stands for two things in the {name} column")))
+            }
+            Ok(chars)
+        };
+        Ok(Encoding {
+            first: column("first", first, game.size())?,
+            second: column("second", second, game.size())?,
+            outcomes: column("outcome", outcomes, 3)?
         })
     }
+
+    /// The letters of the puzzle: the first column counts up from A, the second one ends at Z and encodes the outcomes as X, Y and Z.
+    pub fn letters(game: &Game) -> Result<Encoding, Error> {
+        let n = game.size() as u32;
+        let letters = |from: u32| (from..from + n).filter_map(char::from_u32).collect::<String>();
+        Encoding::new(game, &letters('A' as u32), &letters(('Z' as u32 + 1).saturating_sub(n)), "XYZ")
+    }
+
+    pub fn theirs(&self, c: char) -> Option<Symbol> {
+        self.first.iter().position(|&first| first == c).map(Symbol)
+    }
+    pub fn ours(&self, c: char) -> Option<Symbol> {
+        self.second.iter().position(|&second| second == c).map(Symbol)
+    }
+    pub fn outcome(&self, c: char) -> Option<Outcome> {
+        self.outcomes.iter().position(|&outcome| outcome == c).map(|i| Outcome::ALL[i])
+    }
+}
+
+/// One round of a guide, as some [Interpretation] plays it.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Round {
+    pub theirs: Symbol,
+    pub ours: Symbol,
+    pub outcome: Outcome,
+    pub score: i32
 }
-impl Symbol {
-    fn score(&self) -> i32 { match self {
-        Rock => 1,
-        Paper => 2,
-        Scissors => 3
-    }}
-    fn from(score: i32) -> Option<Symbol> { match (score + 3) % 3 {
-        0 => Some(Scissors),
-        1 => Some(Rock),
-        2 => Some(Paper),
-        _ => None
-    }}
-}
-
-fn play(a: &Symbol, b: &Symbol) -> Result<Outcome, (Symbol, Symbol)> {
-    match (a.score() - b.score() + 3) % 3 {
-        0 => Ok(Draw),
-        1 => Ok(Loss),
-        2 => Ok(Win),
-        _ => Err((*a, *b))
-    }
-}
-
-fn inverse_play(a: &Symbol, outcome: &Outcome) -> Option<Symbol> {
-    Symbol::from((a.score() - match outcome {
-        Draw => 0,
-        Loss => 1,
-        Win => 2
-    }))
-}
-
-fn score(a: &Symbol, b: &Symbol) -> Result<i32, (Symbol, Symbol)> {
-    Ok(b.score() + play(a, b)?.score())
-}
-
-fn translate_first_column(c: &char) -> Option<Symbol> { match c {
-    'A' => Some(Rock),
-    'B' => Some(Paper),
-    'C' => Some(Scissors),
-    _ => None
-}}
-
-fn translate_second_column_p1(c: &char) -> Option<Symbol> { match c {
-    'X' => Some(Rock),
-    'Y' => Some(Paper),
-    'Z' => Some(Scissors),
-    _ => None
-}}
-
-fn translate_second_column_p2(c: &char) -> Option<Outcome> { match c {
-    'X' => Some(Loss),
-    'Y' => Some(Draw),
-    'Z' => Some(Win),
-    _ => None
-}}
-
-fn run(path: &str, part2: bool) -> Option<()> {
-    let lines = utils::read_lines(path);
-    let games: Vec<(Symbol, Symbol)> = lines.ok()?.map(|str_r| {
-        let re = Regex::new(r"(\w) (\w)").unwrap();
-        let str = str_r.ok()?;
-        let captures = re.captures(str.as_str())?;
-        let theirs = translate_first_column(&captures[1].chars().nth(0)?)?;
-        let second_char = &captures[2].chars().nth(0)?;
-        let ours = if part2 {
-            inverse_play(&theirs, &translate_second_column_p2(second_char)?)?
-        } else {
-            translate_second_column_p1(second_char)?
+
+impl Round {
+    pub fn describe(&self, game: &Game) -> String {
+        format!("{} against {}: {} for {}", game.name(self.ours), game.name(self.theirs), self.outcome, self.score)
+    }
+}
+
+/// What the second column of a guide might mean: our symbol as part 1 assumes, or the outcome as part 2 does.
+/// Different characters always mean different things.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Interpretation {
+    Symbols(Vec<(char, Symbol)>),
+    Outcomes(Vec<(char, Outcome)>)
+}
+
+impl Interpretation {
+    /// Every way to read the characters `chars`, first as symbols and then as outcomes.
+    pub fn all(game: &Game, chars: &[char]) -> Vec<Interpretation> {
+        let symbols = game.symbols().permutations(chars.len())
+            .map(|symbols| Interpretation::Symbols(chars.iter().copied().zip(symbols).collect()));
+        let outcomes = Outcome::ALL.into_iter().permutations(chars.len())
+            .map(|outcomes| Interpretation::Outcomes(chars.iter().copied().zip(outcomes).collect()));
+        symbols.chain(outcomes).collect()
+    }
+
+    /// What we play against `theirs` for the character `c`, if it is interpreted at all.
+    pub fn ours(&self, game: &Game, theirs: Symbol, c: char) -> Option<Symbol> {
+        match self {
+            Interpretation::Symbols(symbols) => symbols.iter().find(|(k, _)| *k == c).map(|&(_, symbol)| symbol),
+            Interpretation::Outcomes(outcomes) => outcomes.iter().find(|(k, _)| *k == c).map(|&(_, outcome)| game.inverse_play(theirs, outcome))
+        }
+    }
+
+    /// Plays every round of `guide`, unless it has characters this does not interpret.
+    pub fn play(&self, game: &Game, guide: &[(Symbol, char)]) -> Option<Vec<Round>> {
+        guide.iter().map(|&(theirs, c)| {
+            let ours = self.ours(game, theirs, c)?;
+            Some(Round { theirs, ours, outcome: game.play(theirs, ours), score: game.score(theirs, ours) })
+        }).collect()
+    }
+
+    /// Like `X=Rock, Y=Paper, Z=Scissors`.
+    pub fn describe(&self, game: &Game) -> String {
+        match self {
+            Interpretation::Symbols(symbols) => symbols.iter().map(|&(c, symbol)| format!("{c}={}", game.name(symbol))).join(", "),
+            Interpretation::Outcomes(outcomes) => outcomes.iter().map(|(c, outcome)| format!("{c}={outcome}")).join(", ")
+        }
+    }
+}
+
+/// Scores `guide` under every interpretation of the characters in its second column, best first.
+/// Interpretations scoring the same stay in the order of [Interpretation::all].
+pub fn analyze(game: &Game, guide: &[(Symbol, char)]) -> Vec<(Interpretation, i32)> {
+    let chars: Vec<char> = guide.iter().map(|&(_, c)| c).sorted().dedup().collect();
+    let mut ranking: Vec<(Interpretation, i32)> = Interpretation::all(game, &chars).into_iter().filter_map(|interpretation| {
+        let total = interpretation.play(game, guide)?.iter().map(|round| round.score).sum();
+        Some((interpretation, total))
+    }).collect();
+    ranking.sort_by_key(|&(_, total)| -total);
+    ranking
+}
+
+/// Lists characters like `X, Y or Z`.
+fn one_of(chars: &[char]) -> String {
+    match chars.split_last() {
+        Some((last, [])) => last.to_string(),
+        Some((last, rest)) => format!("{} or {last}", rest.iter().map(char::to_string).collect::<Vec<_>>().join(", ")),
+        None => String::new()
+    }
+}
+
+/// The rounds of a guide with the second column left uninterpreted, remembering where each round is in the input.
+pub struct Guide {
+    pub rounds: Vec<(Symbol, char)>,
+    /// The index and text of the line of each round, and the offset of its second column.
+    lines: Vec<(usize, String, usize)>
+}
+
+impl Guide {
+    /// The error for a round whose second column `expected` does not contain, pointing at the column in the line.
+    fn unexpected_second(&self, round: usize, expected: &[char]) -> Error {
+        let (i, line, offset) = &self.lines[round];
+        let second = self.rounds[round].1;
+        Error::parse_at(format!("Expected {}, found {second}", one_of(expected)), *offset, second.len_utf8()).on_line(*i, line)
+    }
+}
+
+fn total_score(day: &Day02, guide: &Guide, ours_for: impl Fn(Symbol, char) -> Option<Symbol>, expected: &[char]) -> Result<Answer, Error> {
+    let mut total = 0;
+    for (i, &(theirs, second_char)) in guide.rounds.iter().enumerate() {
+        let ours = ours_for(theirs, second_char).ok_or_else(|| guide.unexpected_second(i, expected))?;
+        total += day.game.score(theirs, ours);
+    }
+    Ok(total.into())
+}
+
+pub struct Day02 {
+    pub game: Game,
+    pub encoding: Encoding
+}
+
+impl Day02 {
+    /// The game and encoding the settings ask for, or else rock-paper-scissors with the letters of the puzzle.
+    pub fn from_params(params: &Params) -> Result<Day02, Error> {
+        let mut game = params.get(2, "symbols")?.unwrap_or_else(Game::rock_paper_scissors);
+        if let Some(scores) = params.get_with(2, "outcome_scores", Game::parse_outcome_scores)? { game.outcome_scores = scores }
+        let letters = Encoding::letters(&game)?;
+        let column = |key: &str, default: &[char]| -> Result<String, Error> {
+            Ok(params.get_with(2, key, |value| Ok(value.to_string()))?.unwrap_or_else(|| default.iter().collect()))
         };
-        Some((theirs, ours))
-    }).collect::<Option<Vec<(Symbol, Symbol)>>>()?;
-    let scores: Vec<i32> = games.iter().map(|(theirs, ours)| score(theirs, ours).ok()).collect::<Option<Vec<i32>>>()?;
-    let res: i32 = scores.iter().sum();
-    Some(println!("{}", res))
+        let encoding = Encoding::new(&game, &column("first_column", &letters.first)?, &column("second_column", &letters.second)?,
+                                     &column("outcome_column", &letters.outcomes)?)?;
+        Ok(Day02 { game, encoding })
+    }
+
+    /// The rounds of a guide with the second column left uninterpreted, which [analyze] can make sense of.
+    pub fn parse_guide(&self, input: &str) -> Result<Guide, Error> {
+        let (rounds, lines) = input.lines().enumerate().map(|(i, line)| {
+            let (round, offset) = self.parse_round(line).map_err(|err| err.on_line(i, line))?;
+            Ok((round, (i, line.to_string(), offset)))
+        }).collect::<Result<(Vec<_>, Vec<_>), Error>>()?;
+        Ok(Guide { rounds, lines })
+    }
+
+    /// One round, and the offset of its second column in `line`.
+    fn parse_round(&self, line: &str) -> Result<((Symbol, char), usize), Error> {
+        let (first, second): (Field, Field) = GAME.parse(line)?;
+        let (first_char, second_char): (char, char) = (first.parse()?, second.parse()?);
+        let theirs = self.encoding.theirs(first_char)
+            .ok_or(Error::parse_at(format!("Expected {}, found {first_char}", one_of(&self.encoding.first)), first.offset, first_char.len_utf8()))?;
+        Ok(((theirs, second_char), second.offset))
+    }
+}
+
+impl Solution for Day02 {
+    type Input = Guide;
+
+    /// Rejects second columns that neither part can read. Those only one part reads fail that part, pointing at the round.
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        let guide = self.parse_guide(input)?;
+        for (i, &(_, second)) in guide.rounds.iter().enumerate() {
+            if self.encoding.ours(second).is_none() && self.encoding.outcome(second).is_none() {
+                let expected: Vec<char> = self.encoding.second.iter().chain(&self.encoding.outcomes).copied().unique().collect();
+                return Err(guide.unexpected_second(i, &expected))
+            }
+        }
+        Ok(guide)
+    }
+
+    fn part1(&self, games: &Self::Input) -> Result<Answer, Error> {
+        total_score(self, games, |_, second_char| self.encoding.ours(second_char), &self.encoding.second)
+    }
+
+    fn part2(&self, games: &Self::Input) -> Result<Answer, Error> {
+        total_score(self, games, |theirs, second_char| Some(self.game.inverse_play(theirs, self.encoding.outcome(second_char)?)),
+                    &self.encoding.outcomes)
+    }
+}
+
+/// Generates `size` rounds with random columns.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size.max(1)).map(|_| format!("{} {}", rng.pick(&['A', 'B', 'C']), rng.pick(&['X', 'Y', 'Z']))).collect::<Vec<_>>().join("\n")
 }
\ No newline at end of file
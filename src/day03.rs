@@ -1,57 +1,246 @@
-use std::error::Error;
-use std::fmt::Display;
-use crate::utils;
-use crate::utils::ErrorMsg;
+use std::collections::HashMap;
+use std::str::FromStr;
+use crate::solution::{Answer, Params, Solution};
+use crate::utils::{parse_lines, Error};
+use crate::generate::Rng;
 
-pub fn run_sample() {
-    ErrorMsg::print(run("input/day03_sample.txt"));
+/// The items there are, in the order of their priority, which counts from 1.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Priorities {
+    items: Vec<char>,
+    indices: HashMap<char, usize>
 }
 
-pub fn run_actual() {
-    ErrorMsg::print(run("input/day03.txt"));
+impl Priorities {
+    pub fn new(items: Vec<char>) -> Result<Priorities, Error> {
+        let mut indices = HashMap::new();
+        for (i, &item) in items.iter().enumerate() {
+            if indices.insert(item, i).is_some() {
+                return Err(Error::invalid(format!("{item} has two priorities")))
+            }
+        }
+        if items.is_empty() {
+            return Err(Error::invalid("There are no items"))
+        }
+        Ok(Priorities { items, indices })
+    }
+
+    /// a to z are worth 1 to 26, A to Z 27 to 52.
+    pub fn letters() -> Priorities {
+        "a-zA-Z".parse().expect("Letters are distinct")
+    }
+
+    pub fn len(&self) -> usize { self.items.len() }
+    pub fn is_empty(&self) -> bool { self.items.is_empty() }
+
+    /// The place of `item` in the table, counting from 0 like the bits of an [ItemSet].
+    pub fn index(&self, item: char) -> Option<usize> { self.indices.get(&item).copied() }
+
+    pub fn item(&self, index: usize) -> char { self.items[index] }
+
+    pub fn priority(&self, index: usize) -> u64 { index as u64 + 1 }
+
+    /// The items of `set`, from the lowest priority up.
+    pub fn describe(&self, set: &ItemSet) -> String {
+        set.iter().map(|index| self.items[index]).collect()
+    }
+}
+
+impl FromStr for Priorities {
+    type Err = Error;
+    /// The items in order, where `a-z` stands for every character from a to z. Ranges do not reach across whitespace,
+    /// so `+ -` or a `-` at either end is just a dash.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut items = Vec::new();
+        for word in s.split_whitespace() {
+            let mut chars: &[char] = &word.chars().collect::<Vec<_>>();
+            while let Some(&first) = chars.first() {
+                if let [from, '-', to, ..] = *chars {
+                    if from > to { return Err(Error::invalid(format!("{from}-{to} is an empty range"))) }
+                    items.extend(from..=to);
+                    chars = &chars[3..];
+                } else {
+                    items.push(first);
+                    chars = &chars[1..];
+                }
+            }
+        }
+        Priorities::new(items)
+    }
+}
+
+/// Items by their index in the [Priorities], as wide as the table needs.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct ItemSet {
+    words: Vec<u64>
 }
 
+impl ItemSet {
+    pub fn insert(&mut self, index: usize) {
+        if self.words.len() <= index / 64 { self.words.resize(index / 64 + 1, 0) }
+        self.words[index / 64] |= 1 << (index % 64);
+    }
+
+    pub fn contains(&self, index: usize) -> bool {
+        self.words.get(index / 64).is_some_and(|word| word & (1 << (index % 64)) != 0)
+    }
+
+    pub fn intersection(&self, other: &ItemSet) -> ItemSet {
+        ItemSet { words: self.words.iter().zip(&other.words).map(|(a, b)| a & b).collect() }
+    }
+
+    pub fn union(&self, other: &ItemSet) -> ItemSet {
+        let (longer, shorter) = if self.words.len() >= other.words.len() { (self, other) } else { (other, self) };
+        let mut words = longer.words.clone();
+        for (word, other) in words.iter_mut().zip(&shorter.words) { *word |= other }
+        ItemSet { words }
+    }
 
-fn priority_from(c: char) -> Result<u32, char> {
-    match c {
-        'A'..='Z' => Ok(c as u32 - 'A' as u32 + 27),
-        'a'..='z' => Ok(c as u32 - 'a' as u32 + 1),
-        _ => Err(c)
+    /// The indices, in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item=usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(w, &word)| {
+            (0..64).filter(move |bit| word & (1 << bit) != 0).map(move |bit| w * 64 + bit)
+        })
     }
+
+    pub fn is_empty(&self) -> bool { self.words.iter().all(|&word| word == 0) }
 }
 
-impl From<char> for ErrorMsg {
-    fn from(err: char) -> Self {
-        ErrorMsg { wrapped: format!("Invalid char: {}", err.to_string()) }
+/// The items in every one of the sets, if there are any sets.
+fn common<'a>(mut sets: impl Iterator<Item=&'a ItemSet>) -> ItemSet {
+    let first = sets.next().cloned().unwrap_or_default();
+    sets.fold(first, |common, set| common.intersection(set))
+}
+
+/// The items of a rucksack, split into its equally big compartments.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Rucksack {
+    pub compartments: Vec<ItemSet>
+}
+
+impl Rucksack {
+    /// The items in every compartment.
+    pub fn shared(&self) -> ItemSet {
+        common(self.compartments.iter())
+    }
+
+    pub fn items(&self) -> ItemSet {
+        self.compartments.iter().fold(ItemSet::default(), |items, compartment| items.union(compartment))
     }
 }
 
-fn run(path: &str) -> Result<(), ErrorMsg> {
-    let lines = utils::read_lines(path)?;
-    let mut priority_sum: u32 = 0;
-    let mut badge_priority_sum: u32 = 0;
-    let mut badge_index = 0;
-    let mut badge_priority: u64 = !0;
-    for line_r in lines {
-        let line = line_r?;
-        let (left_half, right_half) = line.split_at(line.len() / 2);
-        let mut left_acc: u64 = 0;
-        for c in left_half.chars() {
-            left_acc |= 1 << priority_from(c)?;
+/// The items in every rucksack of each group of `group_size`, which have to add up.
+pub fn badges(rucksacks: &[Rucksack], group_size: usize) -> Result<Vec<ItemSet>, Error> {
+    if group_size == 0 || !rucksacks.len().is_multiple_of(group_size) {
+        return Err(Error::invalid(format!("{} rucksacks cannot be split into groups of {group_size}", rucksacks.len())))
+    }
+    Ok(rucksacks.chunks(group_size).map(|group| {
+        let items: Vec<ItemSet> = group.iter().map(Rucksack::items).collect();
+        common(items.iter())
+    }).collect())
+}
+
+/// `compartments` is how many equal parts every rucksack is split into, and `group_size` how many elves share a badge.
+pub struct Day03 {
+    pub compartments: usize,
+    pub group_size: usize,
+    pub priorities: Priorities
+}
+
+impl Day03 {
+    pub fn from_params(params: &Params) -> Result<Day03, Error> {
+        Ok(Day03 {
+            compartments: params.get_positive(3, "compartments")?.unwrap_or(2),
+            group_size: params.get_positive(3, "group_size")?.unwrap_or(3),
+            priorities: params.get(3, "priorities")?.unwrap_or_else(Priorities::letters)
+        })
+    }
+
+    /// The item with the lowest priority in every set, which is the one that counts. Every set needs one.
+    fn counted_items(what: &str, sets: &[ItemSet]) -> Result<Vec<usize>, Error> {
+        sets.iter().enumerate().map(|(i, set)| set.iter().next().ok_or(Error::invalid(format!("{what} {} has no item in common", i + 1))))
+            .collect()
+    }
+
+    fn total_priority(&self, what: &str, sets: &[ItemSet]) -> Result<Answer, Error> {
+        Ok(Day03::counted_items(what, sets)?.into_iter().map(|index| self.priorities.priority(index)).sum::<u64>().into())
+    }
+
+    /// Names the items the compartments of every rucksack share, like `rucksack 1: p (priority 16)`, followed by
+    /// the badge of every group, like `group 1: badge r (priority 18)`. Of several shared items, the first counts.
+    pub fn describe(&self, rucksacks: &[Rucksack]) -> Result<Vec<String>, Error> {
+        let shared: Vec<ItemSet> = rucksacks.iter().map(Rucksack::shared).collect();
+        let badges = badges(rucksacks, self.group_size)?;
+        let mut lines = Vec::new();
+        for (i, (set, index)) in shared.iter().zip(Day03::counted_items("Rucksack", &shared)?).enumerate() {
+            lines.push(format!("rucksack {}: {} (priority {})", i + 1, self.priorities.describe(set), self.priorities.priority(index)));
         }
-        let mut right_acc: u64 = 0;
-        for c in right_half.chars() {
-            right_acc |= 1 << priority_from(c)?;
+        for (i, (set, index)) in badges.iter().zip(Day03::counted_items("Group", &badges)?).enumerate() {
+            lines.push(format!("group {}: badge {} (priority {})", i + 1, self.priorities.describe(set), self.priorities.priority(index)));
         }
-        let shared = left_acc & right_acc;
-        priority_sum += shared.trailing_zeros();
+        Ok(lines)
+    }
+}
 
-        badge_priority &= (left_acc | right_acc);
-        if badge_index == 2 {
-            badge_index = 0;
-            badge_priority_sum += badge_priority.trailing_zeros();
-            badge_priority = !0;
-        } else { badge_index += 1; }
+impl Solution for Day03 {
+    type Input = Vec<Rucksack>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        if self.compartments == 0 {
+            return Err(Error::invalid("Rucksacks need at least one compartment"))
+        }
+        parse_lines(input, |line| {
+            let len = line.chars().count();
+            if !len.is_multiple_of(self.compartments) {
+                return Err(Error::parse_at(format!("{len} items cannot be split into {} compartments", self.compartments), 0, line.len()))
+            }
+            let mut compartments = vec![ItemSet::default(); self.compartments];
+            for (n, (i, c)) in line.char_indices().enumerate() {
+                let index = self.priorities.index(c).ok_or(Error::parse_at(format!("Invalid item {c}"), i, c.len_utf8()))?;
+                compartments[n * self.compartments / len].insert(index);
+            }
+            Ok(Rucksack { compartments })
+        })
+    }
+
+    fn part1(&self, rucksacks: &Self::Input) -> Result<Answer, Error> {
+        self.total_priority("Rucksack", &rucksacks.iter().map(Rucksack::shared).collect::<Vec<_>>())
+    }
+
+    fn part2(&self, rucksacks: &Self::Input) -> Result<Answer, Error> {
+        self.total_priority("Group", &badges(rucksacks, self.group_size)?)
+    }
+}
+
+const ITEMS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// Generates `size` rucksacks, rounded up to whole groups of three. The compartments of every rucksack share
+/// exactly one item, and the rucksacks of a group exactly one badge.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut rucksacks = Vec::new();
+    for _ in 0..size.div_ceil(3).max(1) {
+        let badge = *rng.pick(ITEMS);
+        let mut others: Vec<u8> = ITEMS.iter().copied().filter(|&item| item != badge).collect();
+        rng.shuffle(&mut others);
+        for elf in 0..3 {
+            // Every other item is missing from one of the three rucksacks, so that only the badge is in all of them
+            let allowed: Vec<u8> = others.iter().enumerate().filter(|(i, _)| i % 3 != elf).map(|(_, &item)| item).collect();
+            let (left_items, right_items) = allowed.split_at(allowed.len() / 2);
+            let len = rng.range(8..=16) as usize;
+            let shared = if rng.chance(20) { badge } else { *rng.pick(&allowed) };
+            let mut left = vec![shared];
+            if shared != badge { left.push(badge) }
+            let mut right = vec![shared];
+            let fill = |rng: &mut Rng, compartment: &mut Vec<u8>, items: &[u8]| {
+                let items: Vec<u8> = items.iter().copied().filter(|&item| item != shared).collect();
+                while compartment.len() < len { compartment.push(*rng.pick(&items)) }
+                rng.shuffle(compartment);
+            };
+            fill(rng, &mut left, left_items);
+            fill(rng, &mut right, right_items);
+            if rng.chance(50) { std::mem::swap(&mut left, &mut right) }
+            rucksacks.push(String::from_utf8(left.into_iter().chain(right).collect()).expect("Items are ASCII letters"));
+        }
     }
-    Ok(println!("Sum is {}, badge sum is {}", priority_sum, badge_priority_sum))
+    rucksacks.join("\n")
 }
\ No newline at end of file
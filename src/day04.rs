@@ -1,31 +1,42 @@
-use regex::Regex;
-use crate::utils;
-use crate::utils::ErrorMsg;
+use crate::solution::{Answer, Solution};
+use crate::utils::{parse_lines, Error, Pattern};
+use crate::generate::Rng;
 
-pub fn run_sample() {
-    ErrorMsg::print(run("input/day04_sample.txt"));
+lazy_static! {
+    static ref PAIR: Pattern = Pattern::new("{u32}-{u32},{u32}-{u32}");
 }
 
-pub fn run_actual() {
-    ErrorMsg::print(run("input/day04.txt"));
-}
+pub struct Day04;
+
+impl Solution for Day04 {
+    type Input = Vec<((u32, u32), (u32, u32))>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        parse_lines(input, |line| {
+            let (left_from, left_to, right_from, right_to) = PAIR.parse(line)?;
+            Ok(((left_from, left_to), (right_from, right_to)))
+        })
+    }
 
-fn run(path: &str) -> Result<(), ErrorMsg> {
-    let lines = utils::read_lines(path)?;
-    let mut num_contained = 0;
-    let mut num_overlaps = 0;
-    let re = Regex::new(r"(\d+)-(\d+),(\d+)-(\d+)")?;
-    for line_r in lines {
-        let line = line_r?;
-        let captures = re.captures(line.as_str()).ok_or(ErrorMsg{wrapped: "Regex failed".to_string()})?;
-        let left = (captures[1].parse::<u32>()?, captures[2].parse::<u32>()?);
-        let right = (captures[3].parse::<u32>()?, captures[4].parse::<u32>()?);
-        if (left.0 <= right.0 && left.1 >= right.1) || (left.0 >= right.0 && left.1 <= right.1) {
-            num_contained += 1;
-        }
-        if !(left.1 < right.0 || left.0 > right.1) {
-            num_overlaps += 1;
-        }
+    fn part1(&self, pairs: &Self::Input) -> Result<Answer, Error> {
+        Ok(pairs.iter().filter(|(left, right)|
+            (left.0 <= right.0 && left.1 >= right.1) || (left.0 >= right.0 && left.1 <= right.1)
+        ).count().into())
     }
-    Ok(println!("{} pairs contained each other, {} pairs overlapped", num_contained, num_overlaps))
+
+    fn part2(&self, pairs: &Self::Input) -> Result<Answer, Error> {
+        Ok(pairs.iter().filter(|(left, right)| !(left.1 < right.0 || left.0 > right.1)).count().into())
+    }
+}
+
+/// Generates `size` pairs of sections between 1 and 99.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let assignment = |rng: &mut Rng| {
+        let start = rng.range(1..=99);
+        (start, rng.range(start..=99))
+    };
+    (0..size.max(1)).map(|_| {
+        let ((a, b), (c, d)) = (assignment(rng), assignment(rng));
+        format!("{a}-{b},{c}-{d}")
+    }).collect::<Vec<_>>().join("\n")
 }
\ No newline at end of file
@@ -1,60 +1,111 @@
-use regex::Regex;
-use crate::utils;
-use crate::utils::ErrorMsg;
+use crate::solution::{Answer, Solution};
+use crate::utils::{Error, Field, Pattern};
+use crate::generate::Rng;
 
-pub fn run_sample() {
-    ErrorMsg::print(run("input/day05_sample.txt"));
+lazy_static! {
+    static ref INSTRUCTION: Pattern = Pattern::new("move {u16} from {usize} to {usize}");
 }
 
-pub fn run_actual() {
-    ErrorMsg::print(run("input/day05.txt"));
-}
-
-struct Instruction {
+pub struct Instruction {
     amount: u16,
     from: usize,
     to: usize
 }
 
-fn run(path: &str) -> Result<(), ErrorMsg> {
-    let mut lines = utils::read_lines(path)?;
-    let mut initial_state: Vec<Vec<char>> = Vec::new();
-    while let Some(stack_line_r) = lines.next() {
-        let stack_line = stack_line_r?;
-        if stack_line == "" {break;}
-        for i in 0..((stack_line.len() + 1) / 4) {
-            if initial_state.len() <= i { initial_state.push(Vec::new()) }
-            let char = stack_line.chars().nth(i * 4 + 1).ok_or(ErrorMsg{wrapped: "Instruction ended unexpectedly".to_string()})?;
-            if char != ' ' { initial_state[i].insert(0, char) }
+pub struct Day05;
+
+type Stacks = Vec<Vec<char>>;
+
+fn format_top(state_vec: &Stacks) -> String {
+    state_vec.iter().map(|stack| stack.last().unwrap_or(&' ')).collect::<String>()
+}
+
+fn take_box(state: &mut Stacks, from: usize) -> Result<char, Error> {
+    state[from].pop().ok_or(Error::invalid(format!("Tried to take a crate from empty stack {}", from + 1)))
+}
+
+impl Solution for Day05 {
+    type Input = (Stacks, Vec<Instruction>);
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        let mut lines = input.lines();
+        let mut initial_state: Stacks = Vec::new();
+        let mut line_idx = 0;
+        for stack_line in lines.by_ref() {
+            line_idx += 1;
+            if stack_line.is_empty() {break;}
+            for i in 0..stack_line.len().div_ceil(4) {
+                if initial_state.len() <= i { initial_state.push(Vec::new()) }
+                let char = stack_line.chars().nth(i * 4 + 1)
+                    .ok_or(Error::parse_at("Line ended in the middle of a crate", i * 4, stack_line.len() - i * 4).on_line(line_idx - 1, stack_line))?;
+                if char != ' ' { initial_state[i].insert(0, char) }
+            }
         }
-    }
 
-    let mut instructions: Vec<Instruction> = Vec::new();
-    let instruction_regex = Regex::new(r"move (\d+) from (\d+) to (\d+)")?;
-    while let Some(formatted_instruction_r) = lines.next() {
-        let formatted_instruction = formatted_instruction_r?;
-        let captures = instruction_regex.captures(formatted_instruction.as_str()).ok_or(ErrorMsg{wrapped: format!("Regex failed on '{}'", formatted_instruction)})?;
-        instructions.push(Instruction{
-            amount: captures[1].parse::<u16>()?,
-            from: captures[2].parse::<usize>()? - 1,
-            to: captures[3].parse::<usize>()? - 1
-        })
+        let mut instructions: Vec<Instruction> = Vec::new();
+        for (i, formatted_instruction) in lines.enumerate() {
+            let at_line = |err: Error| err.on_line(line_idx + i, formatted_instruction);
+            let (amount, from, to): (u16, Field, Field) = INSTRUCTION.parse(formatted_instruction).map_err(at_line)?;
+            let stack = |field: Field| -> Result<usize, Error> {
+                match field.parse::<usize>()? {
+                    num if (1..=initial_state.len()).contains(&num) => Ok(num - 1),
+                    num => Err(Error::parse_at(format!("There is no stack {num}"), field.offset, field.text.len()))
+                }
+            };
+            instructions.push(Instruction{
+                amount,
+                from: stack(from).map_err(at_line)?,
+                to: stack(to).map_err(at_line)?
+            })
+        }
+        Ok((initial_state, instructions))
     }
 
-    let mut state_v1 = initial_state.clone();
-    let mut state_v2 = initial_state.clone();
-    for instruction in instructions {
-        for _ in 0..instruction.amount {
-            let moved_box = state_v1[instruction.from].pop().ok_or(ErrorMsg{wrapped: "Failed to remove top element from stack: Nothing left".to_string()})?;
-            state_v1[instruction.to].push(moved_box)
+    fn part1(&self, (initial_state, instructions): &Self::Input) -> Result<Answer, Error> {
+        let mut state = initial_state.clone();
+        for instruction in instructions {
+            for _ in 0..instruction.amount {
+                let moved_box = take_box(&mut state, instruction.from)?;
+                state[instruction.to].push(moved_box)
+            }
         }
+        Ok(format_top(&state).into())
+    }
 
-        let v2_index = state_v2[instruction.to].len();
-        for _ in 0..instruction.amount {
-            let moved_box = state_v2[instruction.from].pop().ok_or(ErrorMsg{wrapped: "Failed to remove top element from stack: Nothing left".to_string()})?;
-            state_v2[instruction.to].insert(v2_index, moved_box)
+    fn part2(&self, (initial_state, instructions): &Self::Input) -> Result<Answer, Error> {
+        let mut state = initial_state.clone();
+        for instruction in instructions {
+            let index = state[instruction.to].len();
+            for _ in 0..instruction.amount {
+                let moved_box = take_box(&mut state, instruction.from)?;
+                state[instruction.to].insert(index, moved_box)
+            }
         }
+        Ok(format_top(&state).into())
+    }
+}
+
+/// Generates nine stacks and `size` moves, which always leave at least one crate on the stack they take from.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut stacks: Stacks = (0..9).map(|_| (0..rng.range(2..=8)).map(|_| (b'A' + rng.below(26) as u8) as char).collect()).collect();
+    let height = stacks.iter().map(|stack| stack.len()).max().unwrap_or(0);
+    let mut lines: Vec<String> = (0..height).rev().map(|level| {
+        stacks.iter().map(|stack| stack.get(level).map_or("   ".to_string(), |c| format!("[{c}]")))
+            .collect::<Vec<_>>().join(" ").trim_end().to_string()
+    }).collect();
+    lines.push((1..=stacks.len()).map(|i| format!(" {i} ")).collect::<Vec<_>>().join(" "));
+    lines.push(String::new());
+    for _ in 0..size.max(1) {
+        let from = loop {
+            let from = rng.below(stacks.len());
+            if stacks[from].len() > 1 { break from }
+        };
+        let to = (from + 1 + rng.below(stacks.len() - 1)) % stacks.len();
+        let amount = rng.range(1..=stacks[from].len() as i64 - 1) as usize;
+        let remaining = stacks[from].len() - amount;
+        let moved = stacks[from].split_off(remaining);
+        stacks[to].extend(moved);
+        lines.push(format!("move {amount} from {} to {}", from + 1, to + 1));
     }
-    let format_top: fn(&Vec<Vec<char>>) -> String = |state_vec| state_vec.iter().map(|stack| stack.last().unwrap_or(&' ')).collect::<String>();
-    Ok(println!("{} | {}", format_top(&state_v1), format_top(&state_v2)))
+    lines.join("\n")
 }
\ No newline at end of file
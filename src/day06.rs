@@ -1,36 +1,64 @@
-use crate::utils;
-use crate::utils::ErrorMsg;
+use crate::solution::{Answer, Solution};
+use crate::utils::Error;
+use crate::generate::Rng;
 
-pub fn run_sample(marker_len: usize) {
-    ErrorMsg::print(run(marker_len, "input/day06_sample.txt"));
-}
-
-pub fn run_actual(marker_len: usize) {
-    ErrorMsg::print(run(marker_len, "input/day06.txt"));
-}
-
-fn are_distinct(init: &Vec<char>, last: char) -> bool {
+fn are_distinct(init: &[char], last: char) -> bool {
     let mut accum: u32 = 1 << (last as u32 - 'a' as u32);
     for &c in init {
         let curr = 1 << (c as u32 - 'a' as u32);
         if (accum & curr) != 0 { return false; }
         accum |= curr;
     }
-    return true;
+    true
 }
 
-fn run(marker_len: usize, path: &str) -> Result<(), ErrorMsg> {
-    let line = utils::read_lines(path)?.next()
-        .ok_or(ErrorMsg{wrapped: "No lines read".to_string()})??;
+fn find_marker(marker_len: usize, line: &str) -> Result<usize, Error> {
     let mut queue: Vec<char> = Vec::new();
     for (i, c) in line.chars().enumerate() {
         if i >= marker_len - 1 {
             if are_distinct(&queue, c) {
-                return Ok(println!("{}", i + 1));
+                return Ok(i + 1);
             }
             queue.remove(0);
         }
         queue.push(c);
     }
-    Err(ErrorMsg{wrapped: "Iterated whole signal without finding marker".to_string()})
+    Err(Error::no_solution("Iterated whole signal without finding marker"))
+}
+
+/// `marker_len` overrides the length of the start-of-packet (4) and start-of-message (14) markers.
+pub struct Day06 {
+    pub marker_len: Option<usize>
+}
+
+impl Solution for Day06 {
+    type Input = String;
+
+    fn parse(&self, input: &str) -> Result<String, Error> {
+        let line = input.lines().next().ok_or(Error::parse("Input is empty"))?;
+        if let Some(i) = line.find(|c: char| !c.is_ascii_lowercase()) {
+            return Err(Error::parse_at("The signal may only consist of lowercase letters", i, 1).on_line(0, line))
+        }
+        Ok(line.to_string())
+    }
+
+    fn part1(&self, line: &String) -> Result<Answer, Error> {
+        Ok(find_marker(self.marker_len.unwrap_or(4), line)?.into())
+    }
+
+    fn part2(&self, line: &String) -> Result<Answer, Error> {
+        Ok(find_marker(self.marker_len.unwrap_or(14), line)?.into())
+    }
+}
+
+/// Generates a datastream of `size` characters. Most of it only uses a few letters,
+/// but there is a run of 14 different ones somewhere in the second half.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(28);
+    let mut stream: Vec<u8> = (0..size).map(|_| b'a' + rng.below(8) as u8).collect();
+    let mut letters: Vec<u8> = (b'a'..=b'z').collect();
+    rng.shuffle(&mut letters);
+    let start = size / 2 + rng.below(size / 2 - 13);
+    stream[start..start + 14].copy_from_slice(&letters[..14]);
+    String::from_utf8(stream).expect("The stream is ASCII letters")
 }
\ No newline at end of file
@@ -1,15 +1,15 @@
 use std::collections::HashMap;
 use crate::day07::Element::{Directory, File};
-use crate::utils;
-use crate::utils::ErrorMsg;
+use crate::solution::{Answer, Solution};
+use crate::utils::{Error, Pattern};
 use by_address::ByAddress;
+use crate::generate::Rng;
+use std::collections::HashSet;
 
-pub fn run_sample() {
-    ErrorMsg::print(run("input/day07_sample.txt"));
-}
-
-pub fn run_actual() {
-    ErrorMsg::print(run("input/day07.txt"));
+lazy_static! {
+    static ref DIR: Pattern = Pattern::new("dir {}");
+    static ref FILE: Pattern = Pattern::new("{u32} {}");
+    static ref CD: Pattern = Pattern::new("$ cd {}");
 }
 
 struct DirectoryData {
@@ -22,93 +22,145 @@ impl DirectoryData {
 }
 
 enum Element {
-    File { name: String, size: u32 },
+    File { size: u32 },
     Directory(DirectoryData)
 }
 
 impl Element{
-    fn parse(line: &String) -> Result<Element, ErrorMsg> {
-        if line.starts_with("dir ") { Ok(Directory(DirectoryData { name: line[4..].to_string() })) }
-        else {
-            let (size_str, name) = line.split_at(line.find(" ").ok_or(ErrorMsg{wrapped: "Failed to parse file: no whitespace found.".to_string()})?);
-            Ok(File {
-                name: name[1..].to_string(),
-                size: size_str.parse::<u32>()?
-            })
+    fn parse(line: &str) -> Result<Element, Error> {
+        if DIR.matches(line) {
+            let (name,) = DIR.parse(line)?;
+            Ok(Directory(DirectoryData { name }))
+        } else {
+            let (size, _): (u32, &str) = FILE.parse(line)?;
+            Ok(File { size })
         }
     }
     fn size(&self, elements_of: &HashMap<ByAddress<&DirectoryData>, Vec<&Element>>) -> u32 {
         match self {
-            File { size: x, .. } => *x,
+            File { size: x } => *x,
             Directory(d) => d.size(elements_of)
         }
     }
 }
 
-fn run(path: &str) -> Result<(), ErrorMsg> {
-    let mut lines: Vec<String> = utils::read_lines(path)?.collect::<Result<Vec<String>,std::io::Error>>()?;
-    let mut root: DirectoryData = DirectoryData { name: "/".to_string() };
-    let mut current_dir: Vec<&DirectoryData> = Vec::from([&root]);
+/// The sizes of all directories that were listed in the terminal output.
+pub struct DirectorySizes {
+    used_size: u32,
+    sizes: Vec<u32>
+}
+
+/// `disk_size` is the capacity of the device, `needed_space` how much has to be free for the update.
+pub struct Day07 {
+    pub disk_size: u32,
+    pub needed_space: u32
+}
+
+impl Solution for Day07 {
+    type Input = DirectorySizes;
 
-    let mut elements_of: HashMap<ByAddress<&DirectoryData>, Vec<&Element>> = HashMap::new();
+    fn parse(&self, input: &str) -> Result<DirectorySizes, Error> {
+        let lines: Vec<&str> = input.lines().collect();
+        let root: DirectoryData = DirectoryData { name: "/".to_string() };
+        let mut current_dir: Vec<&DirectoryData> = Vec::from([&root]);
 
-    let mut all_elements: HashMap<usize, Element> = HashMap::new();
-    for (i, line) in lines.iter().enumerate() {
-        if !line.starts_with("$ ") {
-            all_elements.entry(i).or_insert(Element::parse(line)?);
+        let mut elements_of: HashMap<ByAddress<&DirectoryData>, Vec<&Element>> = HashMap::new();
+
+        let mut all_elements: HashMap<usize, Element> = HashMap::new();
+        for (i, line) in lines.iter().enumerate() {
+            if !line.starts_with("$ ") {
+                all_elements.entry(i).or_insert(Element::parse(line).map_err(|err| err.on_line(i, line))?);
+            }
         }
-    }
 
-    for (i, line) in lines.iter().enumerate() {
-        if line.starts_with("$ ") {
-            match line[2..].split_at(2) {
-                ("ls", "") => {},
-                ("cd", x) => match x {
-                    " .." => { current_dir.pop().ok_or(ErrorMsg::new("Tried to get parent of root"))?; },
-                    " /" => { current_dir = Vec::from([&root]) },
+        for (i, line) in lines.iter().enumerate() {
+            let at_line = |err: Error| err.on_line(i, line);
+            if line.starts_with("$ ") {
+                if *line == "$ ls" { continue }
+                let (dir_name,): (&str,) = CD.parse(line).map_err(at_line)?;
+                match dir_name {
+                    ".." => { current_dir.pop().ok_or(at_line(Error::invalid("Tried to get parent of root")))?; },
+                    "/" => { current_dir = Vec::from([&root]) },
                     _ => {
-                        let dir_name = &x[1..];
                         let mut success = false;
                         for e in elements_of.get(
-                            &ByAddress(current_dir.last().ok_or(ErrorMsg::new("Was not in any dir"))?)
-                        ).ok_or(ErrorMsg::new("No elements found"))? {
-                            match e {
-                                Directory(d) => {
-                                    if d.name.as_str() == dir_name {
-                                        current_dir.push(d);
-                                        success = true;
-                                        break;
-                                    }
-                                },
-                                _ => { }
+                            &ByAddress(current_dir.last().ok_or(Error::invalid("Was not in any dir"))?)
+                        ).ok_or(Error::invalid(format!("Stepped into {dir_name} before listing its parent")))? {
+                            if let Directory(d) = e {
+                                if d.name.as_str() == dir_name {
+                                    current_dir.push(d);
+                                    success = true;
+                                    break;
+                                }
                             }
                         }
                         if !success {
-                            return Err(ErrorMsg { wrapped: format!("Failed to step in dir {}", dir_name) });
+                            return Err(Error::invalid(format!("There is no directory {dir_name}")));
                         }
                     }
-                },
-                _ => return Err(ErrorMsg { wrapped: format!("Failed to parse command {}", line) })
+                }
+            } else {
+                elements_of.entry(ByAddress(current_dir.last().ok_or(Error::invalid("Was not in any dir"))?))
+                    .or_default().push(all_elements.get(&i).expect(""));
             }
-        } else {
-            elements_of.entry(ByAddress(current_dir.last().ok_or(ErrorMsg::new("Was not in any dir"))?))
-                .or_insert(Vec::new()).push(all_elements.get(&i).expect(""));
         }
+
+        Ok(DirectorySizes {
+            used_size: root.size(&elements_of),
+            sizes: elements_of.keys().map(|dir| dir.size(&elements_of)).collect()
+        })
+    }
+
+    fn part1(&self, dirs: &DirectorySizes) -> Result<Answer, Error> {
+        Ok(dirs.sizes.iter().filter(|s| **s <= 100000).sum::<u32>().into())
+    }
+
+    fn part2(&self, dirs: &DirectorySizes) -> Result<Answer, Error> {
+        let min_to_delete = (dirs.used_size + self.needed_space).saturating_sub(self.disk_size);
+        Ok(dirs.sizes.iter()
+            .filter(|s| **s >= min_to_delete)
+            .min().copied().ok_or(Error::no_solution("No folder big enough"))?
+            .into())
     }
+}
+
+/// A made up name of a file or directory, which is not among `taken`.
+fn new_name(rng: &mut Rng, taken: &mut HashSet<String>, extension: bool) -> String {
+    loop {
+        let mut name: String = (0..rng.range(1..=8)).map(|_| (b'a' + rng.below(26) as u8) as char).collect();
+        if extension && rng.chance(50) { name = format!("{name}.{}", rng.pick(&["txt", "dat", "log", "ext", "lst"])) }
+        if taken.insert(name.clone()) { return name }
+    }
+}
+
+/// Lists a directory holding `files` files, some of which are in subdirectories, and then descends into those.
+fn generate_dir(rng: &mut Rng, files: usize, depth: usize, lines: &mut Vec<String>) {
+    let own_files = if depth >= 10 { files } else { files.min(rng.range(0..=4) as usize) };
+    let mut dirs = vec![0; if files > own_files { rng.range(1..=4) as usize } else { 0 }];
+    for _ in own_files..files {
+        let dir = rng.below(dirs.len());
+        dirs[dir] += 1;
+    }
+    let mut taken = HashSet::new();
+    let dir_names: Vec<String> = dirs.iter().map(|_| new_name(rng, &mut taken, false)).collect();
+    lines.push("$ ls".to_string());
+    for name in &dir_names {
+        lines.push(format!("dir {name}"));
+    }
+    for _ in 0..own_files {
+        let name = new_name(rng, &mut taken, true);
+        lines.push(format!("{} {name}", rng.range(1000..=300000)));
+    }
+    for (name, files) in dir_names.iter().zip(dirs) {
+        lines.push(format!("$ cd {name}"));
+        generate_dir(rng, files, depth + 1, lines);
+        lines.push("$ cd ..".to_string());
+    }
+}
 
-    let total_size = 70000000;
-    let used_size = root.size(&elements_of);
-    let needed_size = 30000000;
-    let min_to_delete = used_size + needed_size - total_size;
-
-    Ok(println!("Part 1: {}, Part 2: {}",
-        elements_of.keys()
-            .map(|dir| dir.size(&elements_of))
-            .filter(|s| *s <= 100000)
-            .sum::<u32>(),
-        elements_of.keys()
-            .map(|dir| dir.size(&elements_of))
-                .filter(|s| *s >= min_to_delete)
-                .min().ok_or(ErrorMsg::new("No folder big enough"))?
-    ))
+/// Generates the terminal output of exploring a file system with `size` files.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut lines = vec!["$ cd /".to_string()];
+    generate_dir(rng, size.max(1), 0, &mut lines);
+    lines.join("\n")
 }
\ No newline at end of file
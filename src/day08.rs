@@ -1,95 +1,54 @@
-use crate::utils;
-use crate::utils::ErrorMsg;
+use crate::solution::{Answer, Solution};
+use crate::utils::{Error, Grid};
+use crate::generate::Rng;
 
-pub fn run_sample() {
-    ErrorMsg::print(run("input/day08_sample.txt"));
+/// How many trees one can see from a tree of the given height when looking past `trees`, nearest first.
+fn viewing_distance<'a>(height: i8, trees: impl Iterator<Item=&'a i8>) -> u32 {
+    let mut distance = 0;
+    for &tree in trees {
+        distance += 1;
+        if tree >= height { break }
+    }
+    distance
 }
 
-pub fn run_actual() {
-    ErrorMsg::print(run("input/day08.txt"));
+/// The trees between `(x, y)` and each of the four edges, nearest first.
+fn lines_of_sight(trees: &Grid<i8>, x: usize, y: usize) -> [Vec<&i8>; 4] {
+    let row = trees.row(y);
+    [
+        row[..x].iter().rev().collect(),
+        row[x + 1..].iter().collect(),
+        trees.column(x).take(y).rev().collect(),
+        trees.column(x).skip(y + 1).collect()
+    ]
 }
 
-fn run(path: &str) -> Result<(), ErrorMsg> {
-    let lines: Vec<String> = utils::read_lines_to_vec(path)?;
-    let height = lines.len();
-    let width = lines[0].len();
-    let mut tree_heights = vec![vec![0i8; width]; height];
-    for (y, line) in lines.iter().enumerate() {
-        for (x, c) in line.chars().enumerate() {
-            tree_heights[y][x] = c.to_digit(10).ok_or(ErrorMsg {wrapped: format!("Failed to parse char {}", c)})? as i8
-        }
-    }
+pub struct Day08;
+
+impl Solution for Day08 {
+    type Input = Grid<i8>;
 
-    // part 1
-    let mut tree_visibility = vec![vec![false; width]; height];
-    for (y, row) in tree_heights.iter().enumerate() {
-        let mut max = -1i8;
-        for (x, height) in row.iter().enumerate() {
-            if *height > max {
-                tree_visibility[y][x] = true;
-                max = *height;
-            }
-        }
-        max = -1i8;
-        // println!("Inverse traversing row {:?}, starting at {max}", row);
-        for x in (0..width).rev() {
-            let height = row[x];
-            // println!("{height}");
-            if height > max {
-                tree_visibility[y][x] = true;
-                max = height;
-            }
-        }
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        Grid::parse(input, |c| Ok(
+            c.to_digit(10).ok_or(Error::parse(format!("Tree height has to be a digit, found {c}")))? as i8
+        ))
     }
-    for x in 0..width {
-        let mut max = -1i8;
-        for (y, row) in tree_heights.iter().enumerate() {
-            let height = row[x];
-            if height > max {
-                tree_visibility[y][x] = true;
-                max = height;
-            }
-        }
-        max = -1i8;
-        for y in (0..height).rev() {
-            let height = tree_heights[y][x];
-            if height > max {
-                tree_visibility[y][x] = true;
-                max = height;
-            }
-        }
+
+    fn part1(&self, trees: &Self::Input) -> Result<Answer, Error> {
+        Ok(trees.iter().filter(|&((x, y), height)|
+            lines_of_sight(trees, x, y).iter().any(|line| line.iter().all(|&tree| tree < height))
+        ).count().into())
     }
 
-    // part 2
-    let mut tree_scenic_view = vec![vec![1u32; width]; height];
-    let scenic_view_in_dir = |max_height: i8, trees_to_consider: Vec<(usize, usize)>| -> u32 {
-        let mut ret = 0u32;
-        for (x, y) in trees_to_consider {
-            ret += 1;
-            if tree_heights[y][x] >= max_height { return ret; }
-        }
-        return ret;
-    };
-    for (y, row) in tree_heights.iter().enumerate() {
-        for (x, h) in row.iter().enumerate() {
-            tree_scenic_view[y][x] =
-                scenic_view_in_dir(*h, (0..=x).rev().map(|xn| (xn, y)).skip(1).collect()) *
-                scenic_view_in_dir(*h, (x..width).map(|xn| (xn, y)).skip(1).collect()) *
-                scenic_view_in_dir(*h, (0..=y).rev().map(|yn| (x, yn)).skip(1).collect()) *
-                scenic_view_in_dir(*h, (y..height).map(|yn| (x, yn)).skip(1).collect())
-        }
+    fn part2(&self, trees: &Self::Input) -> Result<Answer, Error> {
+        trees.iter().map(|((x, y), &height)|
+            lines_of_sight(trees, x, y).into_iter().map(|line| viewing_distance(height, line.into_iter())).product::<u32>()
+        ).max().map(Answer::from).ok_or(Error::invalid("Forest is empty"))
     }
+}
 
-    // Ok({
-    //     tree_visibility.into_iter().for_each(|it| {
-    //         println!("{:?}", it);
-    //     });
-    //     tree_heights.into_iter().for_each(|it| {
-    //         println!("{:?}", it);
-    //     });
-    // })
-    Ok(println!("{} trees are visible and the best scenic view is {}",
-        tree_visibility.iter().map(|row| row.iter().filter(|b| **b).count()).sum::<usize>(),
-        tree_scenic_view.iter().map(|row| row.iter().max().ok_or(ErrorMsg::new("Row has no max"))).collect::<Result<Vec<&u32>, ErrorMsg>>()?.iter().max().ok_or(ErrorMsg::new("Forest has no max"))?
-    ))
+/// Generates a forest of `size` by `size` trees.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size.max(1)).map(|_| (0..size.max(1)).map(|_| (b'0' + rng.below(10) as u8) as char).collect::<String>())
+        .collect::<Vec<_>>().join("\n")
 }
\ No newline at end of file
@@ -1,44 +1,26 @@
-use std::cmp::{max, min};
 use std::collections::HashSet;
-use crate::utils::{ErrorMsg, read_lines};
+use crate::solution::{Answer, Solution};
+use crate::utils::{parse_lines, Error, Pattern};
+use crate::generate::Rng;
 
-pub fn run_sample_1() {
-    ErrorMsg::print(run(2, "input/day09_sample.txt"));
+lazy_static! {
+    static ref MOVE: Pattern = Pattern::new("{char} {i32}");
 }
 
-pub fn run_sample_2() {
-    ErrorMsg::print(run(10, "input/day09_sample_2.txt"));
-}
-
-pub fn run_actual_1() {
-    ErrorMsg::print(run(2, "input/day09.txt"));
-}
-
-pub fn run_actual_2() {
-    ErrorMsg::print(run(10, "input/day09.txt"));
-}
-
-fn run(rope_len: usize, path: &str) -> Result<(), ErrorMsg> {
+fn tail_visits(rope_len: usize, moves: &[(i32, i32)]) -> usize {
     let mut rope_positions = vec![(0i32, 0i32); rope_len];
     let mut tail_visits: HashSet<(i32, i32)> = HashSet::new();
     tail_visits.insert((0,0));
-    for line in read_lines(path)? {
-        let (x_diff, y_diff) = match line?.split_at(2) {
-            ("R ", num) => Ok((num.parse::<i32>()?, 0)),
-            ("L ", num) => Ok((-num.parse::<i32>()?, 0)),
-            ("U ", num) => Ok((0, num.parse::<i32>()?)),
-            ("D ", num) => Ok((0, -num.parse::<i32>()?)),
-            (l, r) => Err(ErrorMsg{wrapped: format!("Failed to parse line {} {}", l, r)})
-        }?;
+    for &(x_diff, y_diff) in moves {
         for _ in 0..(x_diff.abs()) {
             rope_positions[0].0 += x_diff.signum();
             for i in 1..rope_len {
                 if (rope_positions[i - 1].0 - rope_positions[i].0).abs() >= 2 {
                     rope_positions[i].0 += (rope_positions[i - 1].0 - rope_positions[i].0) / 2;
-                    rope_positions[i].1 += min(1, max(-1, (rope_positions[i - 1].1 - rope_positions[i].1)));
+                    rope_positions[i].1 += (rope_positions[i - 1].1 - rope_positions[i].1).clamp(-1, 1);
                 } else if (rope_positions[i - 1].1 - rope_positions[i].1).abs() >= 2 {
                     rope_positions[i].1 += (rope_positions[i - 1].1 - rope_positions[i].1) / 2;
-                    rope_positions[i].0 += min(1, max(-1, (rope_positions[i - 1].0 - rope_positions[i].0)));
+                    rope_positions[i].0 += (rope_positions[i - 1].0 - rope_positions[i].0).clamp(-1, 1);
                 }
             }
             tail_visits.insert(rope_positions[rope_len - 1]);
@@ -48,14 +30,46 @@ fn run(rope_len: usize, path: &str) -> Result<(), ErrorMsg> {
             for i in 1..rope_len {
                 if (rope_positions[i - 1].1 - rope_positions[i].1).abs() >= 2 {
                     rope_positions[i].1 += (rope_positions[i - 1].1 - rope_positions[i].1) / 2;
-                    rope_positions[i].0 += min(1, max(-1, (rope_positions[i - 1].0 - rope_positions[i].0)));
+                    rope_positions[i].0 += (rope_positions[i - 1].0 - rope_positions[i].0).clamp(-1, 1);
                 } else if (rope_positions[i - 1].0 - rope_positions[i].0).abs() >= 2 {
                     rope_positions[i].0 += (rope_positions[i - 1].0 - rope_positions[i].0) / 2;
-                    rope_positions[i].1 += min(1, max(-1, (rope_positions[i - 1].1 - rope_positions[i].1)));
+                    rope_positions[i].1 += (rope_positions[i - 1].1 - rope_positions[i].1).clamp(-1, 1);
                 }
             }
             tail_visits.insert(rope_positions[rope_len - 1]);
         }
     }
-    Ok(println!("Visited {} positions", tail_visits.len()))
+    tail_visits.len()
+}
+
+pub struct Day09;
+
+impl Solution for Day09 {
+    type Input = Vec<(i32, i32)>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        parse_lines(input, |line| {
+            let (dir, num): (char, i32) = MOVE.parse(line)?;
+            match dir {
+                'R' => Ok((num, 0)),
+                'L' => Ok((-num, 0)),
+                'U' => Ok((0, num)),
+                'D' => Ok((0, -num)),
+                _ => Err(Error::parse_at("Direction has to be one of R, L, U or D", 0, 1))
+            }
+        })
+    }
+
+    fn part1(&self, moves: &Self::Input) -> Result<Answer, Error> {
+        Ok(tail_visits(2, moves).into())
+    }
+
+    fn part2(&self, moves: &Self::Input) -> Result<Answer, Error> {
+        Ok(tail_visits(10, moves).into())
+    }
+}
+
+/// Generates `size` moves of up to 20 steps.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size.max(1)).map(|_| format!("{} {}", rng.pick(&['R', 'L', 'U', 'D']), rng.range(1..=20))).collect::<Vec<_>>().join("\n")
 }
\ No newline at end of file
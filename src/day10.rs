@@ -1,16 +1,16 @@
-use crate::utils;
-use crate::utils::ErrorMsg;
+use crate::solution::{Answer, Solution};
+use crate::utils::{parse_lines, Error, Pattern};
+use crate::generate::Rng;
 
-pub fn run_sample() {
-    ErrorMsg::print(run("input/day10_sample.txt"));
+lazy_static! {
+    static ref ADDX: Pattern = Pattern::new("addx {i32}");
 }
 
-pub fn run_actual() {
-    ErrorMsg::print(run("input/day10.txt"));
-}
+const SCREEN_WIDTH: i32 = 40;
+const SCREEN_HEIGHT: usize = 6;
 
-fn run(path: &str) -> Result<(), ErrorMsg> {
-    const SCREEN_WIDTH: i32 = 40;
+/// Runs the program and returns the total signal strength and the pixels drawn by the CRT.
+fn execute(instructions: &[Option<i32>]) -> (i32, Vec<char>) {
     let mut x_reg = 1;
     let mut total_signal_strength = 0;
     let mut cycle_num = 1;
@@ -20,26 +20,71 @@ fn run(path: &str) -> Result<(), ErrorMsg> {
         let new_cycle_num = cycle_num + 1;
         let x = (new_cycle_num - 1) % SCREEN_WIDTH;
         if x == 0 { image.push('\n'); }
-        image.push(if (x_reg - x).abs() <= 1 {'#'} else {' '});
+        image.push(if (x_reg - x).abs() <= 1 {'#'} else {'.'});
         if (new_cycle_num - 20) % 40 == 0 {
             (total_signal_strength + new_cycle_num * x_reg, new_cycle_num)
         } else {
             (total_signal_strength, new_cycle_num)
         }
-    };
-    for line_r in utils::read_lines(path)? {
-        let line = line_r?;
-        if line == "noop" {
-            (total_signal_strength, cycle_num) = advance_cycle(x_reg, total_signal_strength, cycle_num, &mut resulting_image);
-        } else if line.starts_with("addx") {
-            let mut new_pixel = ' ';
-            (total_signal_strength, cycle_num) = advance_cycle(x_reg, total_signal_strength, cycle_num, &mut resulting_image);
-            let num = line[5..].parse::<i32>()?;
-            x_reg += num;
-            (total_signal_strength, cycle_num) = advance_cycle(x_reg, total_signal_strength, cycle_num, &mut resulting_image);
+    }
+    for instruction in instructions {
+        match instruction {
+            None => {
+                (total_signal_strength, cycle_num) = advance_cycle(x_reg, total_signal_strength, cycle_num, &mut resulting_image);
+            },
+            Some(num) => {
+                (total_signal_strength, cycle_num) = advance_cycle(x_reg, total_signal_strength, cycle_num, &mut resulting_image);
+                x_reg += num;
+                (total_signal_strength, cycle_num) = advance_cycle(x_reg, total_signal_strength, cycle_num, &mut resulting_image);
+            }
+        }
+    }
+    (total_signal_strength, resulting_image)
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    /// `None` is a noop, `Some(num)` adds num to the x register.
+    type Input = Vec<Option<i32>>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        parse_lines(input, |line| {
+            if line == "noop" {
+                Ok(None)
+            } else if line.starts_with("addx") {
+                let (num,) = ADDX.parse(line)?;
+                Ok(Some(num))
+            } else {
+                Err(Error::parse("Expected 'noop' or 'addx <num>'"))
+            }
+        })
+    }
+
+    fn part1(&self, instructions: &Self::Input) -> Result<Answer, Error> {
+        Ok(execute(instructions).0.into())
+    }
+
+    fn part2(&self, instructions: &Self::Input) -> Result<Answer, Error> {
+        let image = execute(instructions).1.iter().collect::<String>();
+        Ok(Answer::Image(image.lines().take(SCREEN_HEIGHT).map(|row| row.to_string()).collect()))
+    }
+}
+
+/// Generates a program of `size` instructions that runs for at least the 240 cycles the screen needs,
+/// and keeps the sprite on the screen.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let (mut lines, mut cycles, mut x) = (Vec::new(), 0, 1);
+    while lines.len() < size || cycles < 240 {
+        if rng.chance(30) {
+            lines.push("noop".to_string());
+            cycles += 1;
         } else {
-            Err(ErrorMsg{wrapped: format!("Failed to parse line {}", line)})?;
+            let num = rng.range((-15).max(-1 - x)..=15.min(SCREEN_WIDTH as i64 - x));
+            lines.push(format!("addx {num}"));
+            x += num;
+            cycles += 2;
         }
     }
-    Ok(println!("Total signal was {total_signal_strength}\nimage: \n{}", resulting_image.iter().collect::<String>()))
+    lines.join("\n")
 }
\ No newline at end of file
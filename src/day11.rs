@@ -1,14 +1,6 @@
-use crate::utils;
-use crate::utils::ErrorMsg;
-use std::num::ParseIntError;
-
-pub fn run_sample(part_2: bool) {
-    ErrorMsg::print(run("input/day11_sample.txt", part_2));
-}
-
-pub fn run_actual(part_2: bool) {
-    ErrorMsg::print(run("input/day11.txt", part_2));
-}
+use crate::solution::{Answer, Solution};
+use crate::utils::{parse_num, records, Error, Field, FromFields, Pattern};
+use crate::generate::Rng;
 
 enum Operation {
     Old,
@@ -23,25 +15,25 @@ impl Operation {
         Operation::Add(l, r) => (l.eval(old_val, md) + r.eval(old_val, md)) % md,
         Operation::Mul(l, r) => (l.eval(old_val, md) * r.eval(old_val, md)) % md
     }}
-    fn parse(s: &str) -> Result<Operation, ErrorMsg> {
-        return if s == "old" { Ok(Operation::Old) } 
+    fn parse(s: &str) -> Result<Operation, Error> {
+        if s == "old" { Ok(Operation::Old) }
         else if let Some(idx) = s.find(" + ") {
             Ok(Operation::Add(
                 Box::new(Operation::parse(&s[..idx])?),
-                Box::new(Operation::parse(&s[(idx + 3)..])?)
+                Box::new(Operation::parse(&s[(idx + 3)..]).map_err(|err| err.shifted(idx + 3))?)
             ))
         } else if let Some(idx) = s.find(" * ") {
             Ok(Operation::Mul(
                 Box::new(Operation::parse(&s[..idx])?),
-                Box::new(Operation::parse(&s[(idx + 3)..])?)
+                Box::new(Operation::parse(&s[(idx + 3)..]).map_err(|err| err.shifted(idx + 3))?)
             ))
         } else {
-            Ok(Operation::Lit(s.parse::<u64>()?))
+            Ok(Operation::Lit(parse_num(s)?))
         }
     }
 }
 
-struct Monkey {
+pub struct Monkey {
     items: Vec<u64>,
     op: Operation,
     div_check: u64,
@@ -49,67 +41,123 @@ struct Monkey {
     if_false: usize
 }
 
-fn run(path: &str, part_2: bool) -> Result<(), ErrorMsg> {
-    const STARTIN_ITEMS_LINE_HEAD: &str = "  Starting items: ";
-    const OP_LINE_HEAD: &str = "  Operation: new = ";
-    const TEST_LINE_HEAD: &str = "  Test: divisible by ";
-    const TRUE_LINE_HEAD: &str = "    If true: throw to monkey ";
-    const FALSE_LINE_HEAD: &str = "    If false: throw to monkey ";
-    let mut lines = utils::read_lines(path)?.enumerate();
-    let mut monkeys: Vec<Monkey> = Vec::new();
-    while let Some((i, Ok(line0))) = lines.next() {
-        if line0 != format!("Monkey {}:", i / 7) {
-            return Err(ErrorMsg{wrapped: format!("Unexpected line encountered: {line0}") })
-        }
-        let line1 = lines.next().ok_or(ErrorMsg::new("Starting items not found"))?.1?;
-        if !line1.starts_with(STARTIN_ITEMS_LINE_HEAD) {
-            return Err(ErrorMsg{wrapped: format!("Line did not start with '{}', was '{}'", STARTIN_ITEMS_LINE_HEAD, line1)})
-        }
-        let starting_items: Vec<u64> = line1[STARTIN_ITEMS_LINE_HEAD.len()..].split(", ").map(|n| n.parse::<u64>()).collect::<Result<Vec<u64>, ParseIntError>>()?;
-        let line2 = lines.next().ok_or(ErrorMsg::new("Operation not found"))?.1?;
-        if !line2.starts_with(OP_LINE_HEAD) {
-            return Err(ErrorMsg{wrapped: format!("Line did not start with '{}', was '{}'", OP_LINE_HEAD, line2)})
-        }
-        let op = Operation::parse(&line2[OP_LINE_HEAD.len()..])?;
-        let line3 = lines.next().ok_or(ErrorMsg::new("Test not found"))?.1?;
-        if !line3.starts_with(TEST_LINE_HEAD) {
-            return Err(ErrorMsg{wrapped: format!("Line did not start with '{}', was '{}'", TEST_LINE_HEAD, line3)})
-        }
-        let div_check = line3[TEST_LINE_HEAD.len()..].parse::<u64>()?;
-        let line4 = lines.next().ok_or(ErrorMsg::new("True not found"))?.1?;
-        if !line4.starts_with(TRUE_LINE_HEAD) {
-            return Err(ErrorMsg{wrapped: format!("Line did not start with '{}', was '{}'", TRUE_LINE_HEAD, line4)})
-        }
-        let if_true = line4[TRUE_LINE_HEAD.len()..].parse::<usize>()?;
-        let line5 = lines.next().ok_or(ErrorMsg::new("False not found"))?.1?;
-        if !line5.starts_with(FALSE_LINE_HEAD) {
-            return Err(ErrorMsg{wrapped: format!("Line did not start with '{}', was '{}'", FALSE_LINE_HEAD, line5)})
-        }
-        let if_false = line5[FALSE_LINE_HEAD.len()..].parse::<usize>()?;
-        monkeys.push(Monkey {
-            items: starting_items,
-            op,
-            div_check,
-            if_true,
-            if_false
-        });
-        lines.next();
-    }
+fn monkey_business(monkeys: &[Monkey], rounds: usize, relief: u64) -> usize {
+    let mut items: Vec<Vec<u64>> = monkeys.iter().map(|m| m.items.clone()).collect();
     let mut inspections = vec![0usize; monkeys.len()];
     let div_check_p: u64 = monkeys.iter().map(|m| m.div_check).product();
-    for _ in 0..(if part_2 {10000} else {20}) {
+    for _ in 0..rounds {
         for i in 0..monkeys.len() {
-            while monkeys[i].items.len() > 0 {
-                let old_val: u64 = monkeys[i].items.remove(0);
-                let item_val: u64 = monkeys[i].op.eval(old_val, div_check_p) / if part_2 {1} else {3};
-                let target = if (item_val % monkeys[i].div_check) == 0 {
+            while !items[i].is_empty() {
+                let old_val: u64 = items[i].remove(0);
+                let item_val: u64 = monkeys[i].op.eval(old_val, div_check_p) / relief;
+                let target = if item_val.is_multiple_of(monkeys[i].div_check) {
                     monkeys[i].if_true
                 } else {monkeys[i].if_false};
-                monkeys[target as usize].items.push(item_val);
+                items[target].push(item_val);
                 inspections[i] += 1;
             }
         }
     }
     inspections.sort();
-    Ok(println!("{}", inspections[inspections.len() - 1] * inspections[inspections.len() - 2]))
+    inspections[inspections.len() - 1] * inspections[inspections.len() - 2]
+}
+
+lazy_static! {
+    static ref HEADER: Pattern = Pattern::new("Monkey {usize}:");
+    static ref STARTING_ITEMS: Pattern = Pattern::new("  Starting items: {}");
+    static ref OPERATION: Pattern = Pattern::new("  Operation: new = {}");
+    static ref TEST: Pattern = Pattern::new("  Test: divisible by {u64}");
+    static ref IF_TRUE: Pattern = Pattern::new("    If true: throw to monkey {usize}");
+    static ref IF_FALSE: Pattern = Pattern::new("    If false: throw to monkey {usize}");
+}
+
+/// Parses the next line of a monkey with `pattern`, then `parse` the fields.
+fn next_line<'a, F: FromFields<'a>, T>(
+    lines: &mut impl Iterator<Item=(usize, &'a str)>, pattern: &Pattern, parse: impl Fn(F) -> Result<T, Error>
+) -> Result<T, Error> {
+    let (i, line) = lines.next().ok_or(Error::parse(format!("Monkey ended, expected '{}'", pattern.as_str().trim_start())))?;
+    pattern.parse(line).and_then(parse).map_err(|err| err.on_line(i, line))
+}
+
+/// `rounds` to play in each part, `relief` divides the worry level after inspections in part 1.
+pub struct Day11 {
+    pub rounds: [usize; 2],
+    pub relief: u64
+}
+
+impl Solution for Day11 {
+    type Input = Vec<Monkey>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        let mut monkeys: Vec<Monkey> = Vec::new();
+        let records = records(input);
+        for record in &records {
+            let mut lines = record.numbered_lines();
+            next_line(&mut lines, &HEADER, |(num,): (Field,)| match num.parse::<usize>()? {
+                n if n == monkeys.len() => Ok(()),
+                _ => Err(Error::parse_at(format!("Expected monkey {}", monkeys.len()), num.offset, num.text.len()))
+            })?;
+            let starting_items = next_line(&mut lines, &STARTING_ITEMS, |(items,): (Field,)| items.list(", ", parse_num))?;
+            let op = next_line(&mut lines, &OPERATION, |(op,): (Field,)| Operation::parse(op.text).map_err(|err| err.shifted(op.offset)))?;
+            let div_check = next_line(&mut lines, &TEST, |(num,): (Field,)| match num.parse::<u64>()? {
+                0 => Err(Error::parse_at("Monkeys cannot test divisibility by 0", num.offset, num.text.len())),
+                n => Ok(n)
+            })?;
+            let if_true = next_line(&mut lines, &IF_TRUE, |(num,)| Ok(num))?;
+            let if_false = next_line(&mut lines, &IF_FALSE, |(num,)| Ok(num))?;
+            if let Some((i, line)) = lines.next() {
+                return Err(Error::parse("Expected a blank line after the monkey").on_line(i, line))
+            }
+            monkeys.push(Monkey {
+                items: starting_items,
+                op,
+                div_check,
+                if_true,
+                if_false
+            });
+        }
+        if let [] | [_] = records.as_slice() {
+            let error = Error::parse(format!("Monkey business needs at least 2 monkeys, found {}", records.len()));
+            return Err(match records.first() {
+                Some(record) => error.on_line(record.start, record.lines[0]),
+                None => error
+            })
+        }
+        for (i, monkey) in monkeys.iter().enumerate() {
+            if let Some(target) = [monkey.if_true, monkey.if_false].into_iter().find(|&t| t >= monkeys.len()) {
+                return Err(Error::invalid(format!("Monkey {i} throws to monkey {target}, which does not exist")))
+            }
+        }
+        Ok(monkeys)
+    }
+
+    fn part1(&self, monkeys: &Self::Input) -> Result<Answer, Error> {
+        Ok(monkey_business(monkeys, self.rounds[0], self.relief).into())
+    }
+
+    fn part2(&self, monkeys: &Self::Input) -> Result<Answer, Error> {
+        Ok(monkey_business(monkeys, self.rounds[1], 1).into())
+    }
+}
+
+/// The tests of the monkeys are distinct primes, whose product keeps the worry levels of part 2 small.
+const PRIMES: [u64; 9] = [2, 3, 5, 7, 11, 13, 17, 19, 23];
+
+/// Generates `size` monkeys, at least 2 and at most 9 so that every monkey can test a different prime.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let count = size.clamp(2, PRIMES.len());
+    let mut primes = PRIMES;
+    rng.shuffle(&mut primes);
+    let squaring = rng.below(count);
+    (0..count).map(|i| {
+        let items: Vec<String> = (0..rng.range(1..=8)).map(|_| rng.range(50..=99).to_string()).collect();
+        let operation = if i == squaring { "old * old".to_string() }
+            else if rng.chance(50) { format!("old + {}", rng.range(1..=8)) }
+            else { format!("old * {}", rng.range(2..=19)) };
+        let mut others: Vec<usize> = (0..count).filter(|&other| other != i).collect();
+        rng.shuffle(&mut others);
+        let (if_true, if_false) = (others[0], others[others.len() - 1]);
+        format!("Monkey {i}:\n  Starting items: {}\n  Operation: new = {operation}\n  Test: divisible by {}\n    \
+                 If true: throw to monkey {if_true}\n    If false: throw to monkey {if_false}", items.join(", "), primes[i])
+    }).collect::<Vec<_>>().join("\n\n")
 }
\ No newline at end of file
@@ -1,99 +1,75 @@
-use std::cmp::Ordering;
-use std::collections::BinaryHeap;
-use crate::utils;
-use crate::utils::ErrorMsg;
+use crate::search::bfs;
+use crate::solution::{Answer, Solution};
+use crate::utils::{Error, Grid};
+use crate::generate::Rng;
 
-pub fn run_sample() {
-    ErrorMsg::print(run("input/day12_sample.txt"));
+/// The fewest steps from `start` to any position at `target_elevation`. With `reverse`, every step may climb
+/// at most one level. Otherwise it may descend at most one level, which is the same as walking the path backwards.
+fn search(elevation: &Grid<i8>, start: (usize, usize), target_elevation: i8, reverse: bool) -> Result<u64, Error> {
+    let path = bfs(start, |&(x, y)| {
+        let my_elevation = elevation[(x, y)];
+        elevation.neighbors4(x, y).filter(move |&n| {
+            let elevation_diff = if reverse { elevation[n] - my_elevation } else { my_elevation - elevation[n] };
+            elevation_diff <= 1
+        })
+    }, |&pos| elevation[pos] == target_elevation);
+    let path = path.ok_or(Error::no_solution("Did not find the destination"))?;
+    trace!("Path: {}", path.states.iter().map(|(x, y)| format!("{x},{y}")).collect::<Vec<_>>().join(" "));
+    Ok(path.cost)
 }
 
-pub fn run_actual() {
-    ErrorMsg::print(run("input/day12.txt"));
+/// Elevations from 0 (a) to 25 (z). The start is at -1 and the target at 26.
+pub struct Heightmap {
+    elevation: Grid<i8>,
+    start: (usize, usize),
+    target: (usize, usize)
 }
 
-#[derive(Copy, Clone, Eq, PartialEq)]
-struct SearchEntry {
-    x: usize,
-    y: usize,
-    total_cost: i32
-}
-impl Ord for SearchEntry {
-    fn cmp(&self, other: &Self) -> Ordering {
-        other.total_cost.cmp(&self.total_cost) // min heap
-    }
-}
-impl PartialOrd for SearchEntry {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Input = Heightmap;
+
+    fn parse(&self, input: &str) -> Result<Heightmap, Error> {
+        let map = Grid::parse(input, |c| match c {
+            'S' | 'E' | 'a'..='z' => Ok(c),
+            _ => Err(Error::parse(format!("Expected an elevation from a to z, found {c}")))
+        })?;
+        let start = map.position(|&c| c == 'S').ok_or(Error::invalid("Did not find starting position"))?;
+        let target = map.position(|&c| c == 'E').ok_or(Error::invalid("Did not find target position"))?;
+        let elevation = map.map(|&c| match c {
+            'S' => -1,
+            'E' => 'z' as i8 - 'a' as i8 + 1,
+            c => c as i8 - 'a' as i8
+        });
+        Ok(Heightmap { elevation, start, target })
     }
-}
-impl SearchEntry {
-    fn neighbors(&self, width: usize, height: usize) -> Vec<SearchEntry> {
-        vec![(self.x as i32 - 1, self.y as i32), (self.x as i32, self.y as i32 - 1), (self.x as i32 + 1, self.y as i32), (self.x as i32, self.y as i32 + 1)]
-            .iter()
-            .filter(|(x, y)| *x >= 0 && *y >= 0 && *x < width as i32 && *y < height as i32)
-            .map(|(x, y)| SearchEntry {
-                x: *x as usize, y: *y as usize,
-                total_cost: self.total_cost + 1
-            }).collect::<Vec<SearchEntry>>()
+
+    fn part1(&self, map: &Heightmap) -> Result<Answer, Error> {
+        Ok(search(&map.elevation, map.start, 'z' as i8 - 'a' as i8 + 1, true)?.into())
     }
-}
 
-fn search(width: usize, height: usize, elevation: &Vec<Vec<i8>>, start: (usize, usize), target_elevation: i8, reverse: bool) -> Result<i32, ErrorMsg> {
-    let mut cost_to_reach = vec![vec![-1i32; width]; height];
-    let mut has_been_handled = vec![vec![false; width]; height];
-    let mut to_search: BinaryHeap<SearchEntry> = BinaryHeap::new();
-    cost_to_reach[start.1][start.0] = 0;
-    to_search.push(SearchEntry {
-        x: start.0,
-        y: start.1,
-        total_cost: 0
-    });
-    while let Some(next) = to_search.pop() {
-        if elevation[next.y][next.x] == target_elevation {
-            return Ok(next.total_cost)
-        }
-        if has_been_handled[next.y][next.x] { continue; }
-        has_been_handled[next.y][next.x] = true;
-        let my_elevation = elevation[next.y][next.x];
-        for n in next.neighbors(width, height) {
-            let elevation_diff = if reverse {
-                elevation[n.y][n.x] - my_elevation
-            } else { my_elevation - elevation[n.y][n.x] };
-            if elevation_diff > 1 { continue; }
-            let old_cost = cost_to_reach[n.y][n.x];
-            if (old_cost < 0 || old_cost > n.total_cost) && !has_been_handled[n.y][n.x] {
-                cost_to_reach[n.y][n.x] = n.total_cost;
-                to_search.push(n);
-            }
-        }
+    fn part2(&self, map: &Heightmap) -> Result<Answer, Error> {
+        Ok(search(&map.elevation, map.target, 0, false)?.into())
     }
-    println!("Costs: \n{}", cost_to_reach.iter().map(|row| row.iter().map(|c| c.to_string() + ", ").collect::<String>() + "\n").collect::<String>());
-    println!("Has been handled: \n{}", has_been_handled.iter().map(|row| row.iter().map(|c| (if *c {"1"} else {"0"})).collect::<String>() + "\n").collect::<String>());
-    Err(ErrorMsg::new("Did not find the destination"))
 }
 
-fn run(path: &str) -> Result<(), ErrorMsg> {
-    let lines: Vec<String> = utils::read_lines_to_vec(path)?;
-    let height = lines.len();
-    let width = lines[0].len();
-    let mut elevation = vec![vec![0i8; width]; height];
-    let mut start_coord: Result<(usize, usize), ErrorMsg> = Err(ErrorMsg::new("Did not find starting position"));
-    let mut target_coord: Result<(usize, usize), ErrorMsg> = Err(ErrorMsg::new("Did not find target position"));
-    for (y, line) in lines.iter().enumerate() {
-        for (x, c) in line.chars().enumerate() {
-            elevation[y][x] = match c {
-                'S' => Ok(-1),
-                'a'..='z' => Ok(c as i8 - 'a' as i8),
-                'E' => Ok('z' as i8 - 'a' as i8 + 1),
-                _ => Err(ErrorMsg{wrapped: format!("Failed to parse char {c}")})
-            }?;
-            if c == 'S' { start_coord = Ok((x, y)) }
-            if c == 'E' { target_coord = Ok((x, y)) }
-        }
-    }
-    Ok({
-        println!("Hill top found in {}", search(width, height, &elevation, start_coord?, 'z' as i8 - 'a' as i8 + 1, true)?);
-        println!("Scenic route is {}", search(width, height, &elevation, target_coord?, 0, false)?)
-    })
+/// Generates a heightmap with `size` rows and four times as many columns, where the elevation changes by at most one
+/// between neighbors, so that every square can be reached. It starts at the left edge, which is at the lowest level,
+/// and rises to the best signal on a peak, with some smaller hills on the way.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let (height, width) = (size.max(5) as i64, (4 * size).max(30) as i64);
+    // The signal is one above z, so it has to be at least that far from the left edge
+    let signal = (rng.range(26.max(width * 3 / 4)..=width - 1), rng.range(0..=height - 1));
+    let start = (0, rng.range(0..=height - 1));
+    let hills: Vec<((i64, i64), i64, i64)> = (0..=size / 4)
+        .map(|_| ((rng.range(0..=width - 1), rng.range(0..=height - 1)), rng.range(5..=24), rng.range(1..=3)))
+        .collect();
+    (0..height).map(|y| (0..width).map(|x| {
+        if (x, y) == start { return 'S' }
+        if (x, y) == signal { return 'E' }
+        let distance = |(cx, cy): (i64, i64)| (x - cx).abs() + (y - cy).abs();
+        let rise = hills.iter().map(|&(center, top, slope)| top - distance(center) / slope).fold(26 - distance(signal), i64::max);
+        (b'a' + rise.min(x).clamp(0, 25) as u8) as char
+    }).collect::<String>()).collect::<Vec<_>>().join("\n")
 }
\ No newline at end of file
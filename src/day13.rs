@@ -1,32 +1,27 @@
 use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 use crate::day13::PacketEntry::{Number, List};
-use crate::utils;
-use crate::utils::ErrorMsg;
+use crate::solution::{Answer, Solution};
+use crate::utils::{parse_num, records, Error};
+use crate::generate::Rng;
 
-pub fn run_sample() {
-    ErrorMsg::print(run("input/day13_sample.txt"));
-}
-
-pub fn run_actual() {
-    ErrorMsg::print(run("input/day13.txt"));
-}
-
-#[derive(Eq, PartialEq)]
-enum PacketEntry {
+/// A distress signal packet. Its [Ord] is the order the packets are supposed to be in.
+#[derive(Eq, PartialEq, Debug)]
+pub enum PacketEntry {
     Number(u8),
     List(Vec<PacketEntry>)
 }
 
 impl FromStr for PacketEntry {
-    type Err = ErrorMsg;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         if s.starts_with('[') {
-            if !s.ends_with(']') { Err(ErrorMsg { wrapped: format!("Failed to parse packet entry {s}: Started with [, but didn't end with ]") }) }
+            if !s.ends_with(']') { Err(Error::parse_at("List is never closed", 0, s.len())) }
             else {
-                fn idx(s: &str, idx: usize) -> Result<char, ErrorMsg> {
-                    s.chars().nth(idx).ok_or(ErrorMsg{wrapped: format!("Unexpected end of string found while trying to parse entry {s}")})
+                fn idx(s: &str, idx: usize) -> Result<char, Error> {
+                    s.chars().nth(idx).ok_or(Error::parse_at("Packet ended inside of a list, missing ']'", s.len(), 1))
                 }
                 let mut entries: Vec<PacketEntry> = Vec::new();
                 if s.len() == 2 { return Ok(List(entries)) }
@@ -38,25 +33,35 @@ impl FromStr for PacketEntry {
                         match idx(s, to)? {
                             '[' => { list_depth += 1; }
                             ']' => {
-                                if list_depth == 0 { return Err(ErrorMsg::new("Tried to escape root list"))}
+                                if list_depth == 0 { return Err(Error::parse_at("Unmatched ']'", to, 1))}
                                 list_depth -= 1; }
                             _ => {}
                         }
                         to += 1;
                     }
-                    entries.push(s[from..to].parse::<PacketEntry>()?);
-                    to = to + 1;
+                    entries.push(s[from..to].parse::<PacketEntry>().map_err(|err| err.shifted(from))?);
+                    to += 1;
                     from = to;
                 }
                 Ok(List(entries))
             }
         } else {
-            Ok(Number(s.parse::<u8>()?))
+            Ok(Number(parse_num(s)?))
+        }
+    }
+}
+
+impl Display for PacketEntry {
+    /// Writes the packet the way it is parsed, like `[1,[2,3]]`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Number(num) => write!(f, "{num}"),
+            List(entries) => write!(f, "[{}]", entries.iter().map(|entry| entry.to_string()).collect::<Vec<_>>().join(","))
         }
     }
 }
 
-fn cmp_lists(l: &Vec<PacketEntry>, r: &Vec<PacketEntry>) -> Ordering {
+fn cmp_lists(l: &[PacketEntry], r: &[PacketEntry]) -> Ordering {
     let mut r_it = r.iter();
     for l_e in l {
         if let Some(r_e) = r_it.next() {
@@ -90,30 +95,48 @@ impl PartialOrd for PacketEntry {
     }
 }
 
-fn run(path: &str) -> Result<(), ErrorMsg> {
-    let mut lines = utils::read_lines(path)?;
-    let mut idx = 0u32;
-    let mut right_sum = 0u32;
-    let mut all_packets: Vec<PacketEntry> = Vec::new();
-    while let Some(l1_r) = lines.next() {
-        idx += 1;
-        let l1 = l1_r?;
-        let l2 = lines.next().ok_or(ErrorMsg::new("Expected second packet, didn't find one"))??;
-        let p1 = l1.parse::<PacketEntry>()?;
-        let p2 = l2.parse::<PacketEntry>()?;
-        if p1 < p2 {
-            right_sum += idx;
-        }
-        all_packets.push(p1);
-        all_packets.push(p2);
-        lines.next();
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Input = Vec<(PacketEntry, PacketEntry)>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        records(input).iter().map(|pair| {
+            let [first, second] = <[PacketEntry; 2]>::try_from(pair.parse_lines(|line| line.parse::<PacketEntry>())?)
+                .map_err(|_| Error::parse(format!("Expected a pair of packets, found {} lines", pair.lines.len()))
+                    .on_line(pair.start, pair.lines[0]))?;
+            Ok((first, second))
+        }).collect()
+    }
+
+    fn part1(&self, pairs: &Self::Input) -> Result<Answer, Error> {
+        Ok(pairs.iter().enumerate()
+            .filter(|(_, (p1, p2))| p1 < p2)
+            .map(|(idx, _)| idx + 1)
+            .sum::<usize>().into())
+    }
+
+    fn part2(&self, pairs: &Self::Input) -> Result<Answer, Error> {
+        let first_div = List(vec![List(vec![Number(2)])]);
+        let second_div = List(vec![List(vec![Number(6)])]);
+        let mut all_packets: Vec<&PacketEntry> = pairs.iter().flat_map(|(p1, p2)| [p1, p2]).collect();
+        all_packets.push(&first_div);
+        all_packets.push(&second_div);
+        all_packets.sort();
+        let first_div_idx = all_packets.iter().position(|e| **e == first_div).ok_or(Error::no_solution("Did not find first divider"))?;
+        let second_div_idx = all_packets.iter().position(|e| **e == second_div).ok_or(Error::no_solution("Did not find second divider"))?;
+        Ok(((first_div_idx + 1) * (second_div_idx + 1)).into())
     }
-    let first_div = List(vec![List(vec![Number(2)])]);
-    let second_div = List(vec![List(vec![Number(6)])]);
-    all_packets.push(first_div);
-    all_packets.push(second_div);
-    all_packets.sort();
-    let first_div_idx = all_packets.iter().position(|e| *e == List(vec![List(vec![Number(2)])])).ok_or(ErrorMsg::new("Did not find first divider"))?;
-    let second_div_idx = all_packets.iter().position(|e| *e == List(vec![List(vec![Number(6)])])).ok_or(ErrorMsg::new("Did not find second divider"))?;
-    Ok(println!("Right sum was {right_sum}, div index mul was {}", (first_div_idx + 1) * (second_div_idx + 1)))
+}
+
+fn generate_packet(rng: &mut Rng, depth: usize) -> String {
+    let entries: Vec<String> = (0..rng.range(0..=4)).map(|_| {
+        if depth < 4 && rng.chance(30) { generate_packet(rng, depth + 1) } else { rng.range(0..=10).to_string() }
+    }).collect();
+    format!("[{}]", entries.join(","))
+}
+
+/// Generates `size` pairs of packets nested up to five levels deep.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size.max(1)).map(|_| format!("{}\n{}", generate_packet(rng, 0), generate_packet(rng, 0))).collect::<Vec<_>>().join("\n\n")
 }
\ No newline at end of file
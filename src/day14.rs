@@ -1,91 +1,67 @@
 use std::cmp::{max, min};
-use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
-use std::ops;
 use std::str::FromStr;
-use regex::Regex;
-use crate::utils;
-use crate::utils::ErrorMsg;
+use crate::geom::Vec2;
+use crate::solution::{Answer, Solution};
+use crate::utils::{parse_lines, parse_list, Error, GrowingGrid};
+use crate::visualize::{Recorder, Visualize};
+use crate::generate::Rng;
 
-pub fn run_sample() {
-    ErrorMsg::print(run("input/day14_sample.txt"));
-}
+/// A path of rock through the points in `handles`, with straight lines between them.
+#[derive(PartialEq, Debug)]
+pub struct RockStrip { handles: Vec<Vec2<i32>> }
 
-pub fn run_actual() {
-    ErrorMsg::print(run("input/day14.txt"));
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Cell {
+    Air, Rock, Sand
 }
 
-#[derive(Clone, Copy, PartialEq)]
-struct Point { x: i32, y: i32 }
-struct RockStrip { handles: Vec<Point> }
-
-impl ops::Add<Point> for Point {
-    type Output = Point;
+impl FromStr for RockStrip {
+    type Err = Error;
 
-    fn add(self, rhs: Point) -> Self::Output {
-        Point {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y
-        }
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(RockStrip{handles: parse_list(s, " -> ", str::parse)?})
     }
 }
-impl Display for Point {
+impl Display for RockStrip {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.write_str(format!("({}|{})", self.x, self.y).as_str())
-    }
-}
-
-impl FromStr for Point {
-    type Err = ErrorMsg;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (l, r) = s.split_once(',')
-            .ok_or(ErrorMsg{wrapped:format!("Failed to find point separator in {s}")})?;
-        Ok(Point {
-            x: l.parse::<i32>()?,
-            y: r.parse::<i32>()?
-        })
+        f.write_str(&self.handles.iter().map(|handle| handle.to_string()).collect::<Vec<_>>().join(" -> "))
     }
 }
-lazy_static! {
-    static ref STRIP_REGEX: Regex = Regex::new(r"(\d+,\d+)").unwrap();
-}
-impl FromStr for RockStrip {
-    type Err = ErrorMsg;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let capturs = STRIP_REGEX.captures_iter(s).filter_map(|n| n.get(0).map(|m| m.as_str()));
-        let points = capturs.map(|s| s.parse::<Point>()).collect::<Result<Vec<Point>, ErrorMsg>>()?;
-        Ok(RockStrip{handles: points})
-    }
+impl RockStrip {
+    pub fn new(handles: Vec<Vec2<i32>>) -> RockStrip { RockStrip { handles } }
 }
 
-fn run(path: &str) -> Result<(), ErrorMsg> {
-    let rock_formations: Vec<RockStrip> = utils::read_lines(path)?.map(|l| l?.parse::<RockStrip>())
-        .collect::<Result<Vec<RockStrip>, ErrorMsg>>()?;
-    let sand_start = Point {x: 500, y: 0};
-    let min_x = rock_formations.iter().flat_map(|s| s.handles.iter().map(|p| p.x)).min().unwrap();
-    let max_x = rock_formations.iter().flat_map(|s| s.handles.iter().map(|p| p.x)).max().unwrap();
-    let max_y = rock_formations.iter().flat_map(|s| s.handles.iter().map(|p| p.y)).max().unwrap();
-    let height = (max_y + 2) as usize;
-    let mut is_blocked: HashMap<i32, Vec<bool>> = HashMap::new();
+/// Pours sand until the source is blocked. Returns how many units came to rest before the first one fell off
+/// the rock formations, and how many it took to block the source when there is a floor below them.
+fn pour_sand(rock_formations: &[RockStrip], recorder: &mut Recorder) -> Result<(usize, usize), Error> {
+    let sand_start = Vec2::new(500, 0);
+    let min_x = rock_formations.iter().flat_map(|s| s.handles.iter().map(|p| p.x)).min().ok_or(Error::invalid("No rocks found"))?;
+    let max_x = rock_formations.iter().flat_map(|s| s.handles.iter().map(|p| p.x)).max().ok_or(Error::invalid("No rocks found"))?;
+    let max_y = rock_formations.iter().flat_map(|s| s.handles.iter().map(|p| p.y)).max().ok_or(Error::invalid("No rocks found"))?;
+    let mut cave = GrowingGrid::new(Cell::Air);
     for (from, to) in rock_formations.iter().flat_map(|strip| strip.handles.iter().zip(strip.handles.iter().skip(1))) {
         for x in min(from.x,to.x)..=max(from.x,to.x) {
             for y in min(from.y,to.y)..=max(from.y,to.y) {
-                is_blocked.entry(x).or_insert(vec![false; height])[y as usize] = true;
+                cave.set(x as i64, y as i64, Cell::Rock);
             }
         }
     }
-    let search_pattern = vec![Point{x:0,y:1}, Point{x:-1,y:1}, Point{x:1,y:1}];
+    let search_pattern = [Vec2::new(0, 1), Vec2::new(-1, 1), Vec2::new(1, 1)];
     let mut num_sand = 0;
     let mut reached_bottom_after = None;
     let mut has_finished = false;
-    println!("Map: \n{}", (0..height).map(|y| (min_x..=max_x).map(|x| if is_blocked.entry(x).or_insert(vec![false; height])[y as usize] {"#"} else {" "}).collect::<String>() + "\n").collect::<String>());
+    let draw = |cave: &GrowingGrid<Cell>| cave.grid().map(|cell| match cell {
+        Cell::Air => '.',
+        Cell::Rock => '#',
+        Cell::Sand => 'o'
+    });
+    trace!("Map:\n{}", draw(&cave));
     while !has_finished {
-        let mut cur_sand_pos = Point{x:sand_start.x, y:sand_start.y};
-        while let Some(next) = search_pattern.iter().map(|diff| cur_sand_pos + *diff).find(|p| !is_blocked.entry(p.x).or_insert(vec![false; height])[p.y as usize]) {
+        let mut cur_sand_pos = sand_start;
+        while let Some(next) = search_pattern.iter().map(|diff| cur_sand_pos + *diff).find(|p| *cave.get(p.x as i64, p.y as i64) == Cell::Air) {
             cur_sand_pos = next;
-            if reached_bottom_after == None && (next.x < min_x || next.x > max_x || next.y > max_y) {
+            if reached_bottom_after.is_none() && (next.x < min_x || next.x > max_x || next.y > max_y) {
                 reached_bottom_after = Some(num_sand);
             }
             if next.y > max_y {
@@ -96,9 +72,45 @@ fn run(path: &str) -> Result<(), ErrorMsg> {
         if cur_sand_pos == sand_start {
             has_finished = true;
         } else {
-            assert!(!is_blocked.entry(cur_sand_pos.x).or_insert(vec![false; height])[cur_sand_pos.y as usize]);
-            is_blocked.entry(cur_sand_pos.x).or_insert(vec![false; height])[cur_sand_pos.y as usize] = true;
+            assert!(*cave.get(cur_sand_pos.x as i64, cur_sand_pos.y as i64) == Cell::Air);
+            cave.set(cur_sand_pos.x as i64, cur_sand_pos.y as i64, Cell::Sand);
         }
+        recorder.tick(|| draw(&cave))?;
+    }
+    Ok((reached_bottom_after.ok_or(Error::no_solution("Sand never reached the edge"))?, num_sand))
+}
+
+pub struct Day14 {
+    pub visualize: Option<Visualize>
+}
+
+impl Solution for Day14 {
+    type Input = Vec<RockStrip>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        parse_lines(input, |l| l.parse::<RockStrip>())
     }
-    Ok(println!("Finished after {num_sand} sands. Reached edge after {} sands", reached_bottom_after.unwrap()))
+
+    fn part1(&self, rock_formations: &Self::Input) -> Result<Answer, Error> {
+        Ok(pour_sand(rock_formations, &mut Recorder::start(self.visualize.as_ref(), "part1")?)?.0.into())
+    }
+
+    fn part2(&self, rock_formations: &Self::Input) -> Result<Answer, Error> {
+        Ok(pour_sand(rock_formations, &mut Recorder::start(self.visualize.as_ref(), "part2")?)?.1.into())
+    }
+}
+
+/// Generates `size` paths of rock, each with up to four horizontal or vertical lines below the source of the sand.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let spread = size.max(10) as i64;
+    (0..size.max(1)).map(|_| {
+        let mut point = (rng.range(500 - spread..=500 + spread), rng.range(2..=170));
+        let mut points = vec![point];
+        for i in 0..rng.range(1..=4) {
+            let len = rng.range(1..=8) * if rng.chance(50) {1} else {-1};
+            point = if i % 2 == 0 { (point.0 + len, point.1) } else { (point.0, (point.1 + len).max(2)) };
+            points.push(point);
+        }
+        points.iter().map(|(x, y)| format!("{x},{y}")).collect::<Vec<_>>().join(" -> ")
+    }).collect::<Vec<_>>().join("\n")
 }
\ No newline at end of file
@@ -1,76 +1,63 @@
-use std::cmp::{max, min, Ordering};
+use std::cmp::{max, min};
 use std::fmt::{Display, Formatter};
 use std::str::FromStr;
-use regex::Regex;
-use crate::utils;
-use crate::utils::ErrorMsg;
-
-pub fn run_sample() {
-    ErrorMsg::print(run("input/day15_sample.txt", 10, 20));
-}
-
-pub fn run_actual() {
-    ErrorMsg::print(run("input/day15.txt", 2000000, 4000000));
-}
+use crate::geom::{Coord, Vec2};
+use crate::solution::{Answer, Solution};
+use crate::utils::{parse_lines, Error, Pattern};
+use crate::generate::Rng;
 
 lazy_static! {
-    static ref POS_REGEX: Regex = Regex::new(r"x=(-?\d+), y=(-?\d+)").unwrap();
-    static ref SENSOR_REGEX: Regex = Regex::new(r"Sensor at (.+): closest beacon is at (.+)").unwrap();
-}
-#[derive(Clone, Copy, PartialEq, Eq, Ord)]
-struct Pos { x: i32, y: i32 }
-impl FromStr for Pos {
-    type Err = ErrorMsg;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let captures = POS_REGEX.captures(s).ok_or(ErrorMsg{wrapped:format!("Failed to capture pos regex in {s}")})?;
-        Ok(Pos {
-            x: captures[1].parse::<i32>()?,
-            y: captures[2].parse::<i32>()?
-        })
-    }
-}
-impl Display for Pos {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.write_str(format!("x={}, y={}", self.x, self.y).as_str())
-    }
+    static ref SENSOR: Pattern = Pattern::new("Sensor at x={i32}, y={i32}: closest beacon is at x={i32}, y={i32}");
 }
-impl PartialOrd for Pos {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(if self.x == other.x {self.y.cmp(&other.y)} else {self.x.cmp(&other.x)})
-    }
-}
-
-#[derive(Clone, Copy, PartialEq)]
-struct Sensor {
-    pos: Pos,
-    beacon: Pos
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Sensor {
+    pos: Vec2<i32>,
+    beacon: Vec2<i32>
 }
 impl FromStr for Sensor {
-    type Err = ErrorMsg;
+    type Err = Error;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let captures = SENSOR_REGEX.captures(s).ok_or(ErrorMsg{wrapped:format!("Failed to capture sensor regex in {s}")})?;
+        let (x, y, beacon_x, beacon_y) = SENSOR.parse(s)?;
         Ok(Sensor {
-            pos: captures[1].parse::<Pos>()?,
-            beacon: captures[2].parse::<Pos>()?
+            pos: Vec2::new(x, y),
+            beacon: Vec2::new(beacon_x, beacon_y)
         })
     }
 }
+impl Display for Sensor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Sensor at x={}, y={}: closest beacon is at x={}, y={}", self.pos.x, self.pos.y, self.beacon.x, self.beacon.y)
+    }
+}
 impl Sensor {
+    pub fn new(pos: Vec2<i32>, beacon: Vec2<i32>) -> Sensor {
+        Sensor { pos, beacon }
+    }
     fn range(&self) -> i32 {
-        (self.beacon.x - self.pos.x).abs() + (self.beacon.y - self.pos.y).abs()
+        self.pos.manhattan(self.beacon)
     }
     fn range_at(&self, row: i32) -> Option<i32> {
-        let ret = self.range() - (self.pos.y - row).abs();
+        let ret = self.range() - self.pos.y.distance(row);
         if ret > 0 { Some(ret) } else { None }
     }
 }
 
-struct Ranges {
+/// A set of integers, stored as sorted, disjoint and inclusive intervals.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Ranges {
     wrapped: Vec<(i32, i32)>
 }
 impl Ranges {
-    fn combine_with(&self, center: i32, radius: i32) -> Ranges {
-        let mut new_range = (center - radius.abs(), center + radius.abs());
+    pub fn new() -> Ranges { Ranges::default() }
+    /// The intervals `(from, to)`, sorted and without overlaps.
+    pub fn intervals(&self) -> &[(i32, i32)] { &self.wrapped }
+    /// Adds all numbers within `radius` of `center`.
+    pub fn combine_with(&self, center: i32, radius: i32) -> Ranges {
+        self.with((center - radius.abs(), center + radius.abs()))
+    }
+    /// Adds the interval `from..=to`, merging it with the ones it overlaps.
+    pub fn with(&self, (from, to): (i32, i32)) -> Ranges {
+        let mut new_range = (from, to);
         let mut ret = Vec::new();
         let mut was_inserted = false;
         for old_range in self.wrapped.as_slice() {
@@ -90,7 +77,8 @@ impl Ranges {
         }
         Ranges {wrapped: ret}
     }
-    fn sub(&self, slice: &(i32, i32)) -> Ranges {
+    /// Removes the interval `slice.0..=slice.1`.
+    pub fn sub(&self, slice: &(i32, i32)) -> Ranges {
         let mut ret = Vec::new();
         for old_range in self.wrapped.as_slice() {
             if old_range.0 > slice.1 || old_range.1 < slice.0 { ret.push(*old_range); }
@@ -105,34 +93,85 @@ impl Ranges {
         }
         Ranges {wrapped: ret}
     }
-    fn contains(&self, point: i32) -> bool {
+    pub fn contains(&self, point: i32) -> bool {
         self.wrapped.iter().any(|r| r.0 <= point && r.1 >= point)
     }
-    fn num(&self) -> usize {
+    /// How many numbers are in the set.
+    pub fn num(&self) -> usize {
         self.wrapped.iter().map(|s| (s.1 - s.0 + 1) as usize).sum()
     }
-    fn free_in(&self, min: i32, max: i32) -> Ranges {
+    /// The numbers from `min` to `max` that are not in the set.
+    pub fn free_in(&self, min: i32, max: i32) -> Ranges {
         let mut ret = Ranges{wrapped: vec![(min, max)]};
         for slice in self.wrapped.iter() {
             ret = ret.sub(slice);
         }
         ret
     }
-    fn all(&self) -> impl Iterator<Item=i32> + '_ {
+    pub fn all(&self) -> impl Iterator<Item=i32> + '_ {
         self.wrapped.iter().flat_map(|sub| sub.0..=sub.1)
     }
 }
 
-fn run(path: &str, row: i32, max: i32) -> Result<(), ErrorMsg> {
-    let sensors = utils::read_lines(path)?.map(|l| l?.parse::<Sensor>()).collect::<Result<Vec<Sensor>, ErrorMsg>>()?;
-    let mut beacons = sensors.iter().map(|s| s.beacon).collect::<Vec<Pos>>();
-    beacons.sort();
-    beacons.dedup();
-    let ranges_on = |y| sensors.iter()
+fn ranges_on(sensors: &[Sensor], y: i32) -> Ranges {
+    sensors.iter()
         .filter_map(|sensor| sensor.range_at(y).map(|r| (sensor.pos.x, r)))
-        .fold(Ranges{wrapped:Vec::new()}, |ranges, sensor| ranges.combine_with(sensor.0, sensor.1));
-    let ranges_on_dest_row = ranges_on(row);
-    let blocked_on_row = ranges_on_dest_row.num() - beacons.iter().filter(|b| b.y == row && ranges_on_dest_row.contains(b.x)).count();
-    let beancon_pos = (0..=max).flat_map(|y| ranges_on(y).free_in(0, max).all().map(|x| (x, y)).collect::<Vec<(i32, i32)>>());
-    Ok(println!("Blocked in row: {}, all possible beacon locations: {}", blocked_on_row, beancon_pos.map(|p| format!("{} ", p.0 as u64 * 4000000 + p.1 as u64)).collect::<String>()))
+        .fold(Ranges::new(), |ranges, sensor| ranges.combine_with(sensor.0, sensor.1))
+}
+
+/// `row` is the row in which to count the blocked positions, `max` bounds the search area for the distress beacon.
+pub struct Day15 {
+    pub row: i32,
+    pub max: i32
+}
+
+impl Solution for Day15 {
+    type Input = Vec<Sensor>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        parse_lines(input, |l| l.parse::<Sensor>())
+    }
+
+    fn part1(&self, sensors: &Self::Input) -> Result<Answer, Error> {
+        let mut beacons = sensors.iter().map(|s| s.beacon).collect::<Vec<_>>();
+        beacons.sort();
+        beacons.dedup();
+        let ranges_on_dest_row = ranges_on(sensors, self.row);
+        Ok((ranges_on_dest_row.num() - beacons.iter().filter(|b| b.y == self.row && ranges_on_dest_row.contains(b.x)).count()).into())
+    }
+
+    fn part2(&self, sensors: &Self::Input) -> Result<Answer, Error> {
+        let mut beacon_pos = (0..=self.max).flat_map(|y| ranges_on(sensors, y).free_in(0, self.max).all().map(|x| (x, y)).collect::<Vec<(i32, i32)>>());
+        let (x, y) = beacon_pos.next().ok_or(Error::no_solution("There is no place left for the distress beacon"))?;
+        Ok((x as u64 * 4000000 + y as u64).into())
+    }
+}
+
+/// The upper bound of the search area of the actual puzzle.
+const ACTUAL_MAX: i64 = 4000000;
+
+/// A sensor at `sensor` whose closest beacon is `distance` away in some direction.
+fn sensor_line(rng: &mut Rng, (x, y): (i64, i64), distance: i64) -> String {
+    let dx = rng.range(0..=distance);
+    let (dx, dy) = (dx * if rng.chance(50) {1} else {-1}, (distance - dx) * if rng.chance(50) {1} else {-1});
+    format!("Sensor at x={x}, y={y}: closest beacon is at x={}, y={}", x + dx, y + dy)
+}
+
+/// Generates `size` sensors scattered over the search area of the actual puzzle, plus four that together cover
+/// the whole area except for the position of the distress beacon.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let beacon = (rng.range(0..=ACTUAL_MAX), rng.range(0..=ACTUAL_MAX));
+    // Each of the four covers the quarter of the area towards itself, just not the beacon in the corner
+    let mut lines: Vec<String> = [(1, 1), (1, -1), (-1, 1), (-1, -1)].iter().map(|(sx, sy)| {
+        sensor_line(rng, (beacon.0 + sx * ACTUAL_MAX, beacon.1 + sy * ACTUAL_MAX), 2 * ACTUAL_MAX - 1)
+    }).collect();
+    while lines.len() < size + 4 {
+        let sensor = (rng.range(0..=ACTUAL_MAX), rng.range(0..=ACTUAL_MAX));
+        let to_beacon = (sensor.0 - beacon.0).abs() + (sensor.1 - beacon.1).abs();
+        if to_beacon < 2 { continue }
+        let distance = rng.range(1..=to_beacon - 1);
+        lines.push(sensor_line(rng, sensor, distance));
+    }
+    rng.shuffle(&mut lines);
+    lines.join("\n")
 }
\ No newline at end of file
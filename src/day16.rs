@@ -1,39 +1,31 @@
 use std::cmp::{min};
 use std::collections::HashMap;
+use std::iter;
 use std::str::FromStr;
-use crate::utils::ErrorMsg;
-use regex::Regex;
-use crate::utils;
+use crate::utils::{parse_lines, Error, Field, Pattern};
+use crate::solution::{Answer, Solution};
+use crate::generate::Rng;
 
-pub fn run_sample() {
-    ErrorMsg::print(run("input/day16_sample.txt"));
-}
-
-pub fn run_actual() {
-    ErrorMsg::print(run("input/day16.txt"));
-}
-
-struct Node {
+#[derive(Clone)]
+pub struct Node {
     flow_rate: u32,
     id: String,
     tunnels: Vec<String>
 }
 lazy_static! {
-    static ref NODE_REGEX: Regex = Regex::new(r"Valve (..) has flow rate=(\d+); tunnel(?:s?) lead(?:s?) to valve(?:s?) (.*)").unwrap();
+    static ref NODE: Pattern = Pattern::new("Valve {id} has flow rate={u32}; tunnel{_} lead{_} to valve{_} {}");
 }
 impl FromStr for Node {
-    type Err = ErrorMsg;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let node_captures = NODE_REGEX.captures(s).ok_or(ErrorMsg{wrapped:format!("Failed to capture node regex in {s}")})?;
-        let id = node_captures[1].to_string();
-        let flow_rate = node_captures[2].parse::<u32>()?;
-        let tunnels = node_captures[3].split(", ").map(|s|s.to_string()).collect();
+        let (id, flow_rate, tunnels): (String, u32, Field) = NODE.parse(s)?;
+        let tunnels = tunnels.list(", ", |s| Ok(s.to_string()))?;
         Ok(Node { flow_rate, id, tunnels })
     }
 }
 
-fn remove_node(edges: &mut HashMap<String,HashMap<String,u32>>, node: &Node) -> () {
+fn remove_node(edges: &mut HashMap<String,HashMap<String,u32>>, node: &Node) {
     for to in edges.values_mut() {
         to.remove(node.id.as_str());
     }
@@ -42,13 +34,13 @@ fn remove_node(edges: &mut HashMap<String,HashMap<String,u32>>, node: &Node) ->
 fn eval_subset(
     start: u16,
     subset: usize,
-    nodes: &Vec<(&Node, Vec<u32>)>,
+    nodes: &[(Node, Vec<u32>)],
     max_time: u32
 ) -> u32 {
     if subset == 0 { return 0 }
     (0..(nodes.len()-1))
         .map(|i| i+1)
-        .filter(|i| ((1 << i) & subset != 0))
+        .filter(|i| (1 << i) & subset != 0)
         .filter_map(|i| {
             let cost = nodes[start as usize].1[
                 if start == 0 {i-1}
@@ -58,12 +50,10 @@ fn eval_subset(
                 None
             } else {
                 let remaining_time = max_time - cost;
-                let next_node = nodes[i].0;
+                let next_node = &nodes[i].0;
                 let released_by_this = remaining_time * next_node.flow_rate;
                 let released_later = eval_subset(i as u16, subset & !(1<<i), nodes, remaining_time);
-                // if i == 3 {
-                //     println!("Expanding {i} aka {} at time {} yields {released_by_this} + {released_later}", next_node.id, 30 - remaining_time);
-                // }
+                trace!("Expanding {i} aka {} with {remaining_time} minutes left yields {released_by_this} + {released_later}", next_node.id);
                 Some(released_by_this + released_later)
             }
         }).max().unwrap_or(0)
@@ -74,51 +64,128 @@ fn to_str(edges: &HashMap<String,HashMap<String,u32>>) -> String {
     edges.iter().map(|e| format!("\n{} -> {}", e.0, e.1.iter().map(|ee| ee.0.clone()).collect::<String>())).collect::<String>()
 }
 
-fn run(path: &str) -> Result<(), ErrorMsg> {
-    let all_nodes = utils::read_lines(path)?.map(|l| l?.parse::<Node>())
-        .collect::<Result<Vec<Node>, ErrorMsg>>()?;
-    let nodes_map: HashMap<String, &Node> = all_nodes.iter().map(|n| (n.id.clone(), n)).collect();
-    let mut edges: HashMap<String,HashMap<String,u32>> = all_nodes.iter().map(|n| (n.id.clone(), n.tunnels.iter().map(|s| (s.clone(), 1u32)).collect())).collect();
-    println!("All edges: {}", to_str(&edges));
-    for _ in 0..all_nodes.len() {
-        for node in all_nodes.iter() {
-            for (i, from) in edges.get(node.id.as_str()).unwrap().iter().map(|t| (t.0.clone(), *t.1)).enumerate().collect::<Vec<(usize, (String, u32))>>() {
-                // if edges.get(node.tunnels[i].as_str()) == None { continue; }
-                let cost_from_i = edges.entry(from.0.clone()).or_default().get(node.id.as_str()).map(|v| *v);
-                let cost_to_i = edges.get(node.id.as_str()).unwrap().get(from.0.as_str()).map(|v| *v);
-                for to in edges.get(node.id.as_str()).unwrap().iter().skip(i+1).map(|t| (t.0.clone(), *t.1)).collect::<Vec<(String, u32)>>() {
-                    // if edges.get(node.tunnels[j].as_str()) == None { continue; }
-                    let cost_from_j = edges.entry(to.0.clone()).or_default().get(node.id.as_str()).map(|v| *v);
-                    let cost_to_j = edges.get(node.id.as_str()).unwrap().get(to.0.as_str()).map(|v| *v);
-                    if cost_from_i != None && cost_to_j != None {
-                        let e: &mut _ = edges.entry(from.0.clone()).or_default().entry(to.0.clone()).or_insert(10000);
-                        *e = min(*e, cost_from_i.unwrap() + cost_to_j.unwrap());
-                    } else {println!("There was no way from {} to {}", from.0, to.0)}
-                    if cost_from_j != None && cost_to_i != None {
-                        let e: &mut _ = edges.entry(to.0.clone()).or_default().entry(from.0.clone()).or_insert(10000);
-                        *e = min(*e, cost_from_j.unwrap() + cost_to_i.unwrap());
-                    } else {println!("There was no way from {} to {}", to.0, from.0)}
+/// The `minutes` until the volcano erupts in each part. In part 2, some were spent teaching the elephant.
+pub struct Day16 {
+    pub minutes: [u32; 2]
+}
+
+impl Solution for Day16 {
+    /// The valves with a positive flow rate (and the start "AA" at index 0),
+    /// each with the travel times to all the others except itself and the start.
+    type Input = Vec<(Node, Vec<u32>)>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        let all_nodes = parse_lines(input, |l| l.parse::<Node>())?;
+        let nodes_map: HashMap<String, &Node> = all_nodes.iter().map(|n| (n.id.clone(), n)).collect();
+        for ((i, line), node) in input.lines().enumerate().zip(&all_nodes) {
+            // The tunnels are listed last, after "to valve" or "to valves"
+            let mut offset = line.find(" to valve").unwrap_or(0);
+            for tunnel in &node.tunnels {
+                offset += line[offset..].find(tunnel.as_str()).unwrap_or(0);
+                if !nodes_map.contains_key(tunnel) {
+                    return Err(Error::parse_at(format!("There is no valve {tunnel}"), offset, tunnel.len()).on_line(i, line))
+                }
+                offset += tunnel.len();
+            }
+        }
+        if !nodes_map.contains_key("AA") {
+            return Err(Error::invalid("There is no valve AA to start at"))
+        }
+        let missing = |id: &str| Error::invalid(format!("There is no valve {id}"));
+        let mut edges: HashMap<String,HashMap<String,u32>> = all_nodes.iter().map(|n| (n.id.clone(), n.tunnels.iter().map(|s| (s.clone(), 1u32)).collect())).collect();
+        trace!("All edges: {}", to_str(&edges));
+        for _ in 0..all_nodes.len() {
+            for node in all_nodes.iter() {
+                for (i, from) in edges.get(node.id.as_str()).ok_or_else(|| missing(&node.id))?.iter().map(|t| (t.0.clone(), *t.1)).enumerate().collect::<Vec<(usize, (String, u32))>>() {
+                    // if edges.get(node.tunnels[i].as_str()) == None { continue; }
+                    let cost_from_i = edges.entry(from.0.clone()).or_default().get(node.id.as_str()).copied();
+                    let cost_to_i = edges.get(node.id.as_str()).ok_or_else(|| missing(&node.id))?.get(from.0.as_str()).copied();
+                    for to in edges.get(node.id.as_str()).ok_or_else(|| missing(&node.id))?.iter().skip(i+1).map(|t| (t.0.clone(), *t.1)).collect::<Vec<(String, u32)>>() {
+                        // if edges.get(node.tunnels[j].as_str()) == None { continue; }
+                        let cost_from_j = edges.entry(to.0.clone()).or_default().get(node.id.as_str()).copied();
+                        let cost_to_j = edges.get(node.id.as_str()).ok_or_else(|| missing(&node.id))?.get(to.0.as_str()).copied();
+                        if let (Some(cost_from_i), Some(cost_to_j)) = (cost_from_i, cost_to_j) {
+                            let e: &mut _ = edges.entry(from.0.clone()).or_default().entry(to.0.clone()).or_insert(10000);
+                            *e = min(*e, cost_from_i + cost_to_j);
+                        } else {trace!("There was no way from {} to {}", from.0, to.0)}
+                        if let (Some(cost_from_j), Some(cost_to_i)) = (cost_from_j, cost_to_i) {
+                            let e: &mut _ = edges.entry(to.0.clone()).or_default().entry(from.0.clone()).or_insert(10000);
+                            *e = min(*e, cost_from_j + cost_to_i);
+                        } else {trace!("There was no way from {} to {}", to.0, from.0)}
+                    }
                 }
             }
         }
+        for node in all_nodes.iter().filter(|n| n.flow_rate == 0) {
+            remove_node(&mut edges, node);
+            if node.id != "AA" { edges.remove(node.id.as_str()); }
+        }
+        debug!("Edges between valves with flow: {}", to_str(&edges));
+        let mut sorted_nodes = edges.keys().collect::<Vec<_>>();
+        sorted_nodes.sort();
+        let sorted_edges: Vec<(Node,Vec<u32>)> = sorted_nodes.iter().enumerate().map(|(i, &name)| Ok((
+            (*nodes_map.get(name).ok_or_else(|| missing(name))?).clone(),
+            sorted_nodes.iter().enumerate().skip(1).filter(|&(ii,_)| i != ii)
+                .map(|(_,&n)| edges.get(name).and_then(|to| to.get(n)).copied()
+                    .ok_or_else(|| Error::no_solution(format!("There is no way from valve {name} to {n}"))))
+                .collect::<Result<_, Error>>()?
+        ))).collect::<Result<_, Error>>()?;
+        debug!("{} valves are worth opening", sorted_edges.len() - 1);
+        Ok(sorted_edges)
+    }
+
+    fn part1(&self, sorted_edges: &Self::Input) -> Result<Answer, Error> {
+        let max_subset = (1<<(sorted_edges.len()))-1;
+        Ok(eval_subset(0, max_subset, sorted_edges, self.minutes[0]).into())
     }
-    for node in all_nodes.iter().filter(|n| n.flow_rate == 0) {
-        remove_node(&mut edges, node);
-        if node.id != "AA" { edges.remove(node.id.as_str()); }
+
+    fn part2(&self, sorted_edges: &Self::Input) -> Result<Answer, Error> {
+        let max_time = self.minutes[1];
+        let max_subset = (1<<(sorted_edges.len()))-1;
+        let total_pressure = (0..max_subset/2).map(|subset_l|
+            eval_subset(0, subset_l, sorted_edges, max_time)
+             + eval_subset(0, (!subset_l) & max_subset, sorted_edges, max_time)
+        ).max().ok_or(Error::invalid("There are no valves to open"))?;
+        Ok(total_pressure.into())
+    }
+}
+
+/// Generates `size` valves, at most one for every name of two letters. Like in the actual input, a quarter of them
+/// have a flow rate and are connected by corridors of valves without one. The valves with flow decide how long
+/// solving takes, so large sizes take very long.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut names: Vec<String> = (b'A'..=b'Z').flat_map(|a| (b'A'..=b'Z').map(move |b| String::from_utf8(vec![a, b]).expect("Letters are ASCII")))
+        .filter(|name| name != "AA").collect();
+    rng.shuffle(&mut names);
+    names.truncate(size.clamp(2, names.len() + 1) - 1);
+    names.insert(0, "AA".to_string());
+    // The start and the valves with flow come first
+    let junctions = (names.len() / 4 + 1).max(2);
+    let mut corridors: Vec<(usize, usize, Vec<usize>)> = (1..junctions).map(|i| (i, rng.below(i), Vec::new())).collect();
+    for _ in 0..junctions / 4 {
+        let (a, b) = (rng.below(junctions), rng.below(junctions));
+        if a != b { corridors.push((a, b, Vec::new())) }
+    }
+    for valve in junctions..names.len() {
+        let corridor = rng.below(corridors.len());
+        corridors[corridor].2.push(valve);
+    }
+    let mut tunnels: Vec<Vec<usize>> = vec![Vec::new(); names.len()];
+    for (from, to, between) in corridors {
+        let path: Vec<usize> = iter::once(from).chain(between).chain(iter::once(to)).collect();
+        for pair in path.windows(2) {
+            if !tunnels[pair[0]].contains(&pair[1]) {
+                tunnels[pair[0]].push(pair[1]);
+                tunnels[pair[1]].push(pair[0]);
+            }
+        }
     }
-    println!("All edges: {}", to_str(&edges));
-    let mut sorted_nodes = edges.iter().map(|(name, _)| name).collect::<Vec<_>>();
-    sorted_nodes.sort();
-    let sorted_edges: Vec<(&Node,Vec<u32>)> = sorted_nodes.iter().enumerate().map(|(i, &name)| (
-        nodes_map[name],
-        sorted_nodes.iter().enumerate().skip(1).filter(|&(ii,_)| i != ii).map(|(_,&n)| edges[name][n]).collect()
-    )).collect();
-    let max_time = 26;
-    println!("edges: {}", sorted_edges.len());
-    let max_subset = (1<<(sorted_edges.len()))-1;
-    let total_pressure = (0..max_subset/2).map(|subset_l|
-        eval_subset(0, subset_l, &sorted_edges, max_time)
-         + eval_subset(0, (!subset_l) & max_subset, &sorted_edges, max_time)
-    ).max().unwrap();
-    Ok(println!("Total pressure: {}", total_pressure))
+    let mut lines: Vec<String> = names.iter().enumerate().map(|(i, name)| {
+        let flow_rate = if (1..junctions).contains(&i) { rng.range(3..=25) } else { 0 };
+        let to: Vec<&str> = tunnels[i].iter().map(|&other| names[other].as_str()).collect();
+        let leads = if to.len() == 1 { "tunnel leads to valve" } else { "tunnels lead to valves" };
+        format!("Valve {name} has flow rate={flow_rate}; {leads} {}", to.join(", "))
+    }).collect();
+    rng.shuffle(&mut lines);
+    lines.join("\n")
 }
\ No newline at end of file
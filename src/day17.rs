@@ -1,112 +1,141 @@
-use std::cmp::{max, min};
 use std::collections::HashMap;
-use std::str::FromStr;
-use crate::utils;
-use crate::utils::ErrorMsg;
+use crate::solution::{Answer, Solution};
+use crate::utils::{char_map, Error, Grid, GrowingGrid};
+use crate::visualize::{Recorder, Visualize};
+use crate::generate::Rng;
 
-pub fn run_sample() {
-    ErrorMsg::print(run("input/day17_sample.txt"));
+const CHAMBER_WIDTH: i64 = 7;
+/// Towers that grow this high without a completely filled row are unlikely to ever repeat, so `generate` drops their patterns.
+const MAX_HEIGHT_WITHOUT_REPEAT: i64 = 1 << 16;
+/// How many patterns `generate` tries before it settles for one that never repeats.
+const GENERATE_ATTEMPTS: usize = 100;
+/// How much of the top of the chamber frames show.
+const VISIBLE_ROWS: i64 = 40;
+
+lazy_static! {
+    /// The rocks in the order they fall, drawn like in the puzzle.
+    static ref ROCKS: Vec<Grid<bool>> = [
+        "####",
+        ".#.\n###\n.#.",
+        "..#\n..#\n###",
+        "#\n#\n#\n#",
+        "##\n##"
+    ].iter().map(|rock| Grid::parse(rock, |c| Ok(c == '#')).unwrap()).collect();
 }
 
-pub fn run_actual() {
-    ErrorMsg::print(run("input/day17.txt"));
+/// The chamber is stored bottom up, so a rock's top row ends up at the highest y.
+fn rock_cells(rock: &Grid<bool>, pos: (i64, i64)) -> impl Iterator<Item=(i64, i64)> + '_ {
+    rock.iter().filter(|(_, &is_rock)| is_rock)
+        .map(move |((x, y), _)| (pos.0 + x as i64, pos.1 + (rock.height() - 1 - y) as i64))
 }
 
-fn run(path: &str) -> Result<(), ErrorMsg> {
-    let rocks = vec![
-        (vec![
-            0b1111u8
-        ], 4u8),(vec![
-            0b010u8,
-            0b111u8,
-            0b010u8
-        ], 3u8),(vec![
-            0b111u8,
-            0b001u8,
-            0b001u8
-        ], 3u8),(vec![
-            0b1u8,
-            0b1u8,
-            0b1u8,
-            0b1u8
-        ], 1u8),(vec![
-            0b11u8,
-            0b11u8
-        ], 2u8)
-    ];
-    let mut chamber: Vec<u8> = Vec::new();
-    let wind_dirs = utils::read_lines(path)?.nth(0)
-        .ok_or(ErrorMsg::new("Input had no lines"))??
-        .chars().map(|c| match c {
-            '<' => Ok(-1),
-            '>' => Ok(1),
-            other => Err(ErrorMsg{wrapped:format!("No wind dir: {other}")})
-        }).collect::<Result<Vec<i16>, ErrorMsg>>()?;
+fn overlaps(chamber: &GrowingGrid<bool>, rock: &Grid<bool>, pos: (i64, i64)) -> bool {
+    rock_cells(rock, pos).any(|(x, y)| !(0..CHAMBER_WIDTH).contains(&x) || y < 0 || *chamber.get(x, y))
+}
 
-    fn overlaps(chamber: &Vec<u8>, rock: &(Vec<u8>, u8), pos: (i16,i64)) -> bool {
-        if pos.0 < 0 || (rock.1 + pos.0 as u8) > 7u8 || pos.1 < 0 { return true; }
-        if pos.1 >= chamber.len() as i64 {return false;}
-        for y in 0..min(rock.0.len(), chamber.len() - pos.1 as usize) {
-            if rock.0[y] << (7 - pos.0 as u8 - rock.1) & chamber[y + pos.1 as usize] != 0 {return true;}
-        }
-        return false;
-    }
+/// The top of the chamber the way the puzzle draws it, with the floor or the highest rows at the bottom.
+fn draw_chamber(chamber: &GrowingGrid<bool>, height: i64) -> Grid<char> {
+    let rows = (height.saturating_sub(VISIBLE_ROWS).max(0)..height + 3).rev()
+        .map(|y| (0..CHAMBER_WIDTH).map(|x| if *chamber.get(x, y) {'#'} else {'.'}).collect())
+        .collect();
+    Grid::from_rows(rows).expect("All rows are as wide as the chamber")
+}
+
+/// Lets `total_iteration_count` rocks fall. With a `max_height`, gives up once the tower grows past it without a
+/// completely filled row.
+fn tower_height(wind_dirs: &[i16], total_iteration_count: u64, max_height: Option<i64>, recorder: &mut Recorder) -> Result<usize, Error> {
+    let mut chamber = GrowingGrid::new(false);
+    let mut chamber_height = 0i64;
     let mut height_offset = 0;
     let mut current_rock_index = 0usize;
     let mut current_wind_index = 0usize;
     let mut skip_cache: HashMap<(usize, usize), (u64, usize)> = HashMap::new();
-    // let total_iteration_count = 10u64;
-    // let total_iteration_count = 2022u64;
-    let total_iteration_count = 1000000000000u64;
     let mut i = 0;
     while i < total_iteration_count {
-        if chamber.len() > 0 && *chamber.last().unwrap() == 0b1111111u8 {
-            println!("Cleanung up at i={i}, height={}, rock is {}, wind index is {current_wind_index}", chamber.len(), current_rock_index);
-            height_offset += chamber.len();
-            chamber.clear();
+        if chamber_height > 0 && (0..CHAMBER_WIDTH).all(|x| *chamber.get(x, chamber_height - 1)) {
+            debug!("Cleaning up at i={i}, height={chamber_height}, rock is {current_rock_index}, wind index is {current_wind_index}");
+            height_offset += chamber_height as usize;
+            chamber = GrowingGrid::new(false);
+            chamber_height = 0;
             if let Some((last_i, last_height)) = skip_cache.get(&(current_rock_index, current_wind_index)) {
-                println!("Last skip cache was {current_rock_index}, {current_wind_index}, {last_i} {last_height}");
-                // let repetitions = 2;
+                debug!("Seen rock {current_rock_index} and wind {current_wind_index} before at i={last_i}, height {last_height}");
                 let diff = height_offset - last_height;
                 let repetitions = (total_iteration_count - i) / (i - last_i);
-                println!("Was at {i}, {height_offset}, skip {repetitions} iterations to {} with {}", i + repetitions * (i - last_i), height_offset + diff * repetitions as usize);
+                debug!("Was at {i}, {height_offset}, skip {repetitions} iterations to {} with {}", i + repetitions * (i - last_i), height_offset + diff * repetitions as usize);
                 height_offset += diff * repetitions as usize;
                 i = i + repetitions * (i - last_i);
-                // last_skip_cache = Some((current_rock_index, current_wind_index, i));
                 continue;
             }
             skip_cache.insert((current_rock_index, current_wind_index), (i, height_offset));
-            // println!("New skip cache is {current_rock_index}, {current_wind_index}, {new_offset}, {i}");
         }
-        if i > 0 && i & (i - 1) == 0 { println!("Loop {i}")}
-        let current_rock = &rocks[current_rock_index];
-        current_rock_index = (current_rock_index+1) % rocks.len();
-        let mut rock_pos: (i16,i64) = (2, chamber.len() as i64+3);
-        // rock_pos.0 +=
-        //     wind_dirs[current_wind_index] +
-        //     wind_dirs[(current_wind_index+1) % wind_dirs.len()] +
-        //     wind_dirs[(current_wind_index+2) % wind_dirs.len()];
-        // current_wind_index = (current_wind_index+3) % wind_dirs.len();
-        // if rock_pos.0 < 0 { rock_pos.0 = 0;}
-        // else if rock_pos.0 + current_rock.1 as i16 > 7 { rock_pos.0 = 7 - current_rock.1 as i16; }
+        if max_height.is_some_and(|max_height| chamber_height > max_height) {
+            return Err(Error::no_solution(format!("The tower grew to {chamber_height} without a completely filled row to repeat from")))
+        }
+        if i > 0 && i & (i - 1) == 0 { trace!("Loop {i}") }
+        let current_rock = &ROCKS[current_rock_index];
+        current_rock_index = (current_rock_index+1) % ROCKS.len();
+        let mut rock_pos = (2, chamber_height + 3);
         loop {
-            let new_pos = (rock_pos.0 + wind_dirs[current_wind_index], rock_pos.1);
+            let new_pos = (rock_pos.0 + wind_dirs[current_wind_index] as i64, rock_pos.1);
             current_wind_index = (current_wind_index+1) % wind_dirs.len();
-            if !overlaps(&chamber, &current_rock, new_pos) {
+            if !overlaps(&chamber, current_rock, new_pos) {
                 rock_pos = new_pos;
             }
             let new_pos = (rock_pos.0, rock_pos.1 - 1);
-            if overlaps(&chamber, &current_rock, new_pos) {
-                for _ in (chamber.len() as i64)..(rock_pos.1 + current_rock.0.len() as i64) { chamber.push(0) }
-                for y in 0..current_rock.0.len() {
-                    chamber[y + rock_pos.1 as usize] |= current_rock.0[y] << (7 - rock_pos.0 as u8 - current_rock.1);
+            if overlaps(&chamber, current_rock, new_pos) {
+                for (x, y) in rock_cells(current_rock, rock_pos) {
+                    chamber.set(x, y, true);
+                    chamber_height = chamber_height.max(y + 1);
                 }
+                recorder.tick(|| draw_chamber(&chamber, chamber_height))?;
                 break;
             }
             rock_pos = new_pos;
         }
-        i = i + 1;
+        i += 1;
+    }
+    Ok(chamber_height as usize + height_offset)
+}
+
+/// How many `rocks` fall in each part.
+pub struct Day17 {
+    pub rocks: [u64; 2],
+    pub visualize: Option<Visualize>
+}
+
+impl Solution for Day17 {
+    type Input = Vec<i16>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        let line = input.lines().next().ok_or(Error::parse("Input had no lines"))?;
+        if line.is_empty() {
+            return Err(Error::parse("The jet pattern is empty").on_line(0, line))
+        }
+        let wind = char_map(&[('<', -1), ('>', 1)]);
+        line.char_indices().map(|(i, c)| wind(c).map_err(|err| err.located(i, 1).on_line(0, line))).collect()
+    }
+
+    fn part1(&self, wind_dirs: &Self::Input) -> Result<Answer, Error> {
+        Ok(tower_height(wind_dirs, self.rocks[0], None, &mut Recorder::start(self.visualize.as_ref(), "part1")?)?.into())
+    }
+
+    fn part2(&self, wind_dirs: &Self::Input) -> Result<Answer, Error> {
+        Ok(tower_height(wind_dirs, self.rocks[1], None, &mut Recorder::start(self.visualize.as_ref(), "part2")?)?.into())
+    }
+}
+
+/// Generates a pattern of `size` jets. Part 2 needs the tower to repeat from a completely filled row, which only
+/// some patterns ever make, so the others are dropped. Patterns of less than 50 jets are taken as they are,
+/// since hardly any of them works, and after `GENERATE_ATTEMPTS` patterns the last one is taken anyway.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let jets = |rng: &mut Rng| (0..size.max(1)).map(|_| if rng.chance(50) {'<'} else {'>'}).collect::<String>();
+    let repeats = |input: &str| Day17 { rocks: [0, 0], visualize: None }.parse(input)
+        .and_then(|wind_dirs| tower_height(&wind_dirs, 1000000000000, Some(MAX_HEIGHT_WITHOUT_REPEAT), &mut Recorder::disabled()))
+        .is_ok();
+    let mut input = jets(rng);
+    for _ in 1..GENERATE_ATTEMPTS {
+        if size < 50 || repeats(&input) { break }
+        input = jets(rng);
     }
-    // println!("{}", (0..chamber.len()).rev().map(|y| (0..7).rev().map(|x| if chamber[y] & 1 << x != 0 {'#'} else {'.'}).collect::<String>() + "\n").collect::<String>());
-    Ok(println!("Height: {}", chamber.len() + height_offset))
+    input
 }
\ No newline at end of file
@@ -1,18 +1,9 @@
-use std::cmp::max;
 use std::collections::HashMap;
 use std::ops::{Add, Sub};
-use crate::utils::ErrorMsg;
+use crate::utils::{parse_lines, Error, Pattern};
 use std::str::FromStr;
-use regex::Regex;
-use crate::utils;
-
-pub fn run_sample() {
-    ErrorMsg::print(run("input/day19_sample.txt", false));
-}
-
-pub fn run_actual() {
-    ErrorMsg::print(run("input/day19.txt", true));
-}
+use crate::solution::{Answer, Solution};
+use crate::generate::Rng;
 
 #[derive(Default, Clone, Copy, Eq, PartialEq, Hash)]
 struct Material {
@@ -48,16 +39,12 @@ impl Material {
 }
 
 lazy_static! {
-    static ref ORE_REGEX: Regex = Regex::new(r"Each ore robot costs (\d+) ore.").unwrap();
-    static ref CLAY_REGEX: Regex = Regex::new(r"Each clay robot costs (\d+) ore.").unwrap();
-    static ref OBSIDIAN_REGEX: Regex = Regex::new(r"Each obsidian robot costs (\d+) ore and (\d+) clay.").unwrap();
-    static ref GEODE_REGEX: Regex = Regex::new(r"Each geode robot costs (\d+) ore and (\d+) obsidian.").unwrap();
+    static ref BLUEPRINT: Pattern = Pattern::new(concat!(
+        "Blueprint {u32}: Each ore robot costs {u32} ore. Each clay robot costs {u32} ore. ",
+        "Each obsidian robot costs {u32} ore and {u32} clay. Each geode robot costs {u32} ore and {u32} obsidian."
+    ));
 }
-struct Blueprint {
-    ore_robot_cost: Material,
-    clay_robot_cost: Material,
-    obsidian_robot_cost: Material,
-    geode_robot_cost: Material,
+pub struct Blueprint {
     expand_options_including_null: [(Material, Material); 5],
     expand_options_excluding_null: [(Material, Material); 4]
 }
@@ -68,10 +55,6 @@ impl Blueprint {
        obsidian_robot_cost: Material,
        geode_robot_cost: Material
     ) -> Blueprint { Blueprint {
-        ore_robot_cost,
-        clay_robot_cost,
-        obsidian_robot_cost,
-        geode_robot_cost,
         expand_options_including_null: [
             (ore_robot_cost, Material{ore: 1, ..Default::default()}),
             (clay_robot_cost, Material{clay: 1, ..Default::default()}),
@@ -88,22 +71,22 @@ impl Blueprint {
     }}
 }
 impl FromStr for Blueprint {
-    type Err = ErrorMsg;
+    type Err = Error;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let ore = ORE_REGEX.captures(s).ok_or(ErrorMsg{wrapped:format!("No ore robot cost found in {s}")})?;
-        let clay = CLAY_REGEX.captures(s).ok_or(ErrorMsg{wrapped:format!("No clay robot cost found in {s}")})?;
-        let obsidian = OBSIDIAN_REGEX.captures(s).ok_or(ErrorMsg{wrapped:format!("No obsidian robot cost found in {s}")})?;
-        let geode = GEODE_REGEX.captures(s).ok_or(ErrorMsg{wrapped:format!("No geode robot cost found in {s}")})?;
+        let (_, ore, clay, obsidian_ore, obsidian_clay, geode_ore, geode_obsidian): (u32, _, _, _, _, _, _) = BLUEPRINT.parse(s)?;
         Ok(Blueprint::new(
-            Material {ore: ore[1].parse()?, ..Default::default()},
-            Material {ore: clay[1].parse()?, ..Default::default()},
-            Material {ore: obsidian[1].parse()?, clay: obsidian[2].parse()?, ..Default::default()},
-            Material {ore: geode[1].parse()?, obsidian: geode[2].parse()?, ..Default::default()}
+            Material {ore, ..Default::default()},
+            Material {ore: clay, ..Default::default()},
+            Material {ore: obsidian_ore, clay: obsidian_clay, ..Default::default()},
+            Material {ore: geode_ore, obsidian: geode_obsidian, ..Default::default()}
         ))
     }
 }
 
-fn max_geodes_for(blueprint: &Blueprint, current_materials: Material, current_robots: Material, time_left: u32, cache: &mut HashMap<((u32, u32, u32),(u32, u32, u32),u32),u32>) -> u32 {
+/// Maps (materials, robots, time left) - ignoring geodes - to the number of geodes gained from there on.
+type GeodeCache = HashMap<((u32, u32, u32),(u32, u32, u32),u32),u32>;
+
+fn max_geodes_for(blueprint: &Blueprint, current_materials: Material, current_robots: Material, time_left: u32, cache: &mut GeodeCache) -> u32 {
     if time_left == 0 { return current_materials.geode; }
     if let Some(res) = cache.get(&(current_materials.except_geodes(), current_robots.except_geodes(), time_left)) {
         return *res + current_materials.geode + current_robots.geode * time_left
@@ -124,12 +107,19 @@ fn max_geodes_for(blueprint: &Blueprint, current_materials: Material, current_ro
     res
 }
 
-fn run(path: &str, part2: bool) -> Result<(), ErrorMsg> {
-    let blueprints = utils::read_lines(path)?.map(|l| l?.parse()).collect::<Result<Vec<Blueprint>, ErrorMsg>>()?;
-    if !part2 {
+pub struct Day19;
+
+impl Solution for Day19 {
+    type Input = Vec<Blueprint>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        parse_lines(input, |l| l.parse())
+    }
+
+    fn part1(&self, blueprints: &Self::Input) -> Result<Answer, Error> {
         let mut sum = 0;
         for (i, blueprint) in blueprints.iter().enumerate() {
-            println!("STarting blueprint {i}");
+            info!("Starting blueprint {}", i + 1);
             let max_geodes = max_geodes_for(
                 blueprint,
                 Material { ..Default::default() },
@@ -137,14 +127,16 @@ fn run(path: &str, part2: bool) -> Result<(), ErrorMsg> {
                 24,
                 &mut HashMap::new()
             );
-            println!("Max was {max_geodes}");
+            info!("Blueprint {} opens at most {max_geodes} geodes", i + 1);
             sum += (i as u32 + 1) * max_geodes;
         }
-        Ok(println!("Result: {sum}"))
-    } else {
+        Ok(sum.into())
+    }
+
+    fn part2(&self, blueprints: &Self::Input) -> Result<Answer, Error> {
         let mut prod = 1;
-        for blueprint in blueprints.iter().take(3) {
-            println!("STarting blueprint");
+        for (i, blueprint) in blueprints.iter().take(3).enumerate() {
+            info!("Starting blueprint {}", i + 1);
             let max_geodes = max_geodes_for(
                 blueprint,
                 Material { ..Default::default() },
@@ -152,9 +144,19 @@ fn run(path: &str, part2: bool) -> Result<(), ErrorMsg> {
                 32,
                 &mut HashMap::new()
             );
-            println!("Max was {max_geodes}");
+            info!("Blueprint {} opens at most {max_geodes} geodes", i + 1);
             prod *= max_geodes;
         }
-        Ok(println!("Result: {prod}"))
+        Ok(prod.into())
     }
+}
+
+/// Generates `size` blueprints with costs in the ranges of the actual puzzle. Cheap obsidian robots are left out,
+/// since they make for huge numbers of geodes that take very long to search.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (1..=size.max(1)).map(|i| format!(
+        "Blueprint {i}: Each ore robot costs {} ore. Each clay robot costs {} ore. Each obsidian robot costs {} ore and {} clay. \
+         Each geode robot costs {} ore and {} obsidian.",
+        rng.range(2..=4), rng.range(2..=4), rng.range(2..=4), rng.range(8..=20), rng.range(2..=4), rng.range(7..=20)
+    )).collect::<Vec<_>>().join("\n")
 }
\ No newline at end of file
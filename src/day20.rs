@@ -1,27 +1,19 @@
-use crate::utils;
-use crate::utils::ErrorMsg;
+use crate::solution::{Answer, Solution};
+use crate::utils::{parse_lines, parse_num, Error};
+use crate::generate::Rng;
 
-pub fn run_sample() {
-    ErrorMsg::print(run("input/day20_sample.txt", true));
-}
-
-pub fn run_actual() {
-    ErrorMsg::print(run("input/day20.txt", true));
-}
-
-fn run(path: &str, part_2: bool) -> Result<(), ErrorMsg> {
-    let numbers = utils::read_lines(path)?.map(|s| Ok(
-        s?.parse::<i64>()? * (if part_2 {811589153} else {1})
-    )).collect::<Result<Vec<i64>, ErrorMsg>>()?;
+/// Mixes the file the given number of rounds after applying the decryption key and returns the grove coordinates.
+fn grove_coordinates(encrypted: &[i64], key: i64, rounds: usize) -> Result<i64, Error> {
+    let numbers = encrypted.iter().map(|n| n * key).collect::<Vec<i64>>();
     let mut index_for_num = (0..numbers.len()).collect::<Vec<_>>();
     let mut num_for_index = (0..numbers.len()).collect::<Vec<_>>();
-    for outer_i in 0..(if part_2 {10} else {1})
+    for round in 0..rounds
     {for (i, num) in numbers.iter().enumerate() {
         let prev_index = index_for_num[i];
         let mut new_index_oob = prev_index as i64 + num;
         let max_i = numbers.len() as i64 - 1;
         if new_index_oob < 0 { new_index_oob = (new_index_oob % max_i) + max_i }
-        else if new_index_oob >= numbers.len() as i64 { new_index_oob = new_index_oob % max_i }
+        else if new_index_oob >= numbers.len() as i64 { new_index_oob %= max_i }
         let new_index = new_index_oob as usize;
         let should_be_i = num_for_index.remove(prev_index);
         assert_eq!(should_be_i, i);
@@ -36,14 +28,44 @@ fn run(path: &str, part_2: bool) -> Result<(), ErrorMsg> {
             }
         }
         index_for_num[i] = new_index;
-        // println!("{outer_i}:{i}: Nums: {}", num_for_index.iter().map(|i|numbers[*i].to_string() + " ").collect::<String>());
-        // println!("{outer_i}:{i}: {}", num_for_index.iter().map(|i|i.to_string() + " ").collect::<String>());
-        // println!("{outer_i}:{i}: {}", index_for_num.iter().map(|i|i.to_string() + " ").collect::<String>())
+        trace!("{round}:{i}: Nums: {}", num_for_index.iter().map(|i|numbers[*i].to_string() + " ").collect::<String>());
     }
     }
-    let index_of_zero = index_for_num[numbers.iter().position(|&n| n == 0).unwrap()];
+    let index_of_zero = index_for_num[numbers.iter().position(|&n| n == 0).ok_or(Error::invalid("There is no zero in the file"))?];
     let a = numbers[num_for_index[(index_of_zero + 1000) % numbers.len()]];
     let b = numbers[num_for_index[(index_of_zero + 2000) % numbers.len()]];
     let c = numbers[num_for_index[(index_of_zero + 3000) % numbers.len()]];
-    Ok(println!("index_of_zero: {index_of_zero}, a: {a}, b: {b}, c: {c}, sum: {}", a+b+c))
+    Ok(a + b + c)
+}
+
+pub struct Day20;
+
+impl Solution for Day20 {
+    type Input = Vec<i64>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        let numbers = parse_lines(input, parse_num)?;
+        if numbers.len() < 2 {
+            return Err(Error::invalid(format!("Mixing needs at least 2 numbers, found {}", numbers.len())))
+        }
+        Ok(numbers)
+    }
+
+    fn part1(&self, numbers: &Self::Input) -> Result<Answer, Error> {
+        Ok(grove_coordinates(numbers, 1, 1)?.into())
+    }
+
+    fn part2(&self, numbers: &Self::Input) -> Result<Answer, Error> {
+        Ok(grove_coordinates(numbers, 811589153, 10)?.into())
+    }
+}
+
+/// Generates an encrypted file of `size` numbers, exactly one of which is 0.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut numbers: Vec<i64> = (1..size.max(1)).map(|_| {
+        let num = rng.range(1..=10000);
+        if rng.chance(50) { num } else { -num }
+    }).collect();
+    numbers.insert(rng.below(numbers.len() + 1), 0);
+    numbers.iter().map(|num| num.to_string()).collect::<Vec<_>>().join("\n")
 }
\ No newline at end of file
@@ -1,28 +1,26 @@
 use std::collections::HashMap;
-use crate::utils;
-use crate::utils::ErrorMsg;
+use crate::solution::{Answer, Solution};
+use crate::utils::{parse_lines, parse_num, Error, Field, Pattern};
 use std::str::FromStr;
+use crate::generate::Rng;
+use std::collections::HashSet;
 
-pub fn run_sample() {
-    ErrorMsg::print(run("input/day21_sample.txt"));
-}
-
-pub fn run_actual() {
-    ErrorMsg::print(run("input/day21.txt"));
+fn divide(dividend: i64, divisor: i64) -> Result<i64, Error> {
+    dividend.checked_div(divisor).ok_or(Error::invalid(format!("Cannot divide {dividend} by {divisor}")))
 }
 
 trait Expression {
-    fn eval(&self, expressions: &HashMap<String, Box<dyn Expression>>) -> Result<i64, ErrorMsg>;
-    fn inv_eval(&self, expressions: &HashMap<String, Box<dyn Expression>>, expected_res: i64, to_expand: Vec<String>) -> Result<i64, ErrorMsg>;
+    fn eval(&self, expressions: &HashMap<String, Box<dyn Expression>>) -> Result<i64, Error>;
+    fn inv_eval(&self, expressions: &HashMap<String, Box<dyn Expression>>, expected_res: i64, to_expand: Vec<String>) -> Result<i64, Error>;
     fn children(&self) -> Vec<String>;
 }
 struct Lit { val: i64 }
 impl Expression for Lit { 
-    fn eval(&self, _: &HashMap<String, Box<dyn Expression>>) -> Result<i64, ErrorMsg> {
+    fn eval(&self, _: &HashMap<String, Box<dyn Expression>>) -> Result<i64, Error> {
         Ok(self.val) 
     }
 
-    fn inv_eval(&self, _: &HashMap<String, Box<dyn Expression>>, expected_res: i64, to_expand: Vec<String>) -> Result<i64, ErrorMsg> {
+    fn inv_eval(&self, _: &HashMap<String, Box<dyn Expression>>, expected_res: i64, to_expand: Vec<String>) -> Result<i64, Error> {
         assert!(to_expand.is_empty());
         Ok(expected_res)
     }
@@ -31,130 +29,225 @@ impl Expression for Lit {
 }
 struct Add { l: String, r: String }
 impl Expression for Add { 
-    fn eval(&self, expressions: &HashMap<String, Box<dyn Expression>>) -> Result<i64, ErrorMsg> { 
+    fn eval(&self, expressions: &HashMap<String, Box<dyn Expression>>) -> Result<i64, Error> { 
         Ok(expressions[&self.l].eval(expressions)? + expressions[&self.r].eval(expressions)?)
     }
 
-    fn inv_eval(&self, expressions: &HashMap<String, Box<dyn Expression>>, expected_res: i64, mut to_expand: Vec<String>) -> Result<i64, ErrorMsg> {
-        let e = to_expand.pop().ok_or(ErrorMsg::new("Unexpected end of expansion"))?;
+    fn inv_eval(&self, expressions: &HashMap<String, Box<dyn Expression>>, expected_res: i64, mut to_expand: Vec<String>) -> Result<i64, Error> {
+        let e = to_expand.pop().ok_or(Error::invalid("Unexpected end of expansion"))?;
         if self.l == e {
             expressions[&self.l].inv_eval(expressions, expected_res - expressions[&self.r].eval(expressions)?, to_expand)
         } else if self.r == e {
             expressions[&self.r].inv_eval(expressions, expected_res - expressions[&self.l].eval(expressions)?, to_expand)
-        } else { Err(ErrorMsg::new("Failed to expand: Queried entry was not present")) }
+        } else { Err(Error::invalid("Failed to expand: Queried entry was not present")) }
     }
 
     fn children(&self) -> Vec<String> { vec![self.l.clone(), self.r.clone()] }
 }
 struct Mul { l: String, r: String }
 impl Expression for Mul {
-    fn eval(&self, expressions: &HashMap<String, Box<dyn Expression>>) -> Result<i64, ErrorMsg> {
+    fn eval(&self, expressions: &HashMap<String, Box<dyn Expression>>) -> Result<i64, Error> {
         Ok(expressions[&self.l].eval(expressions)? * expressions[&self.r].eval(expressions)?)
     }
 
-    fn inv_eval(&self, expressions: &HashMap<String, Box<dyn Expression>>, expected_res: i64, mut to_expand: Vec<String>) -> Result<i64, ErrorMsg> {
-        let e = to_expand.pop().ok_or(ErrorMsg::new("Unexpected end of expansion"))?;
+    fn inv_eval(&self, expressions: &HashMap<String, Box<dyn Expression>>, expected_res: i64, mut to_expand: Vec<String>) -> Result<i64, Error> {
+        let e = to_expand.pop().ok_or(Error::invalid("Unexpected end of expansion"))?;
         if self.l == e {
-            expressions[&self.l].inv_eval(expressions, expected_res / expressions[&self.r].eval(expressions)?, to_expand)
+            expressions[&self.l].inv_eval(expressions, divide(expected_res, expressions[&self.r].eval(expressions)?)?, to_expand)
         } else if self.r == e {
-            expressions[&self.r].inv_eval(expressions, expected_res / expressions[&self.l].eval(expressions)?, to_expand)
-        } else { Err(ErrorMsg::new("Failed to expand: Queried entry was not present")) }
+            expressions[&self.r].inv_eval(expressions, divide(expected_res, expressions[&self.l].eval(expressions)?)?, to_expand)
+        } else { Err(Error::invalid("Failed to expand: Queried entry was not present")) }
     }
 
     fn children(&self) -> Vec<String> { vec![self.l.clone(), self.r.clone()] }
 }
 struct Sub { l: String, r: String }
 impl Expression for Sub {
-    fn eval(&self, expressions: &HashMap<String, Box<dyn Expression>>) -> Result<i64, ErrorMsg> {
+    fn eval(&self, expressions: &HashMap<String, Box<dyn Expression>>) -> Result<i64, Error> {
         Ok(expressions[&self.l].eval(expressions)? - expressions[&self.r].eval(expressions)?)
     }
 
-    fn inv_eval(&self, expressions: &HashMap<String, Box<dyn Expression>>, expected_res: i64, mut to_expand: Vec<String>) -> Result<i64, ErrorMsg> {
-        let e = to_expand.pop().ok_or(ErrorMsg::new("Unexpected end of expansion"))?;
+    fn inv_eval(&self, expressions: &HashMap<String, Box<dyn Expression>>, expected_res: i64, mut to_expand: Vec<String>) -> Result<i64, Error> {
+        let e = to_expand.pop().ok_or(Error::invalid("Unexpected end of expansion"))?;
         if self.l == e {
             expressions[&self.l].inv_eval(expressions, expected_res + expressions[&self.r].eval(expressions)?, to_expand)
         } else if self.r == e {
             expressions[&self.r].inv_eval(expressions, expressions[&self.l].eval(expressions)? - expected_res, to_expand)
-        } else { Err(ErrorMsg::new("Failed to expand: Queried entry was not present")) }
+        } else { Err(Error::invalid("Failed to expand: Queried entry was not present")) }
     }
 
     fn children(&self) -> Vec<String> { vec![self.l.clone(), self.r.clone()] }
 }
 struct Div { l: String, r: String }
 impl Expression for Div {
-    fn eval(&self, expressions: &HashMap<String, Box<dyn Expression>>) -> Result<i64, ErrorMsg> {
-        Ok(expressions[&self.l].eval(expressions)? / expressions[&self.r].eval(expressions)?)
+    fn eval(&self, expressions: &HashMap<String, Box<dyn Expression>>) -> Result<i64, Error> {
+        divide(expressions[&self.l].eval(expressions)?, expressions[&self.r].eval(expressions)?)
     }
 
-    fn inv_eval(&self, expressions: &HashMap<String, Box<dyn Expression>>, expected_res: i64, mut to_expand: Vec<String>) -> Result<i64, ErrorMsg> {
-        let e = to_expand.pop().ok_or(ErrorMsg::new("Unexpected end of expansion"))?;
+    fn inv_eval(&self, expressions: &HashMap<String, Box<dyn Expression>>, expected_res: i64, mut to_expand: Vec<String>) -> Result<i64, Error> {
+        let e = to_expand.pop().ok_or(Error::invalid("Unexpected end of expansion"))?;
         if self.l == e {
             expressions[&self.l].inv_eval(expressions, expected_res * expressions[&self.r].eval(expressions)?, to_expand)
         } else if self.r == e {
-            expressions[&self.r].inv_eval(expressions, expressions[&self.l].eval(expressions)? / expected_res, to_expand)
-        } else { Err(ErrorMsg::new("Failed to expand: Queried entry was not present")) }
+            expressions[&self.r].inv_eval(expressions, divide(expressions[&self.l].eval(expressions)?, expected_res)?, to_expand)
+        } else { Err(Error::invalid("Failed to expand: Queried entry was not present")) }
     }
 
     fn children(&self) -> Vec<String> { vec![self.l.clone(), self.r.clone()] }
 }
 
+lazy_static! {
+    static ref ENTRY: Pattern = Pattern::new("{id}: {}");
+    static ref OPERATION: Pattern = Pattern::new("{id} {char} {id}");
+}
+
 impl FromStr for Box<dyn Expression> {
-    type Err = ErrorMsg;
+    type Err = Error;
+    /// Parses the job of a monkey, like `pppw + sjmn` or `5`.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.chars().nth(11) == Some('+') {
-            Ok(Box::new(Add {
-                l: s[6..10].to_string(),
-                r: s[13..17].to_string()
-            }))
-        } else if s.chars().nth(11) == Some('-') {
-            Ok(Box::new(Sub {
-                l: s[6..10].to_string(),
-                r: s[13..17].to_string()
-            }))
-        } else if s.chars().nth(11) == Some('*') {
-            Ok(Box::new(Mul {
-                l: s[6..10].to_string(),
-                r: s[13..17].to_string()
-            }))
-        } else if s.chars().nth(11) == Some('/') {
-            Ok(Box::new(Div {
-                l: s[6..10].to_string(),
-                r: s[13..17].to_string()
-            }))
-        } else {Ok(Box::new(Lit{val:s[6..].parse()?}))}
+        if !s.contains(' ') {
+            return Ok(Box::new(Lit { val: parse_num(s)? }))
+        }
+        let (l, op, r): (String, char, String) = OPERATION.parse(s)?;
+        match op {
+            '+' => Ok(Box::new(Add { l, r })),
+            '-' => Ok(Box::new(Sub { l, r })),
+            '*' => Ok(Box::new(Mul { l, r })),
+            '/' => Ok(Box::new(Div { l, r })),
+            _ => Err(Error::parse_at(format!("Unknown operation {op}"), l.len() + 1, 1))
+        }
     }
 
 }
 
-fn parse_entry(res_s: std::io::Result<String>) -> Result<(String, Box<dyn Expression>), ErrorMsg> {
-    let s = res_s?;
-    Ok((s[..4].to_string(), s.parse::<Box<dyn Expression>>()?))
+fn parse_entry(s: &str) -> Result<(String, Box<dyn Expression>), Error> {
+    let (name, job): (String, Field) = ENTRY.parse(s)?;
+    Ok((name, job.text.parse::<Box<dyn Expression>>().map_err(|err| err.shifted(job.offset))?))
 }
 
-fn run(path: &str) -> Result<(), ErrorMsg> {
-    let mut expressions: HashMap<String, Box<dyn Expression>> = HashMap::new();
-    let mut parent_of : HashMap<String, String> = HashMap::new();
-    for s in utils::read_lines(path)? {
-        let e = parse_entry(s)?;
-        for child in e.1.children() {
-            parent_of.insert(child, e.0.clone());
+/// All monkeys by name, plus the name of the monkey that listens to each one.
+pub struct Monkeys {
+    expressions: HashMap<String, Box<dyn Expression>>,
+    parent_of: HashMap<String, String>
+}
+
+pub struct Day21;
+
+impl Solution for Day21 {
+    type Input = Monkeys;
+
+    fn parse(&self, input: &str) -> Result<Monkeys, Error> {
+        let mut expressions: HashMap<String, Box<dyn Expression>> = HashMap::new();
+        let mut parent_of : HashMap<String, String> = HashMap::new();
+        let entries = parse_lines(input, parse_entry)?;
+        let names: HashSet<&str> = entries.iter().map(|(name, _)| name.as_str()).collect();
+        for ((i, line), (name, expression)) in input.lines().enumerate().zip(&entries) {
+            let children = expression.children();
+            // The names of the children come in order after the name of the monkey
+            let mut offset = name.len();
+            for child in &children {
+                offset += line[offset..].find(child.as_str()).unwrap_or(0);
+                if !names.contains(child.as_str()) {
+                    return Err(Error::parse_at(format!("There is no monkey {child}"), offset, child.len()).on_line(i, line))
+                }
+                offset += child.len();
+            }
+        }
+        for e in entries {
+            for child in e.1.children() {
+                parent_of.insert(child, e.0.clone());
+            }
+            expressions.insert(e.0, e.1);
+        }
+        Ok(Monkeys { expressions, parent_of })
+    }
+
+    fn part1(&self, monkeys: &Monkeys) -> Result<Answer, Error> {
+        let root = monkeys.expressions.get("root").ok_or(Error::invalid("There is no root monkey"))?;
+        Ok(root.eval(&monkeys.expressions)?.into())
+    }
+
+    fn part2(&self, Monkeys { expressions, parent_of }: &Monkeys) -> Result<Answer, Error> {
+        let root = expressions.get("root").ok_or(Error::invalid("There is no root monkey"))?;
+        let mut inv_search: Vec<String> = vec!["humn".to_string()];
+        loop {
+            let parent = parent_of.get(inv_search.last().unwrap()).ok_or(Error::invalid("humn is not connected to root"))?;
+            if parent == "root" {
+                break;
+            } else {
+                inv_search.push(parent.clone());
+            }
         }
-        expressions.insert(e.0, e.1);
-    }
-    let root = &expressions["root"];
-    let result = root.eval(&expressions)?;
-
-    let mut inv_search: Vec<String> = vec!["humn".to_string()];
-    loop {
-        let parent = &parent_of[inv_search.last().unwrap()];
-        if parent == "root" {
-            break;
-        } else {
-            inv_search.push(parent.clone());
+        let other = root.children().into_iter().find(|c| c != inv_search.last().unwrap()).ok_or(Error::invalid("root does not compare two monkeys"))?;
+        let expected = expressions[&other].eval(expressions)?;
+        Ok(expressions[&inv_search.pop().unwrap()].inv_eval(expressions, expected, inv_search)?.into())
+    }
+}
+
+/// Collects the jobs of generated monkeys, each under a name that is not taken yet.
+struct Troop {
+    jobs: Vec<String>,
+    names: HashSet<String>
+}
+
+impl Troop {
+    fn add(&mut self, rng: &mut Rng, job: String) -> String {
+        let name = loop {
+            let name: String = (0..4).map(|_| (b'a' + rng.below(26) as u8) as char).collect();
+            if name != "root" && name != "humn" && self.names.insert(name.clone()) { break name }
+        };
+        self.jobs.push(format!("{name}: {job}"));
+        name
+    }
+
+    /// Adds about `size` monkeys that don't depend on humn, and returns the one at the top and the number it yells.
+    fn add_constant(&mut self, rng: &mut Rng, size: usize) -> (String, i64) {
+        if size < 3 {
+            let num = rng.range(1..=20);
+            return (self.add(rng, num.to_string()), num)
         }
+        let left_size = rng.range(1..=size as i64 - 2) as usize;
+        let (left, l) = self.add_constant(rng, left_size);
+        let (right, r) = self.add_constant(rng, size - 1 - left_size);
+        // Only exact divisions and small products, so that the numbers stay in range
+        let (op, num) = match rng.below(4) {
+            1 => ('-', l - r),
+            2 if (l * r).abs() <= 100000 => ('*', l * r),
+            3 if r != 0 && l % r == 0 => ('/', l / r),
+            _ => ('+', l + r)
+        };
+        (self.add(rng, format!("{left} {op} {right}")), num)
+    }
+}
+
+/// Generates about `size` monkeys. The number humn yells goes up a chain of monkeys to root, whose other side does not
+/// depend on it, and every operation along the chain can be undone exactly.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut troop = Troop { jobs: Vec::new(), names: HashSet::new() };
+    let chain_len = (size / 30).max(1);
+    let subtree_size = (size / (chain_len + 1)).max(1);
+    let (mut name, mut num) = ("humn".to_string(), rng.range(1..=5000));
+    troop.jobs.push(format!("humn: {}", rng.range(1..=5000)));
+    for _ in 0..chain_len {
+        let constant_size = rng.range(1..=2 * subtree_size as i64 - 1) as usize;
+        let (constant, c) = troop.add_constant(rng, constant_size);
+        let swap = rng.chance(50);
+        let (job, next) = match rng.below(4) {
+            1 if swap => (format!("{constant} - {name}"), c - num),
+            1 => (format!("{name} - {constant}"), num - c),
+            2 if c != 0 && num.checked_mul(c).is_some_and(|product| product.abs() <= 1 << 40) => (
+                if swap { format!("{constant} * {name}") } else { format!("{name} * {constant}") }, num * c
+            ),
+            3 if c != 0 && num % c == 0 => (format!("{name} / {constant}"), num / c),
+            _ => (if swap { format!("{constant} + {name}") } else { format!("{name} + {constant}") }, num + c)
+        };
+        (name, num) = (troop.add(rng, job), next);
     }
-    let expected = expressions[root.children().iter().find(|c| c != &inv_search.last().unwrap()).unwrap()].eval(&expressions)?;
-    let inv_res = expressions[&inv_search.pop().unwrap()].inv_eval(&expressions, expected, inv_search)?;
-    
-    Ok(println!("Aaaand the result iiis ...: {result}!!!!! But I actually expected you to say {inv_res}"))
+    // The other side of root is adjusted to match the number humn has to yell
+    let (other, o) = troop.add_constant(rng, subtree_size);
+    let difference = troop.add(rng, (num - o).abs().to_string());
+    let other = troop.add(rng, format!("{other} {} {difference}", if num >= o {'+'} else {'-'}));
+    troop.jobs.push(if rng.chance(50) { format!("root: {name} + {other}") } else { format!("root: {other} + {name}") });
+    rng.shuffle(&mut troop.jobs);
+    troop.jobs.join("\n")
 }
\ No newline at end of file
@@ -1,63 +1,115 @@
-use std::ops::Add;
+use std::str::FromStr;
 use crate::day22::Instruction::{Move, Rotate};
 use crate::day22::Tile::{Free, Wall};
-use crate::{hashmap, utils};
-use crate::utils::ErrorMsg;
-
-pub fn run_sample() {
-    ErrorMsg::print(run(
-        "input/day22_sample.txt",
-        [(2,0), (0,1), (1,1), (2,1), (2,2), (3,2)],
-        [ // part 2
+use crate::solution::{Answer, Solution};
+use crate::geom::Vec2;
+use crate::utils::{char_map, parse_list, Error, Grid, Part, Pattern};
+use crate::generate::Rng;
+
+lazy_static! {
+    static ref EDGE: Pattern = Pattern::new("{usize}/{u8}");
+}
+
+/// For every face and direction: the face one ends up on and the rotation applied when crossing the edge.
+pub type Edges = [[(usize, u8); 4]; 6];
+
+/// How the six faces of the cube are laid out in the input. Faces are numbered in the order they appear in the input.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct CubeLayout {
+    /// The number of tiles along an edge of a face.
+    pub face_len: usize,
+    /// Where each face is in the input, counted in faces from the top left.
+    pub face_locations: [(usize, usize); 6],
+    /// The edges for each part. Part 1 wraps around the flat map, part 2 folds the net into a cube.
+    pub edges: [Edges; 2]
+}
+
+/// The presets `sample` and `actual`, for the nets of the sample and of the actual inputs.
+impl FromStr for CubeLayout {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sample" => Ok(CubeLayout::sample()),
+            "actual" => Ok(CubeLayout::actual()),
+            _ => Err(Error::invalid(format!("Unknown cube layout {s}, expected sample or actual")))
+        }
+    }
+}
+
+impl CubeLayout {
+    pub fn sample() -> CubeLayout { CubeLayout {
+        face_len: 4,
+        face_locations: [(2,0), (0,1), (1,1), (2,1), (2,2), (3,2)],
+        edges: [[
+            [(0, 0), (3, 0), (0, 0), (4, 0)],
+            [(2, 0), (1, 0), (3, 0), (1, 0)],
+            [(3, 0), (2, 0), (1, 0), (2, 0)],
+            [(1, 0), (4, 0), (2, 0), (0, 0)],
+            [(5, 0), (0, 0), (5, 0), (3, 0)],
+            [(4, 0), (5, 0), (4, 0), (5, 0)]
+        ], [
             [(5, 2), (3, 0), (2, 3), (1, 2)],
             [(2, 0), (4, 2), (5, 1), (0, 2)],
             [(3, 0), (4, 3), (1, 0), (0, 1)],
             [(5, 1), (4, 0), (2, 0), (0, 0)],
             [(5, 0), (1, 2), (2, 1), (3, 0)],
             [(0, 2), (1, 3), (4, 0), (3, 3)]
-        ],
-        // [ // part1
-        //     [(0, 0), (3, 0), (0, 0), (4, 0)],
-        //     [(2, 0), (1, 0), (3, 0), (1, 0)],
-        //     [(3, 0), (2, 0), (1, 0), (2, 0)],
-        //     [(1, 0), (4, 0), (2, 0), (0, 0)],
-        //     [(5, 0), (0, 0), (5, 0), (3, 0)],
-        //     [(4, 0), (5, 0), (4, 0), (5, 0)]
-        // ],
-        4
-    ));
-}
+        ]]
+    }}
 
-pub fn run_actual() {
-    ErrorMsg::print(run(
-        "input/day22.txt",
-        [(1,0), (2,0), (1,1), (0,2), (1,2), (0,3)],
-        [ // part 2
+    pub fn actual() -> CubeLayout { CubeLayout {
+        face_len: 50,
+        face_locations: [(1,0), (2,0), (1,1), (0,2), (1,2), (0,3)],
+        edges: [[
+            [(1,0), (2,0), (1,0), (4,0)],
+            [(0,0), (1,0), (0,0), (1,0)],
+            [(2,0), (4,0), (2,0), (0,0)],
+            [(4,0), (5,0), (4,0), (5,0)],
+            [(3,0), (0,0), (3,0), (2,0)],
+            [(5,0), (3,0), (5,0), (3,0)]
+        ], [
             [(1,0), (2,0), (3,2), (5,1)],
             [(4,2), (2,1), (0,0), (5,0)],
             [(1,3), (4,0), (3,3), (0,0)],
             [(4,0), (5,0), (0,2), (2,1)],
             [(1,2), (5,1), (3,0), (2,0)],
             [(4,3), (1,0), (0,3), (3,0)]
-        ],
-        // [ // part 1
-        //     [(1,0), (2,0), (1,0), (4,0)],
-        //     [(0,0), (1,0), (0,0), (1,0)],
-        //     [(2,0), (4,0), (2,0), (0,0)],
-        //     [(4,0), (5,0), (4,0), (5,0)],
-        //     [(3,0), (0,0), (3,0), (2,0)],
-        //     [(5,0), (3,0), (5,0), (3,0)]
-        // ],
-        50
-    ));
+        ]]
+    }}
+
+    /// Parses six positions like `1,0 2,0 1,1 0,2 1,2 0,3`.
+    pub fn parse_face_locations(s: &str) -> Result<[(usize, usize); 6], Error> {
+        let locations = parse_list(s, " ", |location| location.parse::<Vec2<usize>>().map(|v| (v.x, v.y)))?;
+        let found = locations.len();
+        locations.try_into().map_err(|_| Error::parse(format!("Expected the locations of 6 faces, found {found}")))
+    }
+
+    /// Parses the edges of every face separated by `;`, each as four `face/rotation` pairs for right, down, left and up,
+    /// like `1/0 2/0 3/2 5/1; 4/2 2/1 0/0 5/0; ...`.
+    pub fn parse_edges(s: &str) -> Result<Edges, Error> {
+        let faces = parse_list(s, ";", |face| {
+            let indent = face.len() - face.trim_start().len();
+            let edges = parse_list(face.trim(), " ", |edge| {
+                let (face, rotation) = EDGE.parse(edge)?;
+                if face >= 6 || rotation >= 4 {
+                    return Err(Error::parse_at("Faces go from 0 to 5 and rotations from 0 to 3", 0, edge.len()))
+                }
+                Ok((face, rotation))
+            }).map_err(|err| err.shifted(indent))?;
+            let found = edges.len();
+            <[(usize, u8); 4]>::try_from(edges).map_err(|_| Error::parse_at(format!("Expected 4 edges, found {found}"), indent, face.trim().len()))
+        })?;
+        let found = faces.len();
+        faces.try_into().map_err(|_| Error::parse(format!("Expected the edges of 6 faces, found {found}")))
+    }
 }
 
 #[derive(Clone, Copy, Eq, PartialEq)]
-enum Tile {
+pub enum Tile {
     Free, Wall
 }
 
-enum Instruction {
+pub enum Instruction {
     Move(u32),
     Rotate(u8)
 }
@@ -70,38 +122,12 @@ struct Pose {
     rot: u8
 }
 
-fn run(path: &str, face_locations: [(usize, usize); 6], edge_mapping: [[(usize, u8); 4]; 6], face_len: usize) -> Result<(), ErrorMsg> {
-    let mut lines = utils::read_lines_to_vec(path)?;
-    let mut map: [Vec<Vec<Tile>>; 6] = [Vec::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new()];
-    for face_i in 0..6 {
-        let (face_x, face_y) = face_locations[face_i];
-        let map_entry = &mut map[face_i];
-        for y in (face_len * face_y)..(face_len * (face_y + 1)) {
-            map_entry.push(lines[y][(face_len * face_x)..(face_len * (face_x + 1))].chars().map(|c| match c {
-                '.' => Ok(Free),
-                '#' => Ok(Wall),
-                _ => Err(ErrorMsg { wrapped: format!("Invalid char {c}") })
-            }).collect::<Result<Vec<Tile>, ErrorMsg>>()?)
-        }
-    }
-    let instructions_line = lines.last().ok_or(ErrorMsg::new("Did not find instruction line"))?;
-    let mut instructions: Vec<Instruction> = Vec::new();
-    for c in instructions_line.chars() {
-        match c {
-            'L' => Ok(instructions.push(Rotate(3))),
-            'R' => Ok(instructions.push(Rotate(1))),
-            '0'..='9' => Ok({
-                let by = c.to_digit(10).ok_or(ErrorMsg{wrapped:format!("Failed to parse char {c}")})?;
-                if let Some(Move(last_move_ref)) = instructions.last() {
-                    let last_move = *last_move_ref;
-                    instructions.pop();
-                    instructions.push(Move(last_move * 10 + by))
-                } else { instructions.push(Move(by)) }
-            }),
-            _ => Err(ErrorMsg{wrapped:format!("Failed to parse char {c}")})
-        }?
-    }
-    let next = |mut p: Pose| -> Result<Pose, ErrorMsg> {
+/// Follows the instructions across the faces and returns the final password.
+fn walk(map: &[Grid<Tile>; 6], instructions: &[Instruction], layout: &CubeLayout, part: Part) -> Result<usize, Error> {
+    let face_locations = layout.face_locations;
+    let edge_mapping = layout.edges[if part == Part::One { 0 } else { 1 }];
+    let face_len = layout.face_len;
+    let next = |mut p: Pose| -> Result<Pose, Error> {
         match p.rot {
             0 => {
                 if p.x == (face_len-1) {
@@ -113,7 +139,7 @@ fn run(path: &str, face_locations: [(usize, usize); 6], edge_mapping: [[(usize,
                         1 => Ok((face_len-p.y-1, 0)),
                         2 => Ok((face_len-1, face_len-p.y-1)),
                         3 => Ok((p.y, face_len-1)),
-                        _ => Err(ErrorMsg::new("Unexpected rotation"))
+                        _ => Err(Error::invalid("Unexpected rotation"))
                     }?
                 } else {
                     p.x += 1
@@ -129,7 +155,7 @@ fn run(path: &str, face_locations: [(usize, usize); 6], edge_mapping: [[(usize,
                         1 => Ok((face_len - 1, p.x)),
                         2 => Ok((face_len-p.x-1, face_len-1)),
                         3 => Ok((0, face_len-p.x-1)),
-                        _ => Err(ErrorMsg::new("Unexpected rotation"))
+                        _ => Err(Error::invalid("Unexpected rotation"))
                     }?
                 } else {
                     p.y += 1;
@@ -145,7 +171,7 @@ fn run(path: &str, face_locations: [(usize, usize); 6], edge_mapping: [[(usize,
                         1 => Ok((face_len-p.y-1, face_len-1)),
                         2 => Ok((0, face_len-p.y-1)),
                         3 => Ok((p.y, 0)),
-                        _ => Err(ErrorMsg::new("Unexpected rotation"))
+                        _ => Err(Error::invalid("Unexpected rotation"))
                     }?
                 } else {
                     p.x -= 1;
@@ -161,24 +187,24 @@ fn run(path: &str, face_locations: [(usize, usize); 6], edge_mapping: [[(usize,
                         1 => Ok((0, p.x)),
                         2 => Ok((face_len-p.x-1, 0)),
                         3 => Ok((face_len-1, face_len-p.x-1)),
-                        _ => Err(ErrorMsg::new("Unexpected rotation"))
+                        _ => Err(Error::invalid("Unexpected rotation"))
                     }?
                 } else {
                     p.y -= 1;
                 }
             },
-            _ => Err(ErrorMsg { wrapped: format!("Invalid rotation {}", p.rot) })?
+            _ => Err(Error::invalid(format!("Invalid rotation {}", p.rot)))?
         }
         Ok(p)
     };
     let mut pos = Pose {face: 0, x: 0, y: 0, rot: 0};
     for i in instructions {
-        match i {
+        match *i {
             Rotate(r) => pos.rot = (pos.rot + r) % 4,
             Move(m) => {
                 for _ in 0..m {
                     let new_pos = next(pos)?;
-                    if map[new_pos.face][new_pos.y][new_pos.x] == Wall {
+                    if map[new_pos.face][(new_pos.x, new_pos.y)] == Wall {
                         break;
                     } else {pos = new_pos}
                 }
@@ -187,5 +213,87 @@ fn run(path: &str, face_locations: [(usize, usize); 6], edge_mapping: [[(usize,
     }
     let x = face_len * face_locations[pos.face].0 + pos.x + 1;
     let y = face_len * face_locations[pos.face].1 + pos.y + 1;
-    Ok(println!("Ending at {x}, {y} at rot:{}\n => result is {}", pos.rot, 1000 * y + 4 * x + pos.rot as usize))
+    Ok(1000 * y + 4 * x + pos.rot as usize)
+}
+
+/// `layout` describes how the faces are arranged in the input.
+pub struct Day22 {
+    pub layout: CubeLayout
+}
+
+impl Solution for Day22 {
+    type Input = ([Grid<Tile>; 6], Vec<Instruction>);
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        let face_locations = self.layout.face_locations;
+        let face_len = self.layout.face_len;
+        let lines: Vec<&str> = input.lines().collect();
+        let tile = char_map(&[('.', Free), ('#', Wall)]);
+        let mut faces: Vec<Grid<Tile>> = Vec::new();
+        for (face_i, &(face_x, face_y)) in face_locations.iter().enumerate() {
+            let mut rows = Vec::new();
+            for (y, line) in lines.iter().enumerate().skip(face_len * face_y).take(face_len) {
+                let face_start = face_len * face_x;
+                let row = line.get(face_start..(face_start + face_len))
+                    .ok_or(Error::parse_at(format!("Line ended before face {face_i} of the cube"), line.len(), 1).on_line(y, line))?;
+                rows.push(row.char_indices().map(|(x, c)| tile(c).map_err(|err| err.located(face_start + x, 1).on_line(y, line)))
+                    .collect::<Result<Vec<Tile>, Error>>()?)
+            }
+            if rows.len() != face_len {
+                return Err(Error::parse(format!("Input ended before face {face_i} of the cube")))
+            }
+            faces.push(Grid::from_rows(rows)?);
+        }
+        let map: [Grid<Tile>; 6] = faces.try_into().map_err(|_| Error::invalid("A cube has six faces"))?;
+        let instructions_line = lines.last().ok_or(Error::parse("Did not find instruction line"))?;
+        let mut instructions: Vec<Instruction> = Vec::new();
+        let at = |i: usize, c: char| Error::parse_at(format!("Expected L, R or a digit, found {c}"), i, 1)
+            .on_line(lines.len() - 1, instructions_line);
+        for (i, c) in instructions_line.char_indices() {
+            match c {
+                'L' => Ok(instructions.push(Rotate(3))),
+                'R' => Ok(instructions.push(Rotate(1))),
+                '0'..='9' => Ok({
+                    let by = c.to_digit(10).ok_or(at(i, c))?;
+                    if let Some(Move(last_move_ref)) = instructions.last() {
+                        let last_move = *last_move_ref;
+                        instructions.pop();
+                        instructions.push(Move(last_move * 10 + by))
+                    } else { instructions.push(Move(by)) }
+                }),
+                _ => Err(at(i, c))
+            }?
+        }
+        Ok((map, instructions))
+    }
+
+    fn part1(&self, (map, instructions): &Self::Input) -> Result<Answer, Error> {
+        Ok(walk(map, instructions, &self.layout, Part::One)?.into())
+    }
+
+    fn part2(&self, (map, instructions): &Self::Input) -> Result<Answer, Error> {
+        Ok(walk(map, instructions, &self.layout, Part::Two)?.into())
+    }
+}
+
+/// Generates a map folding into the cube of the actual puzzle, where a tenth of the tiles are walls, followed by
+/// a path of `size` moves and turns.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let CubeLayout { face_len, face_locations, .. } = CubeLayout::actual();
+    let width = face_locations.iter().map(|&(x, _)| x + 1).max().unwrap_or(0) * face_len;
+    let height = face_locations.iter().map(|&(_, y)| y + 1).max().unwrap_or(0) * face_len;
+    let mut map = Grid::new(width, height, ' ');
+    for (face, &(face_x, face_y)) in face_locations.iter().enumerate() {
+        for y in 0..face_len {
+            for x in 0..face_len {
+                // The path starts in the top left corner of the first face
+                let start = face == 0 && x == 0 && y == 0;
+                map[(face_x * face_len + x, face_y * face_len + y)] = if !start && rng.chance(10) {'#'} else {'.'};
+            }
+        }
+    }
+    let path: String = (0..size.max(1)).map(|i| {
+        if i % 2 == 0 { rng.range(1..=50).to_string() } else { rng.pick(&["L", "R"]).to_string() }
+    }).collect();
+    format!("{}\n\n{path}", map.rows().map(|row| row.iter().collect::<String>().trim_end().to_string()).collect::<Vec<_>>().join("\n"))
 }
\ No newline at end of file
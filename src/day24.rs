@@ -1,61 +1,27 @@
-use std::cmp::{max, min, Ordering};
-use std::collections::binary_heap::BinaryHeap;
-use std::collections::{HashSet};
-use std::ops::Add;
-use crate::day24::Dir::{Down, Left, Right, Up};
-use crate::utils;
-use crate::utils::ErrorMsg;
+use std::iter;
+use crate::geom::{Dir, Vec2};
+use crate::search::a_star;
+use crate::solution::{Answer, Solution};
+use crate::utils::{char_map, Error, Grid};
+use crate::visualize::{Recorder, Visualize};
+use crate::generate::Rng;
 
-pub fn run_sample() {
-    ErrorMsg::print(run("input/day24_sample.txt"));
-}
-
-pub fn run_actual() {
-    ErrorMsg::print(run("input/day24.txt"));
-}
-
-#[derive(PartialEq, Eq, Clone, Copy)]
-enum Dir {
-    Up, Right, Down, Left
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Tile {
+    Ground,
+    Wall,
+    Blizzard(Dir)
 }
+const TILES: [(char, Tile); 6] = [
+    ('.', Tile::Ground), ('#', Tile::Wall),
+    ('>', Tile::Blizzard(Dir::Right)), ('<', Tile::Blizzard(Dir::Left)), ('^', Tile::Blizzard(Dir::Up)), ('v', Tile::Blizzard(Dir::Down))
+];
 
 struct Blizzard {
-    pos: Pos,
+    pos: Vec2,
     dir: Dir
 }
 
-#[derive(PartialEq, Eq, Clone, Copy)]
-struct Pos {
-    x: usize,
-    y: usize
-}
-
-impl Add<(Dir, (usize,usize))> for Pos {
-    type Output = Pos;
-
-    fn add(self, rhs: (Dir, (usize,usize))) -> Self::Output {
-        match rhs.0 {
-            Right => Pos { x: (self.x % rhs.1.0) + 1, y: self.y},
-            Down => Pos { y: (self.y % rhs.1.1) + 1, x: self.x},
-            Left => Pos { x: ((self.x + rhs.1.0 - 2) % rhs.1.0) + 1, y: self.y},
-            Up => Pos { y: ((self.y + rhs.1.1 - 2) % rhs.1.1) + 1, x: self.x},
-        }
-    }
-}
-impl Add<(i32, i32)> for Pos {
-    type Output = Option<Pos>;
-    fn add(self, rhs: (i32, i32)) -> Option<Pos> {
-        if (rhs.1 < 0 && self.y == 0) || rhs.0 < 0 && self.x == 0 {
-            None
-        } else {
-            Some(Pos {
-                x: (self.x as i32 + rhs.0) as usize,
-                y: (self.y as i32 + rhs.1) as usize,
-            })
-        }
-    }
-}
-
 fn lcm(first: usize, second: usize) -> usize {
     first * second / gcd(first, second)
 }
@@ -64,9 +30,7 @@ fn gcd(first: usize, second: usize) -> usize {
     let mut max = first;
     let mut min = second;
     if min > max {
-        let val = max;
-        max = min;
-        min = val;
+        std::mem::swap(&mut max, &mut min);
     }
 
     loop {
@@ -80,104 +44,113 @@ fn gcd(first: usize, second: usize) -> usize {
     }
 }
 
-#[derive(PartialEq, Eq, Clone, Copy)]
-struct State {
-    player_pos: Pos,
-    blizzard_index: usize,
-    cost_so_far: usize,
-    total_estimated_cost: usize
-}
-impl State {
-    fn new(player_pos: Pos, target_pos: Pos, blizzard_index: usize, cost_so_far: usize) -> State {
-        State {
-            player_pos,
-            blizzard_index,
-            cost_so_far,
-            total_estimated_cost: cost_so_far
-                + max(target_pos.x,player_pos.x) - min(target_pos.x,player_pos.x)
-                + max(target_pos.y,player_pos.y) - min(target_pos.y,player_pos.y)
-        }
+/// Draws the expedition at `pos` among the blizzards of `map_at[blizzard_index]`.
+fn draw(valley: &Valley, pos: Vec2, blizzard_index: usize) -> Grid<char> {
+    let mut frame = valley.map_at[blizzard_index].map(|&blocked| if blocked {'*'} else {'.'});
+    for ((x, y), &is_wall) in valley.walls.iter() {
+        if is_wall { frame[(x, y)] = '#' }
     }
+    frame[(pos.x as usize, pos.y as usize)] = 'E';
+    frame
 }
-impl Ord for State {
-    fn cmp(&self, other: &Self) -> Ordering {
-        other.total_estimated_cost.cmp(&self.total_estimated_cost)
+
+/// The minutes it takes to get from `start_pos` to `target_pos` when starting at blizzard state `blizzard_index`.
+/// Every minute of the way is recorded.
+fn time_to_reach(valley: &Valley, start_pos: Vec2, target_pos: Vec2, blizzard_index: usize, recorder: &mut Recorder) -> Result<usize, Error> {
+    let map_at = &valley.map_at;
+    let path = a_star((start_pos, blizzard_index), |&(pos, blizzard_index)| {
+        let next_blizzard_i = (blizzard_index + 1) % map_at.len();
+        let next_map = &map_at[next_blizzard_i];
+        Dir::ALL.iter().map(move |dir| pos + dir.offset()).chain(iter::once(pos))
+            .filter(|next_pos| next_map.get_signed(next_pos.x, next_pos.y) == Some(&false))
+            .map(move |next_pos| ((next_pos, next_blizzard_i), 1))
+    }, |(pos, _)| pos.manhattan(target_pos) as u64, |(pos, _)| *pos == target_pos);
+    let path = path.ok_or(Error::no_solution("Did not find the target. Sad."))?;
+    for &(pos, blizzard_index) in &path.states {
+        recorder.tick(|| draw(valley, pos, blizzard_index))?;
     }
+    Ok(path.cost as usize)
 }
-impl PartialOrd for State {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
+
+/// Whether each tile is blocked by a wall or blizzard, for every state of the blizzards until they repeat.
+pub struct Valley {
+    map_at: Vec<Grid<bool>>,
+    walls: Grid<bool>,
+    start_pos: Vec2,
+    target_pos: Vec2
 }
 
-fn time_to_reach(start_pos: Pos, target_pos: Pos, blizzard_index: usize, map_at: &Vec<Vec<Vec<bool>>>) -> Result<usize, ErrorMsg> {
-    let mut queue: BinaryHeap<State> = BinaryHeap::new();
-    queue.push(State::new(start_pos, target_pos, blizzard_index, 0));
-    let mut has_been_explored: HashSet<(usize,usize,usize)> = HashSet::new();
-    let explore_options: [(i32, i32); 5] = [(1,0), (0,1), (0,0), (0,-1), (-1,0)];
-    while let Some(state) = queue.pop() {
-        if has_been_explored.contains(&(state.player_pos.x, state.player_pos.y, state.blizzard_index)) {
-            continue;
-        }
-        if state.player_pos == target_pos {
-            return Ok(state.cost_so_far)
+pub struct Day24 {
+    pub visualize: Option<Visualize>
+}
+
+impl Solution for Day24 {
+    type Input = Valley;
+
+    fn parse(&self, input: &str) -> Result<Valley, Error> {
+        let map = Grid::parse(input, char_map(&TILES))?;
+        if map.width() < 3 || map.height() < 3 {
+            let message = format!("The valley needs at least 3 by 3 tiles to have walls around it, found {} by {}", map.width(), map.height());
+            return Err(Error::parse(message).on_line(0, input.lines().next().unwrap_or_default()))
         }
-        let next_blizzard_i = (state.blizzard_index + 1) % map_at.len();
-        for next_pos in explore_options.iter().filter_map(|&offset| state.player_pos + offset).filter(|pos| pos.y < map_at[next_blizzard_i].len() && !map_at[next_blizzard_i][pos.y][pos.x]) {
-            queue.push(State::new(
-                next_pos,
-                target_pos,
-                next_blizzard_i,
-                state.cost_so_far + 1
-            ))
+        let blizzards: Vec<Blizzard> = map.iter().filter_map(|((x, y), tile)| match tile {
+            Tile::Blizzard(dir) => Some(Blizzard { pos: Vec2::new(x as i64, y as i64), dir: *dir }),
+            _ => None
+        }).collect();
+        let walls = map.map(|&tile| tile == Tile::Wall);
+        // Blizzards stay within the walls around the valley
+        let (valley_min, valley_max) = (Vec2::new(1, 1), Vec2::new(map.width() as i64 - 1, map.height() as i64 - 1));
+        let num_states = lcm(map.height() - 2, map.width() - 2);
+        let mut blizzards_at: Vec<Vec<Blizzard>> = Vec::new();
+        blizzards_at.push(blizzards);
+        for i in 1..num_states {
+            let new_blizzards: Vec<Blizzard> = blizzards_at[i-1].iter().map(|b| Blizzard {
+                pos: b.pos.wrapping_add(b.dir.offset(), valley_min, valley_max),
+                dir: b.dir
+            }).collect();
+            blizzards_at.push(new_blizzards)
         }
-        has_been_explored.insert((state.player_pos.x, state.player_pos.y, state.blizzard_index));
+        let map_at = blizzards_at.iter().map(|current_blizzards| {
+            let mut blocked = walls.clone();
+            for b in current_blizzards {
+                blocked[(b.pos.x as usize, b.pos.y as usize)] = true;
+            }
+            blocked
+        }).collect::<Vec<_>>();
+        let start_pos = Vec2::new(1, 0);
+        let target_pos = Vec2::new(map.width() as i64 - 2, map.height() as i64 - 1);
+        Ok(Valley { map_at, walls, start_pos, target_pos })
+    }
+
+    fn part1(&self, valley: &Valley) -> Result<Answer, Error> {
+        let mut recorder = Recorder::start(self.visualize.as_ref(), "part1")?;
+        Ok(time_to_reach(valley, valley.start_pos, valley.target_pos, 0, &mut recorder)?.into())
+    }
+
+    fn part2(&self, valley: &Valley) -> Result<Answer, Error> {
+        let Valley { map_at, start_pos, target_pos, .. } = valley;
+        let mut recorder = Recorder::start(self.visualize.as_ref(), "part2")?;
+        let time_to_reach_target = time_to_reach(valley, *start_pos, *target_pos, 0, &mut recorder)?;
+        let time_to_go_back = time_to_reach(valley, *target_pos, *start_pos, time_to_reach_target % map_at.len(), &mut recorder)?;
+        let time_to_return = time_to_reach(valley, *start_pos, *target_pos, (time_to_reach_target + time_to_go_back) % map_at.len(), &mut recorder)?;
+        Ok((time_to_reach_target + time_to_go_back + time_to_return).into())
     }
-    Err(ErrorMsg::new("Did not find the target. Sad."))
 }
 
-fn run(path: &str) -> Result<(), ErrorMsg> {
-    let mut blizzards: Vec<Blizzard> = Vec::new();
-    let mut map: Vec<Vec<bool>> = Vec::new();
-    for (y, line_r) in utils::read_lines(path)?.enumerate() {
-        let mut row = Vec::new();
-        let line = line_r?;
-        for (x, c) in line.chars().enumerate() {
-            let pos = Pos {x, y};
-            match c {
-                '>' => Ok({ blizzards.push(Blizzard { pos, dir: Right }); row.push(false) }),
-                '<' => Ok({ blizzards.push(Blizzard { pos, dir: Left }); row.push(false) }),
-                '^' => Ok({ blizzards.push(Blizzard { pos, dir: Up }); row.push(false) }),
-                'v' => Ok({ blizzards.push(Blizzard { pos, dir: Down }); row.push(false) }),
-                '.' => Ok(row.push(false)),
-                '#' => Ok(row.push(true)),
-                _ => Err(ErrorMsg{wrapped:format!("Not a valid char: {c}")})
-            }?
+/// Generates a valley with `size` rows and five times as many columns, so that the blizzards repeat after as many
+/// minutes as there are columns. About a third of the tiles start with a blizzard.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let (height, width) = (size.max(2), 5 * size.max(2));
+    loop {
+        let mut lines = vec![format!("#.{}", "#".repeat(width))];
+        for _ in 0..height {
+            let tiles: String = (0..width).map(|_| if rng.chance(35) { *rng.pick(&['>', '<', '^', 'v']) } else { '.' }).collect();
+            lines.push(format!("#{tiles}#"));
         }
-        map.push(row);
-    }
-    let num_states = lcm(map.len() - 2, map[0].len() - 2);
-    let mut blizzards_at: Vec<Vec<Blizzard>> = Vec::new();
-    blizzards_at.push(blizzards);
-    let blizzard_width = map[0].len() - 2;
-    let blizzard_height = map.len() - 2;
-    for i in 1..num_states {
-        let new_blizzards: Vec<Blizzard> = blizzards_at[i-1].iter().map(|b| Blizzard {
-            pos: b.pos + (b.dir, (blizzard_width, blizzard_height)),
-            dir: b.dir
-        }).collect();
-        blizzards_at.push(new_blizzards)
+        lines.push(format!("{}.#", "#".repeat(width)));
+        let input = lines.join("\n");
+        // Now and then the blizzards cut off the way, which is rare enough to simply try again
+        let day = Day24 { visualize: None };
+        if day.parse(&input).and_then(|valley| day.part2(&valley)).is_ok() { return input }
     }
-    let map_at = blizzards_at.iter().map(|current_blizzards| map.iter().enumerate()
-        .map(|(y,row)| row.iter().enumerate().map(|(x, &is_wall)| is_wall || current_blizzards.iter().any(|b| b.pos == Pos {x, y})
-        ).collect::<Vec<_>>()).collect::<Vec<_>>()).collect::<Vec<_>>();
-    let start_pos = Pos {x: 1, y: 0};
-    let target_pos = Pos {x: map[0].len()-2, y: map.len()-1};
-    let time_to_reach_target = time_to_reach(start_pos, target_pos, 0, &map_at)?;
-    println!("Found target in {time_to_reach_target}");
-    let time_to_go_back = time_to_reach(target_pos, start_pos, time_to_reach_target % map_at.len(), &map_at)?;
-    println!("Got back in {time_to_go_back}");
-    let time_to_return = time_to_reach(start_pos, target_pos, (time_to_reach_target + time_to_go_back) % map_at.len(), &map_at)?;
-    println!("Returned in {time_to_return}");
-    Ok(println!("Total travel time: {}", time_to_reach_target + time_to_go_back + time_to_return))
 }
\ No newline at end of file
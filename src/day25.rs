@@ -1,36 +1,31 @@
 use std::fmt::{Display, Formatter};
 use std::ops::{Add, AddAssign};
-use crate::utils::ErrorMsg;
+use crate::utils::{char_map, parse_lines, Error};
 use std::str::FromStr;
-use crate::utils;
+use crate::solution::{Answer, Solution};
+use crate::generate::Rng;
 
-pub fn run_sample() {
-    ErrorMsg::print(run("input/day25_sample.txt"));
+/// A number in the balanced base five of the SNAFU fuel requirements, with the digits `=`, `-`, `0`, `1` and `2`.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Snafu {
+    num: i64
 }
-
-pub fn run_actual() {
-    ErrorMsg::print(run("input/day25.txt"));
+impl From<i64> for Snafu {
+    fn from(num: i64) -> Self { Snafu { num } }
 }
-
-#[derive(Copy, Clone)]
-struct Snafu {
-    num: i64
+impl From<Snafu> for i64 {
+    fn from(snafu: Snafu) -> Self { snafu.num }
 }
 impl FromStr for Snafu {
-    type Err = ErrorMsg;
+    type Err = Error;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Snafu { num: s.chars().fold(Ok(0), |accum: Result<i64, ErrorMsg>, c| Ok(accum? * 5 + match c {
-            '2' => Ok(2),
-            '1' => Ok(1),
-            '0' => Ok(0),
-            '-' => Ok(-1),
-            '=' => Ok(-2),
-            _ => Err(ErrorMsg{wrapped:format!("Invalid char {c}")})
-        }?))?})
+        let digit = char_map(&[('2', 2), ('1', 1), ('0', 0), ('-', -1), ('=', -2)]);
+        Ok(Snafu { num: s.char_indices().try_fold(0, |accum: i64, (i, c)| Ok::<i64, Error>(accum * 5 + digit(c).map_err(|err| err.located(i, 1))?))? })
     }
 }
 impl Display for Snafu {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.num == 0 { return f.write_str("0") }
         let mut ret: Vec<char> = Vec::new();
         let mut num = self.num;
         while num != 0 {
@@ -67,12 +62,33 @@ impl AddAssign<Snafu> for Snafu {
     }
 }
 
-fn run(path: &str) -> Result<(), ErrorMsg> {
-    let mut sum: Snafu = Snafu{num:0};
-    for l_r in utils::read_lines(path)? {
-        let l = l_r?;
-        let now = l.parse::<Snafu>()?;
-        sum += now;
+pub struct Day25;
+
+impl Solution for Day25 {
+    type Input = Vec<Snafu>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error> {
+        parse_lines(input, |l| l.parse::<Snafu>())
     }
-    Ok(println!("The sum was {}, which is {sum}", sum.num))
+
+    fn part1(&self, numbers: &Self::Input) -> Result<Answer, Error> {
+        let mut sum: Snafu = Snafu{num:0};
+        for now in numbers {
+            sum += *now;
+        }
+        Ok(sum.to_string().into())
+    }
+
+    /// There is no second puzzle on the last day.
+    fn part2(&self, _: &Self::Input) -> Result<Answer, Error> {
+        Ok(Answer::Text("Merry Christmas!".to_string()))
+    }
+}
+
+/// Generates `size` fuel requirements of up to 20 SNAFU digits.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size.max(1)).map(|_| {
+        let digits = rng.range(1..=19) as u32;
+        Snafu::from(rng.range(1..=5i64.pow(digits))).to_string()
+    }).collect::<Vec<_>>().join("\n")
 }
\ No newline at end of file
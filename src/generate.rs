@@ -0,0 +1,100 @@
+use std::ops::RangeInclusive;
+use crate::utils::Error;
+use crate::{day01, day02, day03, day04, day05, day06, day07, day08, day09, day10, day11, day12, day13,
+            day14, day15, day16, day17, day18, day19, day20, day21, day22, day23, day24, day25};
+
+/// A small random number generator (SplitMix64). It is not meant for anything but making up puzzle inputs,
+/// which are the same for the same seed on every platform.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..bound`, which must not be empty.
+    pub fn below(&mut self, bound: usize) -> usize {
+        assert!(bound > 0, "Cannot pick from an empty range");
+        // The bias of the modulo is negligible for the small bounds used here
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    pub fn range(&mut self, range: RangeInclusive<i64>) -> i64 {
+        let (start, end) = range.into_inner();
+        assert!(start <= end, "Cannot pick from an empty range");
+        // Spans are computed with wrapping, so that even the range of every i64 works
+        match end.wrapping_sub(start) as u64 {
+            u64::MAX => self.next_u64() as i64,
+            span => start.wrapping_add((self.next_u64() % (span + 1)) as i64)
+        }
+    }
+
+    /// True with a chance of `percent` in 100.
+    pub fn chance(&mut self, percent: u32) -> bool {
+        self.below(100) < percent as usize
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+type Generator = fn(&mut Rng, usize) -> String;
+
+/// The generator of a day and the size that comes close to the actual puzzle input.
+/// What the size counts is documented at the generator of each day.
+fn generator_for(day: u8) -> Result<(Generator, usize), Error> {
+    Ok(match day {
+        1 => (day01::generate, 250),
+        2 => (day02::generate, 2500),
+        3 => (day03::generate, 300),
+        4 => (day04::generate, 1000),
+        5 => (day05::generate, 500),
+        6 => (day06::generate, 4096),
+        7 => (day07::generate, 300),
+        8 => (day08::generate, 99),
+        9 => (day09::generate, 2000),
+        10 => (day10::generate, 140),
+        11 => (day11::generate, 8),
+        12 => (day12::generate, 41),
+        13 => (day13::generate, 150),
+        14 => (day14::generate, 120),
+        15 => (day15::generate, 25),
+        16 => (day16::generate, 60),
+        17 => (day17::generate, 10000),
+        18 => (day18::generate, 2000),
+        19 => (day19::generate, 30),
+        20 => (day20::generate, 5000),
+        21 => (day21::generate, 2000),
+        22 => (day22::generate, 4000),
+        23 => (day23::generate, 72),
+        24 => (day24::generate, 25),
+        25 => (day25::generate, 120),
+        other => return Err(Error::invalid(format!("There is no day {other}")))
+    })
+}
+
+/// Makes up a valid input for `day` from `seed`, as big as the actual one unless another `size` is given.
+pub fn generate(day: u8, seed: u64, size: Option<usize>) -> Result<String, Error> {
+    let (generator, default_size) = generator_for(day)?;
+    let mut input = generator(&mut Rng::new(seed), size.unwrap_or(default_size));
+    input.push('\n');
+    Ok(input)
+}
@@ -0,0 +1,178 @@
+use std::fmt::{Debug, Display, Formatter};
+use std::num::ParseIntError;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+use crate::utils::{parse_num, Error};
+
+/// The integer types coordinates can have.
+pub trait Coord: Copy + Ord + Default + Debug + Add<Output=Self> + Sub<Output=Self> + Mul<Output=Self> {
+    const ZERO: Self;
+    const ONE: Self;
+    /// The remainder of dividing by `rhs` that is never negative.
+    fn rem_euclid(self, rhs: Self) -> Self;
+    /// How far apart two coordinates are. Works without underflowing for unsigned types too.
+    fn distance(self, other: Self) -> Self {
+        if self > other { self - other } else { other - self }
+    }
+}
+macro_rules! impl_coord {
+    ($($t: ty),*) => {$(
+        impl Coord for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+            fn rem_euclid(self, rhs: Self) -> Self { <$t>::rem_euclid(self, rhs) }
+        }
+    )*}
+}
+impl_coord!(i16, i32, i64, isize, u16, u32, u64, usize);
+
+/// A position or offset on a grid. `y` grows downwards like in the puzzle inputs.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+pub struct Vec2<T = i64> {
+    pub x: T,
+    pub y: T
+}
+
+/// A position or offset in space.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+pub struct Vec3<T = i64> {
+    pub x: T,
+    pub y: T,
+    pub z: T
+}
+
+impl<T: Coord> Vec2<T> {
+    pub const fn new(x: T, y: T) -> Vec2<T> { Vec2 { x, y } }
+
+    /// The number of steps between two points when moving diagonally is not allowed.
+    pub fn manhattan(self, other: Vec2<T>) -> T {
+        self.x.distance(other.x) + self.y.distance(other.y)
+    }
+    /// The number of steps between two points when moving diagonally is allowed.
+    pub fn chebyshev(self, other: Vec2<T>) -> T {
+        self.x.distance(other.x).max(self.y.distance(other.y))
+    }
+    /// Adds `offset`, wrapping around to the other side of the area from `min` up to `max` (exclusive) when leaving it.
+    pub fn wrapping_add(self, offset: Vec2<T>, min: Vec2<T>, max: Vec2<T>) -> Vec2<T> {
+        let size = max - min;
+        let Vec2 { x, y } = self - min + offset;
+        Vec2::new(x.rem_euclid(size.x), y.rem_euclid(size.y)) + min
+    }
+}
+
+impl<T: Coord> Vec3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Vec3<T> { Vec3 { x, y, z } }
+
+    pub fn manhattan(self, other: Vec3<T>) -> T {
+        self.x.distance(other.x) + self.y.distance(other.y) + self.z.distance(other.z)
+    }
+    pub fn chebyshev(self, other: Vec3<T>) -> T {
+        self.x.distance(other.x).max(self.y.distance(other.y)).max(self.z.distance(other.z))
+    }
+}
+impl<T: Coord + Neg<Output=T>> Vec3<T> {
+    /// The six positions sharing a face with this one.
+    pub fn neighbors(self) -> [Vec3<T>; 6] {
+        let (zero, one) = (T::ZERO, T::ONE);
+        [
+            Vec3::new(-one, zero, zero), Vec3::new(one, zero, zero),
+            Vec3::new(zero, -one, zero), Vec3::new(zero, one, zero),
+            Vec3::new(zero, zero, -one), Vec3::new(zero, zero, one)
+        ].map(|offset| self + offset)
+    }
+}
+
+macro_rules! impl_ops {
+    ($name: ident { $($field: ident),* }) => {
+        impl<T: Coord> Add for $name<T> {
+            type Output = Self;
+            fn add(self, rhs: Self) -> Self { $name { $($field: self.$field + rhs.$field),* } }
+        }
+        impl<T: Coord> Sub for $name<T> {
+            type Output = Self;
+            fn sub(self, rhs: Self) -> Self { $name { $($field: self.$field - rhs.$field),* } }
+        }
+        impl<T: Coord> AddAssign for $name<T> {
+            fn add_assign(&mut self, rhs: Self) { *self = *self + rhs }
+        }
+        impl<T: Coord> SubAssign for $name<T> {
+            fn sub_assign(&mut self, rhs: Self) { *self = *self - rhs }
+        }
+        /// Scales by a factor.
+        impl<T: Coord> Mul<T> for $name<T> {
+            type Output = Self;
+            fn mul(self, rhs: T) -> Self { $name { $($field: self.$field * rhs),* } }
+        }
+        impl<T: Coord + Neg<Output=T>> Neg for $name<T> {
+            type Output = Self;
+            fn neg(self) -> Self { $name { $($field: -self.$field),* } }
+        }
+    }
+}
+impl_ops!(Vec2 { x, y });
+impl_ops!(Vec3 { x, y, z });
+
+/// Comma-separated coordinates like `498,4`, the way most inputs list them.
+fn parse_coords<T: FromStr<Err=ParseIntError>, const N: usize>(s: &str) -> Result<[T; N], Error> {
+    let mut coords = Vec::with_capacity(N);
+    let mut offset = 0;
+    for part in s.split(',') {
+        coords.push(parse_num::<T>(part.trim()).map_err(|err| err.shifted(offset + part.len() - part.trim_start().len()))?);
+        offset += part.len() + 1;
+    }
+    let found = coords.len();
+    coords.try_into().map_err(|_| Error::parse(format!("Expected {N} comma-separated coordinates, found {found}")))
+}
+
+impl<T: FromStr<Err=ParseIntError>> FromStr for Vec2<T> {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let [x, y] = parse_coords(s)?;
+        Ok(Vec2 { x, y })
+    }
+}
+impl<T: FromStr<Err=ParseIntError>> FromStr for Vec3<T> {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let [x, y, z] = parse_coords(s)?;
+        Ok(Vec3 { x, y, z })
+    }
+}
+impl<T: Display> Display for Vec2<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+impl<T: Display> Display for Vec3<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{},{},{}", self.x, self.y, self.z)
+    }
+}
+
+/// The four directions on a grid, in clockwise order.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Dir {
+    Up, Right, Down, Left
+}
+
+impl Dir {
+    pub const ALL: [Dir; 4] = [Dir::Up, Dir::Right, Dir::Down, Dir::Left];
+
+    /// Turns clockwise by `quarter_turns`, or counterclockwise if negative.
+    pub fn rotated(self, quarter_turns: i32) -> Dir {
+        Dir::ALL[(self as i32 + quarter_turns).rem_euclid(4) as usize]
+    }
+    pub fn turn_right(self) -> Dir { self.rotated(1) }
+    pub fn turn_left(self) -> Dir { self.rotated(-1) }
+    pub fn opposite(self) -> Dir { self.rotated(2) }
+
+    /// The offset of one step in this direction.
+    pub fn offset<T: Coord + Neg<Output=T>>(self) -> Vec2<T> {
+        match self {
+            Dir::Up => Vec2::new(T::ZERO, -T::ONE),
+            Dir::Right => Vec2::new(T::ONE, T::ZERO),
+            Dir::Down => Vec2::new(T::ZERO, T::ONE),
+            Dir::Left => Vec2::new(-T::ONE, T::ZERO)
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use advent_of_code_2022::config::Config;
+use advent_of_code_2022::day02::{analyze, Day02};
+use advent_of_code_2022::input::InputSource;
+use advent_of_code_2022::solution::Params;
+use advent_of_code_2022::utils::{Error, Part};
+
+pub(crate) struct GuideArgs {
+    pub(crate) input: InputSource,
+    pub(crate) settings: Vec<(String, String)>,
+    pub(crate) config: Option<String>,
+    /// How many rounds of the best interpretation are broken down.
+    pub(crate) rounds: usize
+}
+
+impl GuideArgs {
+    pub(crate) fn execute(&self) -> Result<(), Error> {
+        let params = Params { overrides: self.settings.clone(), ..Params::new(2, &self.input, &Config::load(self.config.as_deref())?) };
+        let day = Day02::from_params(&params)?;
+        let path = self.input.path(2, Part::One)?;
+        let guide = day.parse_guide(&self.input.read(2, Part::One)?).map_err(|err| err.in_file(&path))?.rounds;
+        let ranking = analyze(&day.game, &guide);
+        let Some((best, _)) = ranking.first() else {
+            return Err(Error::invalid("The second column has more characters than there are symbols or outcomes"))
+        };
+
+        let width = ranking.iter().map(|(_, total)| total.to_string().len()).max().unwrap_or(0).max("Total".len());
+        println!("Rank  {:>width$}  Second column", "Total");
+        for (rank, (interpretation, total)) in ranking.iter().enumerate() {
+            println!("{:>4}  {total:>width$}  {}", rank + 1, interpretation.describe(&day.game));
+        }
+        if self.rounds > 0 {
+            println!("\nFirst rounds with {}:", best.describe(&day.game));
+            let rounds = best.play(&day.game, &guide).expect("The best interpretation plays every round");
+            for (i, ((_, c), round)) in guide.iter().zip(rounds).take(self.rounds).enumerate() {
+                println!("{:>6}  {} {c}  {}", i + 1, day.encoding.first[round.theirs.0], round.describe(&day.game));
+            }
+        }
+        Ok(())
+    }
+}
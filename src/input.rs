@@ -0,0 +1,79 @@
+use std::env;
+use std::io::{self, Read};
+use std::path::Path;
+use std::sync::OnceLock;
+use crate::utils::{read_input, Error, Part};
+
+/// Environment variable naming the directory with the `dayNN.txt` and `dayNN_sample.txt` files,
+/// so that somebody else's inputs can be used without moving files around.
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+
+/// The name [InputSource::Stdin] is shown as, and the value of `--input` that selects it.
+pub const STDIN: &str = "-";
+
+/// Where to read the input of a day from.
+#[derive(Clone, PartialEq, Debug)]
+pub enum InputSource {
+    Sample,
+    Actual,
+    Path(String),
+    Stdin
+}
+
+/// The directory [InputSource::Sample] and [InputSource::Actual] are looked up in: [INPUT_DIR_VAR] if set,
+/// otherwise `input` in the working directory.
+pub fn input_dir() -> Result<String, Error> {
+    if let Ok(dir) = env::var(INPUT_DIR_VAR) {
+        return Ok(dir)
+    }
+    if Path::new("input").is_dir() { return Ok("input".to_string()) }
+    Err(Error::invalid(format!("Found no input directory: {INPUT_DIR_VAR} is not set and there is no ./input")))
+}
+
+/// Whether a file is a sample by the naming convention `dayNN_sample.txt` or `dayNN_sample_2.txt`.
+pub fn is_sample_path(path: &str) -> bool {
+    Path::new(path).file_stem().and_then(|stem| stem.to_str()).is_some_and(|stem| stem.contains("_sample"))
+}
+
+impl InputSource {
+    /// Picks the source for a command line value, where `-` means stdin.
+    pub fn from_arg(arg: String) -> InputSource {
+        if arg == STDIN { InputSource::Stdin } else { InputSource::Path(arg) }
+    }
+
+    /// Resolves the input file. Some days (like day 9) come with a separate sample for part 2,
+    /// which is picked up as `dayNN_sample_2.txt` if it exists.
+    pub fn path(&self, day: u8, part: Part) -> Result<String, Error> {
+        Ok(match self {
+            InputSource::Path(path) => path.clone(),
+            InputSource::Stdin => STDIN.to_string(),
+            InputSource::Actual => format!("{}/day{day:02}.txt", input_dir()?),
+            InputSource::Sample => {
+                let part_sample = format!("{}/day{day:02}_sample_2.txt", input_dir()?);
+                if part == Part::Two && Path::new(&part_sample).exists() { part_sample }
+                else { format!("{}/day{day:02}_sample.txt", input_dir()?) }
+            }
+        })
+    }
+
+    /// Reads the input for a part. Stdin is only read once and then handed to every part asking for it.
+    pub fn read(&self, day: u8, part: Part) -> Result<String, Error> {
+        static STDIN_TEXT: OnceLock<Result<String, String>> = OnceLock::new();
+        match self {
+            InputSource::Stdin => STDIN_TEXT.get_or_init(|| {
+                let mut text = String::new();
+                io::stdin().read_to_string(&mut text).map(|_| text).map_err(|err| err.to_string())
+            }).clone().map_err(|err| Error::invalid(format!("Failed to read stdin: {err}"))),
+            _ => read_input(&self.path(day, part)?)
+        }
+    }
+
+    /// Whether the puzzle defaults for the sample apply, which is also the case for files named like samples.
+    pub fn is_sample(&self) -> bool {
+        match self {
+            InputSource::Sample => true,
+            InputSource::Path(path) => is_sample_path(path),
+            InputSource::Actual | InputSource::Stdin => false
+        }
+    }
+}
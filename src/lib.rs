@@ -0,0 +1,46 @@
+//! Solutions for Advent of Code 2022. Every `dayNN` module has a `DayNN` implementing [solution::Solution],
+//! next to the models its input is parsed into. [solution::solution_for] looks up a day at runtime.
+
+#![allow(clippy::unit_arg)]
+
+extern crate core;
+
+#[macro_use]
+extern crate lazy_static;
+
+#[macro_use]
+pub mod log;
+
+pub mod day01;
+pub mod utils;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day05;
+pub mod day06;
+pub mod day07;
+pub mod day08;
+pub mod day09;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
+pub mod input;
+pub mod config;
+pub mod solution;
+pub mod geom;
+pub mod search;
+pub mod generate;
+pub mod visualize;
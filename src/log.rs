@@ -0,0 +1,77 @@
+use std::fmt::{Arguments, Display, Formatter};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+use crate::utils::Error;
+
+/// How much is written to stderr besides the answers, from least to most. Every level includes the ones before it.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Level {
+    /// Things that went wrong. The only level shown by default.
+    Error,
+    /// Progress of long-running days.
+    Info,
+    /// Intermediate results, like the graph a day reduced its input to.
+    Debug,
+    /// Everything, even dumps inside loops.
+    Trace
+}
+
+static LEVEL: AtomicU8 = AtomicU8::new(Level::Error as u8);
+
+impl Level {
+    const ALL: [Level; 4] = [Level::Error, Level::Info, Level::Debug, Level::Trace];
+
+    /// The level for passing `-v` that many times.
+    pub fn from_verbosity(count: usize) -> Level {
+        Level::ALL[count.min(Level::ALL.len() - 1)]
+    }
+}
+
+impl FromStr for Level {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Level::ALL.into_iter().find(|level| level.to_string() == s)
+            .ok_or(Error::invalid(format!("Unknown log level {s}, expected error, info, debug or trace")))
+    }
+}
+impl Display for Level {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.pad(match self {
+            Level::Error => "error",
+            Level::Info => "info",
+            Level::Debug => "debug",
+            Level::Trace => "trace"
+        })
+    }
+}
+
+pub fn set_level(level: Level) {
+    LEVEL.store(level as u8, Ordering::Relaxed);
+}
+pub fn level() -> Level {
+    Level::ALL[LEVEL.load(Ordering::Relaxed) as usize]
+}
+pub fn enabled(level: Level) -> bool {
+    level <= self::level()
+}
+
+/// Used by the macros, which check [enabled] first so that disabled messages are never formatted.
+#[doc(hidden)]
+pub fn write(level: Level, args: Arguments) {
+    eprintln!("[{level:<5}] {args}");
+}
+
+#[macro_export]
+macro_rules! log {
+    ($level: expr, $($arg: tt)*) => {
+        if $crate::log::enabled($level) { $crate::log::write($level, format_args!($($arg)*)) }
+    }
+}
+#[macro_export]
+macro_rules! error { ($($arg: tt)*) => { $crate::log!($crate::log::Level::Error, $($arg)*) } }
+#[macro_export]
+macro_rules! info { ($($arg: tt)*) => { $crate::log!($crate::log::Level::Info, $($arg)*) } }
+#[macro_export]
+macro_rules! debug { ($($arg: tt)*) => { $crate::log!($crate::log::Level::Debug, $($arg)*) } }
+#[macro_export]
+macro_rules! trace { ($($arg: tt)*) => { $crate::log!($crate::log::Level::Trace, $($arg)*) } }
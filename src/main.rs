@@ -1,87 +1,32 @@
-extern crate core;
+mod all;
+mod batch;
+mod bench;
+mod cli;
+mod guide;
+mod report;
+mod rucksacks;
 
-#[macro_use]
-extern crate lazy_static;
-
-
-mod day01;
-mod utils;
-mod day02;
-mod day03;
-mod day04;
-mod day05;
-mod day06;
-mod day07;
-mod day08;
-mod day09;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day18;
-mod day19;
-mod day20;
-mod day21;
-mod day22;
-mod day23;
-mod day24;
-mod day25;
+use advent_of_code_2022::log;
+use advent_of_code_2022::utils::Error;
 
 fn main() {
-    // day01::run_sample();
-    // day01::run_actual();
-    // day02::run_sample(true);
-    // day02::run_actual(true);
-    // day03::run_sample();
-    // day03::run_actual();
-    // day04::run_sample();
-    // day04::run_actual();
-    // day05::run_sample();
-    // day05::run_actual();
-    // day06::run_sample(14);
-    // day06::run_actual(14);
-    // day07::run_sample();
-    // day07::run_actual();
-    // day08::run_sample();
-    // day08::run_actual();
-    // day09::run_sample_1();
-    // day09::run_actual_1();
-    // day09::run_sample_2();
-    // day09::run_actual_2();
-    // day10::run_sample();
-    // day10::run_actual();
-    // day11::run_sample(true);
-    // day11::run_actual(true);
-    // day12::run_sample();
-    // day12::run_actual();
-    // day13::run_sample();
-    // day13::run_actual();
-    // day14::run_sample();
-    // day14::run_actual();
-    // day15::run_sample();
-    // day15::run_actual();
-    // day16::run_sample();
-    // day16::run_actual();
-    // day17::run_sample();
-    // day17::run_actual();
-    // day18::run_sample();
-    // day18::run_actual();
-    // day19::run_sample();
-    day19::run_actual();
-    // day20::run_sample();
-    // day20::run_actual();
-    // day21::run_sample();
-    // day21::run_actual();
-    // day22::run_sample();
-    // day22::run_actual();
-    // day23::run_sample();
-    // day23::run_actual();
-    // day24::run_sample();
-    // day24::run_actual();
-    // day25::run_sample();
-    // day25::run_actual();
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let command = cli::take_log_level(&mut args).and_then(|level| {
+        log::set_level(level);
+        cli::parse_args(args.into_iter())
+    });
+    match command {
+        Ok(cli::Command::Run(args)) => Error::print(args.execute()),
+        Ok(cli::Command::Bench(args)) => Error::print(args.execute()),
+        Ok(cli::Command::All(args)) => Error::print(args.execute()),
+        Ok(cli::Command::Batch(args)) => Error::print(args.execute()),
+        Ok(cli::Command::Generate(args)) => Error::print(args.execute()),
+        Ok(cli::Command::Guide(args)) => Error::print(args.execute()),
+        Ok(cli::Command::Rucksacks(args)) => Error::print(args.execute()),
+        Ok(cli::Command::Help) => println!("{}", cli::USAGE),
+        Err(err) => {
+            eprintln!("Error: {err}\n\n{}", cli::USAGE);
+            std::process::exit(2);
+        }
+    }
 }
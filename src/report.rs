@@ -0,0 +1,93 @@
+use std::panic;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use advent_of_code_2022::input::InputSource;
+use advent_of_code_2022::solution::{solution_for, Answer, Params};
+use advent_of_code_2022::utils::{Error, Part};
+
+/// How results are printed. Text is meant for humans, the others for dashboards and the README.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum Format {
+    Text, Json, Markdown
+}
+impl FromStr for Format {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "markdown" => Ok(Format::Markdown),
+            _ => Err(Error::invalid(format!("Unknown format {s}")))
+        }
+    }
+}
+
+/// The outcome of one part of one day. The duration includes parsing the input.
+pub(crate) struct PartResult {
+    pub(crate) day: u8,
+    pub(crate) part: Part,
+    pub(crate) answer: Result<Answer, String>,
+    pub(crate) duration: Duration
+}
+
+fn solve(day: u8, part: Part, input: &InputSource, params: &Params) -> Result<Answer, Error> {
+    let solution = solution_for(day, params)?;
+    let path = input.path(day, part)?;
+    let parsed = solution.parse_any(&input.read(day, part)?).map_err(|err| err.in_file(&path))?;
+    solution.solve_any(parsed.as_ref(), part)
+}
+
+/// Solves one part of a day. Panics are caught, so that a broken day can't take the others down with it.
+pub(crate) fn solve_part(day: u8, part: Part, input: &InputSource, params: &Params) -> PartResult {
+    let start = Instant::now();
+    let answer = match panic::catch_unwind(|| solve(day, part, input, params)) {
+        Ok(result) => result.map_err(|err| err.to_string()),
+        Err(payload) => Err(format!("Panicked: {}", payload.downcast_ref::<&str>().map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_default()))
+    };
+    PartResult { day, part, answer, duration: start.elapsed() }
+}
+
+fn json_string(s: &str) -> String {
+    let mut escaped = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => escaped += "\\\"",
+            '\\' => escaped += "\\\\",
+            '\n' => escaped += "\\n",
+            c if (c as u32) < 0x20 => escaped += &format!("\\u{:04x}", c as u32),
+            c => escaped.push(c)
+        }
+    }
+    escaped + "\""
+}
+
+/// One object per part. Integer answers are numbers, everything else is a string, images have one line per row.
+pub(crate) fn to_json(results: &[PartResult]) -> String {
+    let entries: Vec<String> = results.iter().map(|result| {
+        let (answer, error) = match &result.answer {
+            Ok(Answer::Int(num)) => (num.to_string(), "null".to_string()),
+            Ok(answer) => (json_string(&answer.to_string()), "null".to_string()),
+            Err(err) => ("null".to_string(), json_string(err))
+        };
+        format!("  {{\"day\": {}, \"part\": {}, \"answer\": {answer}, \"duration_ns\": {}, \"error\": {error}}}",
+                result.day, result.part, result.duration.as_nanos())
+    }).collect();
+    format!("[\n{}\n]", entries.join(",\n"))
+}
+
+/// A table with one row per part. Errors are reduced to their first line.
+pub(crate) fn to_markdown(results: &[PartResult]) -> String {
+    let escape = |s: &str| s.replace('|', "\\|");
+    let mut table = String::from("| Day | Part | Answer | Time |\n|----:|-----:|--------|-----:|\n");
+    for result in results {
+        let answer = match &result.answer {
+            Ok(Answer::Image(rows)) => format!("<pre>{}</pre>", rows.join("<br>")),
+            Ok(answer) => format!("`{}`", escape(&answer.to_string())),
+            Err(err) => format!("**Error:** {}", escape(err.lines().next().unwrap_or_default()))
+        };
+        table += &format!("| {} | {} | {answer} | {:.2?} |\n", result.day, result.part, result.duration);
+    }
+    table
+}
@@ -0,0 +1,24 @@
+use advent_of_code_2022::config::Config;
+use advent_of_code_2022::day03::Day03;
+use advent_of_code_2022::input::InputSource;
+use advent_of_code_2022::solution::{Params, Solution};
+use advent_of_code_2022::utils::{Error, Part};
+
+pub(crate) struct RucksackArgs {
+    pub(crate) input: InputSource,
+    pub(crate) settings: Vec<(String, String)>,
+    pub(crate) config: Option<String>
+}
+
+impl RucksackArgs {
+    pub(crate) fn execute(&self) -> Result<(), Error> {
+        let params = Params { overrides: self.settings.clone(), ..Params::new(3, &self.input, &Config::load(self.config.as_deref())?) };
+        let day = Day03::from_params(&params)?;
+        let path = self.input.path(3, Part::One)?;
+        let rucksacks = day.parse(&self.input.read(3, Part::One)?).map_err(|err| err.in_file(&path))?;
+        for line in day.describe(&rucksacks)? {
+            println!("{line}");
+        }
+        Ok(())
+    }
+}
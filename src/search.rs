@@ -0,0 +1,116 @@
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+/// The cheapest way from the start to a goal. `states` begins with the start and ends with the goal.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Path<S> {
+    pub cost: u64,
+    pub states: Vec<S>
+}
+
+/// Every state seen so far, numbered in the order they were found, with the state they were reached from.
+struct Visited<S> {
+    states: Vec<S>,
+    index: HashMap<S, usize>,
+    parent: Vec<Option<usize>>,
+    cost: Vec<u64>
+}
+
+impl<S: Clone + Eq + Hash> Visited<S> {
+    fn new(start: S) -> Visited<S> {
+        Visited { states: vec![start.clone()], index: HashMap::from([(start, 0)]), parent: vec![None], cost: vec![0] }
+    }
+
+    /// Records that `state` can be reached for `cost` via `parent`. Returns its number, unless it was already
+    /// known to be reachable at least as cheaply.
+    fn reach(&mut self, state: S, parent: usize, cost: u64) -> Option<usize> {
+        match self.index.entry(state) {
+            Entry::Occupied(entry) => {
+                let i = *entry.get();
+                if self.cost[i] <= cost { return None }
+                self.cost[i] = cost;
+                self.parent[i] = Some(parent);
+                Some(i)
+            },
+            Entry::Vacant(entry) => {
+                let i = self.states.len();
+                self.states.push(entry.key().clone());
+                entry.insert(i);
+                self.parent.push(Some(parent));
+                self.cost.push(cost);
+                Some(i)
+            }
+        }
+    }
+
+    fn path_to(&self, mut i: usize) -> Path<S> {
+        let cost = self.cost[i];
+        let mut states = vec![self.states[i].clone()];
+        while let Some(parent) = self.parent[i] {
+            states.push(self.states[parent].clone());
+            i = parent;
+        }
+        states.reverse();
+        Path { cost, states }
+    }
+}
+
+/// Finds the cheapest path from `start` to any state accepted by `is_goal`. `neighbors` lists the states
+/// reachable in one step together with the cost of that step. `heuristic` estimates the cost left to reach a goal
+/// and must never overestimate it, or the path found might not be the cheapest. States are searched again when a
+/// cheaper way to them turns up, so the heuristic does not need to be consistent.
+pub fn a_star<S, I>(
+    start: S,
+    mut neighbors: impl FnMut(&S) -> I,
+    heuristic: impl Fn(&S) -> u64,
+    is_goal: impl Fn(&S) -> bool
+) -> Option<Path<S>> where S: Clone + Eq + Hash, I: IntoIterator<Item=(S, u64)> {
+    let mut queue = BinaryHeap::from([Reverse((heuristic(&start), 0usize))]);
+    let mut visited = Visited::new(start);
+    let mut is_done = vec![false];
+    while let Some(Reverse((_, i))) = queue.pop() {
+        if is_done[i] { continue }
+        is_done[i] = true;
+        let state = visited.states[i].clone();
+        if is_goal(&state) {
+            return Some(visited.path_to(i))
+        }
+        let cost = visited.cost[i];
+        for (next, step_cost) in neighbors(&state) {
+            let estimate = cost + step_cost + heuristic(&next);
+            if let Some(next_i) = visited.reach(next, i, cost + step_cost) {
+                if next_i == is_done.len() { is_done.push(false) } else { is_done[next_i] = false }
+                queue.push(Reverse((estimate, next_i)));
+            }
+        }
+    }
+    None
+}
+
+/// Like [a_star], without any knowledge about where the goals are.
+pub fn dijkstra<S, I>(start: S, neighbors: impl FnMut(&S) -> I, is_goal: impl Fn(&S) -> bool) -> Option<Path<S>>
+    where S: Clone + Eq + Hash, I: IntoIterator<Item=(S, u64)> {
+    a_star(start, neighbors, |_| 0, is_goal)
+}
+
+/// Finds the path with the fewest steps from `start` to any state accepted by `is_goal`.
+pub fn bfs<S, I>(start: S, mut neighbors: impl FnMut(&S) -> I, is_goal: impl Fn(&S) -> bool) -> Option<Path<S>>
+    where S: Clone + Eq + Hash, I: IntoIterator<Item=S> {
+    let mut queue = VecDeque::from([0usize]);
+    let mut visited = Visited::new(start);
+    while let Some(i) = queue.pop_front() {
+        let state = visited.states[i].clone();
+        if is_goal(&state) {
+            return Some(visited.path_to(i))
+        }
+        let cost = visited.cost[i] + 1;
+        for next in neighbors(&state) {
+            if let Some(next_i) = visited.reach(next, i, cost) {
+                queue.push_back(next_i);
+            }
+        }
+    }
+    None
+}
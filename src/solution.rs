@@ -0,0 +1,227 @@
+use std::any::Any;
+use std::fmt::{Display, Formatter};
+use std::io::BufRead;
+use std::path::Path;
+use std::str::FromStr;
+use crate::config::Config;
+use crate::day22::CubeLayout;
+use crate::input::InputSource;
+use crate::visualize::Visualize;
+use crate::utils::{Error, Part};
+use crate::{day01, day02, day03, day04, day05, day06, day07, day08, day09, day10, day11, day12, day13,
+            day14, day15, day16, day17, day18, day19, day20, day21, day22, day23, day24, day25};
+
+/// The result of solving one part of a puzzle.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Answer {
+    Int(i64),
+    Text(String),
+    /// A multi-line picture that has to be read by a human, like the CRT output of day 10.
+    Image(Vec<String>)
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Int(num) => write!(f, "{num}"),
+            Answer::Text(text) => f.write_str(text),
+            Answer::Image(rows) => f.write_str(&rows.join("\n"))
+        }
+    }
+}
+
+macro_rules! answer_from_int {
+    ($($t: ty),*) => {$(
+        impl From<$t> for Answer {
+            fn from(num: $t) -> Self { Answer::Int(num as i64) }
+        }
+    )*}
+}
+answer_from_int!(i32, i64, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self { Answer::Text(text) }
+}
+
+/// A puzzle of one day: the input is parsed once and then handed to both parts.
+pub trait Solution {
+    type Input;
+    fn parse(&self, input: &str) -> Result<Self::Input, Error>;
+    fn part1(&self, input: &Self::Input) -> Result<Answer, Error>;
+    fn part2(&self, input: &Self::Input) -> Result<Answer, Error>;
+
+    /// Lines worth printing after the answers, like which elves carry the most in day 1. Most days have none.
+    fn report(&self, _input: &Self::Input) -> Vec<String> {
+        Vec::new()
+    }
+    /// Reads the input line by line instead of parsing it at once, for inputs too big to keep in memory.
+    /// `None` for the days that need all of it.
+    fn stream(&self, _reader: &mut dyn BufRead) -> Option<Result<Box<dyn Streamed>, Error>> {
+        None
+    }
+}
+
+/// An input read by [Solution::stream], which answers the parts like the parsed one would.
+pub trait Streamed {
+    fn solve(&self, part: Part) -> Result<Answer, Error>;
+    /// Like [Solution::report].
+    fn report(&self) -> Vec<String>;
+}
+
+/// Object safe view on a [Solution], so that all days can be handled alike.
+pub trait AnySolution {
+    fn parse_any(&self, input: &str) -> Result<Box<dyn Any>, Error>;
+    fn solve_any(&self, input: &dyn Any, part: Part) -> Result<Answer, Error>;
+    fn report_any(&self, input: &dyn Any) -> Result<Vec<String>, Error>;
+    fn stream_any(&self, reader: &mut dyn BufRead) -> Option<Result<Box<dyn Streamed>, Error>>;
+}
+
+impl<S: Solution> AnySolution for S where S::Input: 'static {
+    fn parse_any(&self, input: &str) -> Result<Box<dyn Any>, Error> {
+        Ok(Box::new(self.parse(input)?))
+    }
+    fn solve_any(&self, input: &dyn Any, part: Part) -> Result<Answer, Error> {
+        let input = input.downcast_ref::<S::Input>().ok_or(Error::invalid("Input was parsed by another day"))?;
+        match part {
+            Part::One => self.part1(input),
+            Part::Two => self.part2(input)
+        }
+    }
+    fn report_any(&self, input: &dyn Any) -> Result<Vec<String>, Error> {
+        let input = input.downcast_ref::<S::Input>().ok_or(Error::invalid("Input was parsed by another day"))?;
+        Ok(self.report(input))
+    }
+    fn stream_any(&self, reader: &mut dyn BufRead) -> Option<Result<Box<dyn Streamed>, Error>> {
+        self.stream(reader)
+    }
+}
+
+/// Per-day knobs that are not part of the puzzle input. Unset values fall back to the puzzle's defaults,
+/// which sometimes differ between the sample and the actual input.
+#[derive(Clone, Default)]
+pub struct Params {
+    pub sample: bool,
+    /// The file name of the input, which selects its own section of the config.
+    pub input_name: Option<String>,
+    pub config: Config,
+    /// Settings given on the command line as `(key, value)`. They win over the config.
+    pub overrides: Vec<(String, String)>,
+    /// Records the simulations of the days that have one.
+    pub visualize: Option<Visualize>
+}
+
+impl Params {
+    /// The params for solving `day` on `input`, with the sample defaults for inputs that look like samples.
+    pub fn new(day: u8, input: &InputSource, config: &Config) -> Params {
+        let input_name = match input {
+            InputSource::Stdin => None,
+            // Without an input directory there is no input to name, which reading it reports
+            _ => input.path(day, Part::One).ok()
+                .and_then(|path| Path::new(&path).file_name().map(|name| name.to_string_lossy().to_string()))
+        };
+        Params { sample: input.is_sample(), input_name, config: config.clone(), overrides: Vec::new(), visualize: None }
+    }
+
+    /// Looks up a setting: the command line first, then the section of the input in the config, then the one of the day.
+    pub fn get_with<T>(&self, day: u8, key: &str, parse: impl Fn(&str) -> Result<T, Error>) -> Result<Option<T>, Error> {
+        if let Some((_, value)) = self.overrides.iter().rev().find(|(k, _)| k == key) {
+            return parse(value).map(Some).map_err(|err| match err {
+                Error::Parse { message, .. } | Error::InvalidInput(message) => Error::invalid(format!("Invalid value {value} for {key}: {message}")),
+                err => err
+            })
+        }
+        self.config.get(day, self.input_name.as_deref(), key, parse)
+    }
+    pub fn get<T: FromStr>(&self, day: u8, key: &str) -> Result<Option<T>, Error> where T::Err: Into<Error> {
+        self.get_with(day, key, |value| value.parse::<T>().map_err(Into::into))
+    }
+    /// Like [Params::get], for settings that count something and make no sense below 1.
+    pub fn get_positive<T: FromStr + PartialOrd + From<u8>>(&self, day: u8, key: &str) -> Result<Option<T>, Error> where T::Err: Into<Error> {
+        self.get_with(day, key, |value| {
+            let parsed = value.parse::<T>().map_err(Into::into)?;
+            if parsed < T::from(1) {
+                return Err(Error::invalid(format!("{key} in [day{day:02}] has to be at least 1, found {value}")))
+            }
+            Ok(parsed)
+        })
+    }
+
+    /// Fails for settings on the command line or in the config the day does not know.
+    fn check_keys(&self, day: u8, known: &[&str]) -> Result<(), Error> {
+        if let Some((key, _)) = self.overrides.iter().find(|(key, _)| !known.contains(&key.as_str())) {
+            return Err(Error::invalid(format!("Day {day} has no setting {key}")))
+        }
+        self.config.check_keys(day, known)
+    }
+}
+
+/// The settings every day understands, to catch typos.
+fn settings_of(day: u8) -> &'static [&'static str] {
+    match day {
+        1 => &["top"],
+        2 => &["symbols", "outcome_scores", "first_column", "second_column", "outcome_column"],
+        3 => &["compartments", "group_size", "priorities"],
+        6 => &["marker_len"],
+        7 => &["disk_size", "needed_space"],
+        11 => &["rounds_part1", "rounds_part2", "relief"],
+        15 => &["row", "max"],
+        16 => &["minutes_part1", "minutes_part2"],
+        17 => &["rocks_part1", "rocks_part2"],
+        22 => &["cube_layout", "face_len", "face_locations", "edges_part1", "edges_part2"],
+        _ => &[]
+    }
+}
+
+pub fn solution_for(day: u8, params: &Params) -> Result<Box<dyn AnySolution>, Error> {
+    params.check_keys(day, settings_of(day))?;
+    Ok(match day {
+        1 => Box::new(day01::Day01::from_params(params)?),
+        2 => Box::new(day02::Day02::from_params(params)?),
+        3 => Box::new(day03::Day03::from_params(params)?),
+        4 => Box::new(day04::Day04),
+        5 => Box::new(day05::Day05),
+        6 => Box::new(day06::Day06 { marker_len: params.get_positive(6, "marker_len")? }),
+        7 => Box::new(day07::Day07 {
+            disk_size: params.get(7, "disk_size")?.unwrap_or(70000000),
+            needed_space: params.get(7, "needed_space")?.unwrap_or(30000000)
+        }),
+        8 => Box::new(day08::Day08),
+        9 => Box::new(day09::Day09),
+        10 => Box::new(day10::Day10),
+        11 => Box::new(day11::Day11 {
+            rounds: [params.get_positive(11, "rounds_part1")?.unwrap_or(20), params.get_positive(11, "rounds_part2")?.unwrap_or(10000)],
+            relief: params.get_positive(11, "relief")?.unwrap_or(3)
+        }),
+        12 => Box::new(day12::Day12),
+        13 => Box::new(day13::Day13),
+        14 => Box::new(day14::Day14 { visualize: params.visualize.clone() }),
+        15 => {
+            let (default_row, default_max) = if params.sample {(10, 20)} else {(2000000, 4000000)};
+            Box::new(day15::Day15 { row: params.get(15, "row")?.unwrap_or(default_row), max: params.get(15, "max")?.unwrap_or(default_max) })
+        },
+        16 => Box::new(day16::Day16 {
+            minutes: [params.get(16, "minutes_part1")?.unwrap_or(30), params.get(16, "minutes_part2")?.unwrap_or(26)]
+        }),
+        17 => Box::new(day17::Day17 {
+            rocks: [params.get(17, "rocks_part1")?.unwrap_or(2022), params.get(17, "rocks_part2")?.unwrap_or(1000000000000)],
+            visualize: params.visualize.clone()
+        }),
+        18 => Box::new(day18::Day18),
+        19 => Box::new(day19::Day19),
+        20 => Box::new(day20::Day20),
+        21 => Box::new(day21::Day21),
+        22 => {
+            let mut layout = params.get(22, "cube_layout")?
+                .unwrap_or_else(|| if params.sample {CubeLayout::sample()} else {CubeLayout::actual()});
+            if let Some(face_len) = params.get_positive(22, "face_len")? { layout.face_len = face_len }
+            if let Some(locations) = params.get_with(22, "face_locations", CubeLayout::parse_face_locations)? { layout.face_locations = locations }
+            if let Some(edges) = params.get_with(22, "edges_part1", CubeLayout::parse_edges)? { layout.edges[0] = edges }
+            if let Some(edges) = params.get_with(22, "edges_part2", CubeLayout::parse_edges)? { layout.edges[1] = edges }
+            Box::new(day22::Day22 { layout })
+        },
+        23 => Box::new(day23::Day23 { visualize: params.visualize.clone() }),
+        24 => Box::new(day24::Day24 { visualize: params.visualize.clone() }),
+        25 => Box::new(day25::Day25),
+        other => return Err(Error::invalid(format!("There is no day {other}")))
+    })
+}
@@ -1,60 +1,209 @@
-use std::fs::File;
-use std::io;
-use std::io::BufRead;
+use std::fmt::{Display, Formatter};
+use std::{fs, io};
 use std::num::ParseIntError;
-use std::path::Path;
+use std::str::FromStr;
 
-pub(crate) fn read_lines<P>(path: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(path)?;
-    Ok(io::BufReader::new(file).lines())
+mod grid;
+mod parse;
+pub use grid::{Grid, GrowingGrid};
+pub use parse::{char_map, parse_list, parse_nums, Field, FromField, FromFields, Pattern};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Part {
+    One, Two
+}
+impl Display for Part {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.pad(match self {
+            Part::One => "1",
+            Part::Two => "2"
+        })
+    }
+}
+
+/// Where in the input a parse error occurred.
+#[derive(Clone, Debug, Default)]
+pub struct Location {
+    pub file: Option<String>,
+    /// Index of the line, if already known.
+    pub line: Option<usize>,
+    /// Byte offset of the offending text in the line, and its length.
+    pub offset: usize,
+    pub len: usize,
+    pub text: String
 }
 
-pub(crate) fn read_lines_to_vec<P>(path: P) -> Result<Vec<String>,std::io::Error>
-where P: AsRef<Path>, {
-    Ok(read_lines(path)?.collect::<Result<Vec<String>,std::io::Error>>()?)
+#[derive(Debug)]
+pub enum Error {
+    Io { path: Option<String>, source: io::Error },
+    /// The input does not have the expected format.
+    Parse { message: String, location: Option<Location>, source: Option<Box<dyn std::error::Error + Send + Sync>> },
+    /// The input is well-formed, but does not make sense for the puzzle.
+    InvalidInput(String),
+    NoSolution(String)
 }
 
-pub(crate) struct ErrorMsg {
-    pub(crate) wrapped: String
+impl Error {
+    pub fn parse(message: impl Into<String>) -> Error {
+        Error::Parse { message: message.into(), location: None, source: None }
+    }
+    /// A parse error pointing at `len` bytes starting at `offset` in the text being parsed.
+    pub fn parse_at(message: impl Into<String>, offset: usize, len: usize) -> Error {
+        Error::Parse { message: message.into(), location: Some(Location { offset, len, ..Location::default() }), source: None }
+    }
+    pub fn invalid(message: impl Into<String>) -> Error {
+        Error::InvalidInput(message.into())
+    }
+    pub fn no_solution(message: impl Into<String>) -> Error {
+        Error::NoSolution(message.into())
+    }
+
+    /// Moves the location of a parse error by `by` bytes, for when a substring was parsed.
+    pub fn shifted(mut self, by: usize) -> Error {
+        if let Error::Parse { location: Some(location), .. } = &mut self {
+            location.offset += by;
+        }
+        self
+    }
+    /// Points a parse error that has no location yet at `len` bytes starting at `offset`.
+    pub fn located(mut self, offset: usize, len: usize) -> Error {
+        if let Error::Parse { location: location @ None, .. } = &mut self {
+            *location = Some(Location { offset, len, ..Location::default() });
+        }
+        self
+    }
+    /// Attaches the line a parse error occurred in. Without a more precise location, the whole line is blamed.
+    pub fn on_line(mut self, index: usize, text: &str) -> Error {
+        if let Error::Parse { location, .. } = &mut self {
+            let location = location.get_or_insert_with(|| Location { len: text.len(), ..Location::default() });
+            if location.line.is_none() {
+                location.line = Some(index);
+                location.text = text.to_string();
+            }
+        }
+        self
+    }
+    pub fn in_file(mut self, file: &str) -> Error {
+        match &mut self {
+            Error::Io { path, .. } => { path.get_or_insert_with(|| file.to_string()); },
+            Error::Parse { location: Some(location), .. } => { location.file.get_or_insert_with(|| file.to_string()); },
+            _ => {}
+        }
+        self
+    }
+
+    /// Prints the error, if any, and exits with a failure status so that scripts notice.
+    pub fn print(result: Result<(), Error>) {
+        if let Err(err) = result {
+            eprintln!("Error: {err}");
+            let mut source = std::error::Error::source(&err);
+            while let Some(cause) = source {
+                eprintln!("Caused by: {cause}");
+                source = cause.source();
+            }
+            std::process::exit(1);
+        }
+    }
 }
 
-impl ErrorMsg {
-    pub(crate) fn print(result: Result<(), ErrorMsg>) -> () {
-        match result {
-            Ok(val) => val,
-            Err(err) => println!("Error: {}", err.wrapped)
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Io { path: Some(path), .. } => write!(f, "Failed to read {path}"),
+            Error::Io { path: None, .. } => write!(f, "IO error"),
+            Error::Parse { message, location: Some(Location { file, line: Some(line), offset, len, text }), .. } => {
+                let line_num = (line + 1).to_string();
+                let pad = " ".repeat(line_num.len());
+                // Offsets are in bytes, the column and the caret count characters
+                let chars = |range: std::ops::Range<usize>| text.get(range.clone()).map_or(range.len(), |part| part.chars().count());
+                let (column, width) = (chars(0..*offset), chars(*offset..offset + len));
+                if let Some(file) = file { write!(f, "{file}:")?; }
+                writeln!(f, "{line_num}:{}: {message}", column + 1)?;
+                writeln!(f, "{pad} |")?;
+                writeln!(f, "{line_num} | {text}")?;
+                write!(f, "{pad} | {}{}", " ".repeat(column), "^".repeat(width.max(1)))
+            },
+            Error::Parse { message, .. } => write!(f, "Parse error: {message}"),
+            Error::InvalidInput(message) => write!(f, "Invalid input: {message}"),
+            Error::NoSolution(message) => write!(f, "No solution: {message}")
         }
     }
-    pub(crate) fn new(string: &str) -> ErrorMsg {
-        ErrorMsg { wrapped: string.to_string() }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            Error::Parse { source: Some(source), .. } => Some(source.as_ref()),
+            _ => None
+        }
     }
 }
 
-impl From<io::Error> for ErrorMsg {
+impl From<io::Error> for Error {
     fn from(err: io::Error) -> Self {
-       ErrorMsg { wrapped: format!("IO error: {}", err.to_string()) }
+        Error::Io { path: None, source: err }
     }
 }
-impl From<ParseIntError> for ErrorMsg {
+impl From<ParseIntError> for Error {
     fn from(err: ParseIntError) -> Self {
-       ErrorMsg { wrapped: format!("ParseIntError: {}", err.to_string()) }
+        Error::Parse { message: "Not a valid number".to_string(), location: None, source: Some(Box::new(err)) }
     }
 }
-impl From<regex::Error> for ErrorMsg {
-    fn from(err: regex::Error) -> Self {
-       ErrorMsg { wrapped: format!("Failed to compile regex: {}", err.to_string()) }
-    }
+
+pub fn read_input(path: &str) -> Result<String, Error> {
+    fs::read_to_string(path).map_err(|err| Error::from(err).in_file(path))
+}
+
+/// Parses a number, blaming the whole string if that fails.
+pub fn parse_num<T: FromStr<Err=ParseIntError>>(s: &str) -> Result<T, Error> {
+    s.parse::<T>().map_err(|err| Error::Parse {
+        message: format!("'{s}' is not a valid number"),
+        location: Some(Location { len: s.len(), ..Location::default() }),
+        source: Some(Box::new(err))
+    })
+}
+
+/// Parses every line on its own, so that errors point at the line they occurred in.
+pub fn parse_lines<T>(input: &str, parse: impl Fn(&str) -> Result<T, Error>) -> Result<Vec<T>, Error> {
+    input.lines().enumerate().map(|(i, line)| parse(line).map_err(|err| err.on_line(i, line))).collect()
+}
+
+/// Consecutive non-blank lines of the input, like one monkey or one pair of packets.
+pub struct Record<'a> {
+    /// Index of the first line in the input.
+    pub start: usize,
+    /// The lines, without trailing whitespace.
+    pub lines: Vec<&'a str>
 }
-impl From<String> for ErrorMsg {
-    fn from(err: String) -> Self {
-       ErrorMsg { wrapped: err }
+
+impl<'a> Record<'a> {
+    /// The lines together with their index in the input, for use with [Error::on_line].
+    pub fn numbered_lines(&self) -> impl Iterator<Item=(usize, &'a str)> + '_ {
+        self.lines.iter().enumerate().map(|(i, line)| (self.start + i, *line))
+    }
+    /// Parses every line of the record on its own, like [parse_lines].
+    pub fn parse_lines<T>(&self, parse: impl Fn(&str) -> Result<T, Error>) -> Result<Vec<T>, Error> {
+        self.numbered_lines().map(|(i, line)| parse(line).map_err(|err| err.on_line(i, line))).collect()
     }
 }
-impl From<&str> for ErrorMsg {
-    fn from(err: &str) -> Self {
-       ErrorMsg { wrapped: err.to_string() }
+
+/// Splits the input into records separated by blank lines. Lines consisting of whitespace count as blank,
+/// so trailing whitespace, CRLF line endings and any number of separating or final blank lines are fine.
+pub fn records(input: &str) -> Vec<Record<'_>> {
+    let mut records = Vec::new();
+    let mut current: Option<Record> = None;
+    for (i, line) in input.lines().enumerate() {
+        let line = line.trim_end();
+        match (&mut current, line.is_empty()) {
+            (None, true) => {},
+            (None, false) => current = Some(Record { start: i, lines: vec![line] }),
+            (Some(record), false) => record.lines.push(line),
+            (Some(_), true) => records.extend(current.take())
+        }
     }
+    records.extend(current);
+    records
 }
 
 #[macro_export] macro_rules! hashmap {
@@ -0,0 +1,214 @@
+use std::fmt::{Display, Formatter};
+use std::ops::{Index, IndexMut, Range};
+use crate::utils::Error;
+
+const NEIGHBORS4: [(i64, i64); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+const NEIGHBORS8: [(i64, i64); 8] = [(-1, -1), (0, -1), (1, -1), (1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0)];
+
+/// A rectangular grid stored row by row. Positions are `(x, y)`, with `y` growing downwards like in the input.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Grid<T> where T: Clone {
+        Grid { width, height, cells: vec![fill; width * height] }
+    }
+
+    /// Fails if the rows are not all of the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Grid<T>, Error> {
+        let width = rows.first().map_or(0, |row| row.len());
+        let height = rows.len();
+        let mut cells = Vec::with_capacity(width * height);
+        for (y, row) in rows.into_iter().enumerate() {
+            if row.len() != width {
+                return Err(Error::invalid(format!("Row {y} has {} cells, expected {width} like the first one", row.len())))
+            }
+            cells.extend(row);
+        }
+        Ok(Grid { width, height, cells })
+    }
+
+    /// Parses a map with one character per cell. Errors returned by `cell` without a location
+    /// point at the character they were returned for.
+    pub fn parse(input: &str, cell: impl Fn(char) -> Result<T, Error>) -> Result<Grid<T>, Error> {
+        let width = input.lines().next().ok_or(Error::parse("Map is empty"))?.chars().count();
+        let mut cells = Vec::new();
+        let mut height = 0;
+        for (y, line) in input.lines().enumerate() {
+            let mut row_width = 0;
+            for (offset, c) in line.char_indices() {
+                cells.push(cell(c).map_err(|err| err.located(offset, c.len_utf8()).on_line(y, line))?);
+                row_width += 1;
+            }
+            if row_width != width {
+                return Err(Error::parse(format!("Expected {width} columns like in the first row, found {row_width}")).on_line(y, line))
+            }
+            height += 1;
+        }
+        Ok(Grid { width, height, cells })
+    }
+
+    pub fn width(&self) -> usize { self.width }
+    pub fn height(&self) -> usize { self.height }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        if x < self.width && y < self.height { Some(&self.cells[y * self.width + x]) } else { None }
+    }
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        if x < self.width && y < self.height { Some(&mut self.cells[y * self.width + x]) } else { None }
+    }
+    /// Like [Grid::get], but positions left of or above the grid are simply outside of it.
+    pub fn get_signed(&self, x: i64, y: i64) -> Option<&T> {
+        self.to_unsigned(x, y).and_then(|(x, y)| self.get(x, y))
+    }
+    pub fn get_signed_mut(&mut self, x: i64, y: i64) -> Option<&mut T> {
+        self.to_unsigned(x, y).and_then(|(x, y)| self.get_mut(x, y))
+    }
+    fn to_unsigned(&self, x: i64, y: i64) -> Option<(usize, usize)> {
+        Some((usize::try_from(x).ok()?, usize::try_from(y).ok()?))
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item=&[T]> {
+        (0..self.height).map(|y| self.row(y))
+    }
+    /// The cells of column `x` from top to bottom.
+    pub fn column(&self, x: usize) -> impl DoubleEndedIterator<Item=&T> + ExactSizeIterator {
+        assert!(x < self.width, "Column {x} is outside of a grid of width {}", self.width);
+        (0..self.height).map(move |y| &self.cells[y * self.width + x])
+    }
+
+    /// All positions, row by row.
+    pub fn positions(&self) -> impl Iterator<Item=(usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+    pub fn iter(&self) -> impl Iterator<Item=((usize, usize), &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+    pub fn position(&self, predicate: impl Fn(&T) -> bool) -> Option<(usize, usize)> {
+        self.cells.iter().position(predicate).map(|i| (i % self.width, i / self.width))
+    }
+
+    /// The positions right of, below, left of and above `(x, y)` that are inside the grid.
+    pub fn neighbors4(&self, x: usize, y: usize) -> impl Iterator<Item=(usize, usize)> + '_ {
+        self.offset_by(x, y, &NEIGHBORS4)
+    }
+    /// All eight surrounding positions inside the grid, clockwise from the top left.
+    pub fn neighbors8(&self, x: usize, y: usize) -> impl Iterator<Item=(usize, usize)> + '_ {
+        self.offset_by(x, y, &NEIGHBORS8)
+    }
+    fn offset_by(&self, x: usize, y: usize, offsets: &'static [(i64, i64)]) -> impl Iterator<Item=(usize, usize)> + '_ {
+        offsets.iter()
+            .filter_map(move |(dx, dy)| self.to_unsigned(x as i64 + dx, y as i64 + dy))
+            .filter(|&(x, y)| x < self.width && y < self.height)
+    }
+
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid { width: self.width, height: self.height, cells: self.cells.iter().map(f).collect() }
+    }
+
+    /// Draws the grid with one character per cell, for cells that don't implement Display the way the puzzle draws them.
+    pub fn render(&self, cell: impl Fn(&T) -> char) -> String {
+        self.rows().map(|row| row.iter().map(&cell).collect::<String>()).collect::<Vec<_>>().join("\n")
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        self.get(x, y).unwrap_or_else(|| panic!("({x}, {y}) is outside of the {}x{} grid", self.width, self.height))
+    }
+}
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(x, y).unwrap_or_else(|| panic!("({x}, {y}) is outside of the {width}x{height} grid"))
+    }
+}
+
+/// One line per row. Cells wider than one character are right-aligned and separated by spaces, so that numbers line up.
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let cells: Vec<String> = self.cells.iter().map(|cell| cell.to_string()).collect();
+        let cell_width = cells.iter().map(|cell| cell.chars().count()).max().unwrap_or(0);
+        for (y, row) in cells.chunks(self.width.max(1)).enumerate() {
+            if y > 0 { writeln!(f)?; }
+            if cell_width <= 1 {
+                write!(f, "{}", row.concat())?;
+            } else {
+                write!(f, "{}", row.iter().map(|cell| format!("{cell:>cell_width$}")).collect::<Vec<_>>().join(" "))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A grid over signed coordinates that grows whenever a cell outside of it is set.
+/// Cells that were never set hold `fill`.
+#[derive(Clone, Debug)]
+pub struct GrowingGrid<T> {
+    grid: Grid<T>,
+    /// The coordinates of the top left cell of `grid`.
+    origin: (i64, i64),
+    fill: T
+}
+
+impl<T: Clone> GrowingGrid<T> {
+    pub fn new(fill: T) -> GrowingGrid<T> {
+        GrowingGrid { grid: Grid::new(0, 0, fill.clone()), origin: (0, 0), fill }
+    }
+    /// Starts out with `grid`, keeping its coordinates.
+    pub fn from_grid(grid: Grid<T>, fill: T) -> GrowingGrid<T> {
+        GrowingGrid { grid, origin: (0, 0), fill }
+    }
+
+    pub fn get(&self, x: i64, y: i64) -> &T {
+        self.grid.get_signed(x - self.origin.0, y - self.origin.1).unwrap_or(&self.fill)
+    }
+    pub fn set(&mut self, x: i64, y: i64, value: T) {
+        self.grow_to(x, y);
+        let cell = self.grid.get_signed_mut(x - self.origin.0, y - self.origin.1);
+        *cell.expect("Grid has grown to contain the cell") = value;
+    }
+
+    /// The range of x coordinates currently stored. Everything outside of it is `fill`.
+    pub fn x_range(&self) -> Range<i64> { self.origin.0..self.origin.0 + self.grid.width as i64 }
+    pub fn y_range(&self) -> Range<i64> { self.origin.1..self.origin.1 + self.grid.height as i64 }
+
+    /// The cells stored so far. Its top left cell is at `(x_range().start, y_range().start)`.
+    pub fn grid(&self) -> &Grid<T> { &self.grid }
+
+    /// Grows by at least half of the current size in every direction that lacks room, so that growing
+    /// cell by cell doesn't copy the grid every time.
+    fn grow_to(&mut self, x: i64, y: i64) {
+        let (xs, ys) = (self.x_range(), self.y_range());
+        if xs.contains(&x) && ys.contains(&y) { return }
+        let grow = |range: Range<i64>, to: i64| -> Range<i64> {
+            if range.is_empty() { return to..to + 1 }
+            let margin = ((range.end - range.start) / 2).max(4);
+            let start = if to < range.start { range.start.min(to) - margin } else { range.start };
+            let end = if to >= range.end { range.end.max(to + 1) + margin } else { range.end };
+            start..end
+        };
+        let (new_xs, new_ys) = if self.grid.cells.is_empty() { (x..x + 1, y..y + 1) } else { (grow(xs.clone(), x), grow(ys.clone(), y)) };
+        let mut grid = Grid::new((new_xs.end - new_xs.start) as usize, (new_ys.end - new_ys.start) as usize, self.fill.clone());
+        for ((old_x, old_y), cell) in self.grid.iter() {
+            grid[((xs.start + old_x as i64 - new_xs.start) as usize, (ys.start + old_y as i64 - new_ys.start) as usize)] = cell.clone();
+        }
+        self.grid = grid;
+        self.origin = (new_xs.start, new_ys.start);
+    }
+}
+
+impl<T: Display> Display for GrowingGrid<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.grid.fmt(f)
+    }
+}
@@ -0,0 +1,246 @@
+use std::num::ParseIntError;
+use std::str::FromStr;
+use crate::utils::{parse_num, Error};
+
+/// What a placeholder in a [Pattern] matches.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Kind {
+    /// `{u32}` and friends: digits.
+    Unsigned,
+    /// `{i32}` and friends: digits with an optional minus.
+    Signed,
+    /// `{id}`: letters, digits and underscores.
+    Id,
+    /// `{char}`: exactly one character.
+    Char,
+    /// `{}`: anything up to the text following it.
+    Text,
+    /// `{_}`: like `{}`, but not part of the result.
+    Skip
+}
+
+#[derive(Clone, Debug)]
+enum Piece {
+    Literal(String),
+    Field(Kind)
+}
+
+/// A line format like `Valve {id} has flow rate={u32}; {}`. Text outside of braces has to match exactly.
+/// Placeholders are `{id}`, `{char}`, integer types like `{u32}` or `{i64}`, `{}` for any text and `{_}` for
+/// text that is ignored. `{}` and `{_}` must be followed by literal text or end the pattern.
+#[derive(Clone, Debug)]
+pub struct Pattern {
+    pattern: String,
+    pieces: Vec<Piece>
+}
+
+/// A part of a line matched by a placeholder, and where it starts in that line.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Field<'a> {
+    pub text: &'a str,
+    pub offset: usize
+}
+
+impl<'a> Field<'a> {
+    /// Converts the field, moving errors to where the field is in the line.
+    pub fn parse<T: FromField<'a>>(self) -> Result<T, Error> {
+        T::from_field(self)
+    }
+    /// Parses a list like `1, 2, 3` with `parse` called for every element.
+    pub fn list<T>(self, separator: &str, parse: impl Fn(&str) -> Result<T, Error>) -> Result<Vec<T>, Error> {
+        parse_list(self.text, separator, parse).map_err(|err| err.shifted(self.offset))
+    }
+}
+
+/// Types a [Field] can be converted into.
+pub trait FromField<'a>: Sized {
+    fn from_field(field: Field<'a>) -> Result<Self, Error>;
+}
+macro_rules! from_field_num {
+    ($($t: ty),*) => {$(
+        impl<'a> FromField<'a> for $t {
+            fn from_field(field: Field<'a>) -> Result<Self, Error> {
+                parse_num(field.text).map_err(|err| err.shifted(field.offset))
+            }
+        }
+    )*}
+}
+from_field_num!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+impl<'a> FromField<'a> for &'a str {
+    fn from_field(field: Field<'a>) -> Result<Self, Error> { Ok(field.text) }
+}
+impl<'a> FromField<'a> for String {
+    fn from_field(field: Field<'a>) -> Result<Self, Error> { Ok(field.text.to_string()) }
+}
+impl<'a> FromField<'a> for char {
+    fn from_field(field: Field<'a>) -> Result<Self, Error> {
+        let mut chars = field.text.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c),
+            _ => Err(Error::parse_at("Expected a single character", field.offset, field.text.len()))
+        }
+    }
+}
+impl<'a> FromField<'a> for Field<'a> {
+    fn from_field(field: Field<'a>) -> Result<Self, Error> { Ok(field) }
+}
+
+/// Tuples of [FromField] types, one for every field of a [Pattern].
+pub trait FromFields<'a>: Sized {
+    const LEN: usize;
+    fn from_fields(fields: &[Field<'a>]) -> Result<Self, Error>;
+}
+macro_rules! from_fields_tuple {
+    ($len: expr; $($t: ident $i: tt),*) => {
+        impl<'a, $($t: FromField<'a>),*> FromFields<'a> for ($($t,)*) {
+            const LEN: usize = $len;
+            fn from_fields(fields: &[Field<'a>]) -> Result<Self, Error> {
+                Ok(($($t::from_field(fields[$i])?,)*))
+            }
+        }
+    }
+}
+from_fields_tuple!(1; A 0);
+from_fields_tuple!(2; A 0, B 1);
+from_fields_tuple!(3; A 0, B 1, C 2);
+from_fields_tuple!(4; A 0, B 1, C 2, D 3);
+from_fields_tuple!(5; A 0, B 1, C 2, D 3, E 4);
+from_fields_tuple!(6; A 0, B 1, C 2, D 3, E 4, F 5);
+from_fields_tuple!(7; A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+from_fields_tuple!(8; A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+
+impl Pattern {
+    /// Panics if the pattern itself is malformed, since patterns are written into the code.
+    pub fn new(pattern: &str) -> Pattern {
+        let mut pieces = Vec::new();
+        let mut rest = pattern;
+        while !rest.is_empty() {
+            if let Some(after_brace) = rest.strip_prefix('{') {
+                let (name, after) = after_brace.split_once('}').unwrap_or_else(|| panic!("Unclosed placeholder in '{pattern}'"));
+                pieces.push(Piece::Field(match name {
+                    "u8" | "u16" | "u32" | "u64" | "usize" => Kind::Unsigned,
+                    "i8" | "i16" | "i32" | "i64" | "isize" => Kind::Signed,
+                    "id" => Kind::Id,
+                    "char" => Kind::Char,
+                    "" => Kind::Text,
+                    "_" => Kind::Skip,
+                    _ => panic!("Unknown placeholder {{{name}}} in '{pattern}'")
+                }));
+                rest = after;
+            } else {
+                let end = rest.find('{').unwrap_or(rest.len());
+                pieces.push(Piece::Literal(rest[..end].to_string()));
+                rest = &rest[end..];
+            }
+        }
+        for pair in pieces.windows(2) {
+            if let [Piece::Field(Kind::Text | Kind::Skip), Piece::Field(_)] = pair {
+                panic!("{{}} has to be followed by text in '{pattern}'")
+            }
+        }
+        Pattern { pattern: pattern.to_string(), pieces }
+    }
+
+    /// Matches the whole line and returns the text of every placeholder except `{_}`.
+    pub fn fields<'a>(&self, line: &'a str) -> Result<Vec<Field<'a>>, Error> {
+        let mut fields = Vec::new();
+        let mut pos = 0;
+        for (i, piece) in self.pieces.iter().enumerate() {
+            let rest = &line[pos..];
+            let len = match piece {
+                Piece::Literal(literal) => {
+                    if !rest.starts_with(literal.as_str()) {
+                        let matching = rest.bytes().zip(literal.bytes()).take_while(|(l, r)| l == r).count();
+                        let blamed = rest[matching..].chars().next().map_or(1, |c| c.len_utf8());
+                        return Err(Error::parse_at(format!("Expected '{}'", &literal[matching..]), pos + matching, blamed)
+                            .with_expectation(&self.pattern))
+                    }
+                    pos += literal.len();
+                    continue
+                },
+                Piece::Field(kind @ (Kind::Unsigned | Kind::Signed)) => {
+                    let sign = if *kind == Kind::Signed && rest.starts_with('-') { 1 } else { 0 };
+                    let digits = rest[sign..].bytes().take_while(u8::is_ascii_digit).count();
+                    if digits == 0 {
+                        return Err(Error::parse_at("Expected a number", pos, 1).with_expectation(&self.pattern))
+                    }
+                    sign + digits
+                },
+                Piece::Field(Kind::Id) => {
+                    let len = rest.bytes().take_while(|b| b.is_ascii_alphanumeric() || *b == b'_').count();
+                    if len == 0 {
+                        return Err(Error::parse_at("Expected a name", pos, 1).with_expectation(&self.pattern))
+                    }
+                    len
+                },
+                Piece::Field(Kind::Char) => rest.chars().next()
+                    .ok_or(Error::parse_at("Expected a character", pos, 1).with_expectation(&self.pattern))?
+                    .len_utf8(),
+                Piece::Field(Kind::Text | Kind::Skip) => match self.pieces.get(i + 1) {
+                    Some(Piece::Literal(next)) => rest.find(next.as_str())
+                        .ok_or(Error::parse_at(format!("Expected '{next}'"), line.len(), 1).with_expectation(&self.pattern))?,
+                    _ => rest.len()
+                }
+            };
+            if !matches!(piece, Piece::Field(Kind::Skip)) {
+                fields.push(Field { text: &rest[..len], offset: pos });
+            }
+            pos += len;
+        }
+        if pos < line.len() {
+            return Err(Error::parse_at("Unexpected text at the end", pos, line.len() - pos).with_expectation(&self.pattern))
+        }
+        Ok(fields)
+    }
+
+    /// Matches the whole line and converts the fields into a tuple, like `let (id, rate): (&str, u32) = ...`.
+    pub fn parse<'a, T: FromFields<'a>>(&self, line: &'a str) -> Result<T, Error> {
+        let fields = self.fields(line)?;
+        assert_eq!(fields.len(), T::LEN, "'{}' has {} fields, but {} were requested", self.pattern, fields.len(), T::LEN);
+        T::from_fields(&fields)
+    }
+
+    pub fn as_str(&self) -> &str { &self.pattern }
+
+    pub fn matches(&self, line: &str) -> bool {
+        self.fields(line).is_ok()
+    }
+}
+
+impl Error {
+    /// Mentions the pattern a line did not match, so that the message says what the line should look like.
+    fn with_expectation(mut self, pattern: &str) -> Error {
+        if let Error::Parse { message, .. } = &mut self {
+            *message += &format!(" (in '{pattern}')");
+        }
+        self
+    }
+}
+
+/// Parses a list like `79, 98` by calling `parse` for every element. Errors point at the element they occurred in.
+pub fn parse_list<T>(s: &str, separator: &str, parse: impl Fn(&str) -> Result<T, Error>) -> Result<Vec<T>, Error> {
+    let mut offset = 0;
+    s.split(separator).map(|element| {
+        let result = parse(element).map_err(|err| err.shifted(offset));
+        offset += element.len() + separator.len();
+        result
+    }).collect()
+}
+
+/// Parses a list of numbers like `79, 98`.
+pub fn parse_nums<T: FromStr<Err=ParseIntError>>(s: &str, separator: &str) -> Result<Vec<T>, Error> {
+    parse_list(s, separator, parse_num)
+}
+
+/// Turns a table of characters into a parser for [super::Grid::parse] and friends, so that errors list what was expected.
+pub fn char_map<T: Copy>(table: &'static [(char, T)]) -> impl Fn(char) -> Result<T, Error> {
+    move |c| table.iter().find(|(key, _)| *key == c).map(|(_, value)| *value).ok_or_else(|| {
+        let keys: Vec<String> = table.iter().map(|(key, _)| key.to_string()).collect();
+        let expected = match keys.split_last() {
+            Some((last, [])) => last.clone(),
+            Some((last, rest)) => format!("{} or {last}", rest.join(", ")),
+            None => "nothing".to_string()
+        };
+        Error::parse(format!("Expected {expected}, found {c}"))
+    })
+}
@@ -0,0 +1,143 @@
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
+use crate::utils::{Error, Grid};
+
+/// How the frames of a simulation are shown.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Output {
+    /// One `frame_00000.ppm` image per frame in the directory, which e.g. ffmpeg can turn into a video.
+    Ppm(String),
+    /// One `frame_00000.txt` per frame in the directory.
+    Text(String),
+    /// Redraws the frames on stderr, waiting `delay` after each.
+    Terminal { delay: Duration }
+}
+
+/// Which simulations to record, and how.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Visualize {
+    pub output: Output,
+    /// Only every that many ticks are drawn, for simulations with a lot of them.
+    pub every: usize,
+    /// The size of a cell in pixels, for images.
+    pub scale: usize
+}
+
+impl FromStr for Output {
+    type Err = Error;
+    /// `ppm:<dir>`, `text:<dir>` or `terminal`, optionally followed by the delay in milliseconds like `terminal:50`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, arg) = s.split_once(':').map_or((s, None), |(kind, arg)| (kind, Some(arg)));
+        match (kind, arg) {
+            ("ppm", Some(dir)) => Ok(Output::Ppm(dir.to_string())),
+            ("text", Some(dir)) => Ok(Output::Text(dir.to_string())),
+            ("terminal", None) => Ok(Output::Terminal { delay: Duration::from_millis(50) }),
+            ("terminal", Some(millis)) => Ok(Output::Terminal {
+                delay: Duration::from_millis(millis.parse().map_err(|_| Error::invalid(format!("Invalid delay {millis}")))?)
+            }),
+            _ => Err(Error::invalid(format!("Unknown output {s}, expected ppm:<dir>, text:<dir> or terminal[:<millis>]")))
+        }
+    }
+}
+
+/// Collects the frames of one simulation. Simulations call [Recorder::tick] after every step, and only pay for
+/// drawing the frame if it is actually recorded.
+pub struct Recorder {
+    visualize: Option<Visualize>,
+    /// Where the frames of this simulation go, for outputs writing files.
+    dir: Option<PathBuf>,
+    ticks: usize,
+    frames: usize
+}
+
+impl Recorder {
+    /// A recorder that drops every frame.
+    pub fn disabled() -> Recorder {
+        Recorder { visualize: None, dir: None, ticks: 0, frames: 0 }
+    }
+
+    /// Starts recording a simulation called `name`, whose frames go to a subdirectory of that name.
+    /// Without `visualize`, nothing is recorded.
+    pub fn start(visualize: Option<&Visualize>, name: &str) -> Result<Recorder, Error> {
+        let Some(visualize) = visualize else { return Ok(Recorder::disabled()) };
+        let dir = match &visualize.output {
+            Output::Ppm(dir) | Output::Text(dir) => {
+                let dir = PathBuf::from(dir).join(name);
+                fs::create_dir_all(&dir).map_err(|err| Error::from(err).in_file(&dir.to_string_lossy()))?;
+                Some(dir)
+            },
+            Output::Terminal { .. } => None
+        };
+        Ok(Recorder { visualize: Some(visualize.clone()), dir, ticks: 0, frames: 0 })
+    }
+
+    /// Records the frame drawn by `draw`, unless this tick is skipped. Cells are drawn with the characters of the
+    /// puzzle, which [color_of] turns into colors for images.
+    pub fn tick(&mut self, draw: impl FnOnce() -> Grid<char>) -> Result<(), Error> {
+        let Some(visualize) = &self.visualize else { return Ok(()) };
+        self.ticks += 1;
+        if !(self.ticks - 1).is_multiple_of(visualize.every.max(1)) { return Ok(()) }
+        let frame = draw();
+        let file = |extension: &str| self.dir.as_ref().expect("Outputs writing files have a directory")
+            .join(format!("frame_{:05}.{extension}", self.frames));
+        match &visualize.output {
+            Output::Ppm(_) => {
+                let path = file("ppm");
+                fs::write(&path, to_ppm(&frame, visualize.scale)).map_err(|err| Error::from(err).in_file(&path.to_string_lossy()))?
+            },
+            Output::Text(_) => {
+                let path = file("txt");
+                fs::write(&path, frame.to_string() + "\n").map_err(|err| Error::from(err).in_file(&path.to_string_lossy()))?
+            },
+            Output::Terminal { delay } => {
+                let mut stderr = std::io::stderr().lock();
+                writeln!(stderr, "\x1b[H\x1b[2J{frame}")?;
+                stderr.flush()?;
+                thread::sleep(*delay);
+            }
+        }
+        self.frames += 1;
+        Ok(())
+    }
+
+    pub fn frames(&self) -> usize { self.frames }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        if let Some(dir) = &self.dir {
+            info!("Wrote {} frames to {}", self.frames, dir.to_string_lossy());
+        }
+    }
+}
+
+/// The colors of the characters the simulations draw with. Other characters get a gray.
+pub fn color_of(c: char) -> [u8; 3] {
+    match c {
+        '.' | ' ' => [16, 16, 24],
+        '#' => [120, 120, 130],
+        'o' => [220, 190, 110],
+        '+' => [255, 230, 0],
+        '@' => [240, 120, 30],
+        'E' => [230, 40, 40],
+        '>' | '<' | '^' | 'v' | '*' => [150, 210, 255],
+        _ => [200, 200, 200]
+    }
+}
+
+/// A binary PPM image with every cell drawn as a `scale` by `scale` square.
+pub fn to_ppm(frame: &Grid<char>, scale: usize) -> Vec<u8> {
+    let scale = scale.max(1);
+    let mut image = format!("P6\n{} {}\n255\n", frame.width() * scale, frame.height() * scale).into_bytes();
+    for row in frame.rows() {
+        let line: Vec<u8> = row.iter().flat_map(|&c| color_of(c).repeat(scale)).collect();
+        for _ in 0..scale {
+            image.extend_from_slice(&line);
+        }
+    }
+    image
+}
@@ -0,0 +1,115 @@
+//! Checks the ranking of the elves of day 1.
+
+use advent_of_code_2022::day01::{describe_elves, Inventory, Stats, Tally};
+use advent_of_code_2022::generate::{generate, Rng};
+use advent_of_code_2022::solution::{solution_for, Answer, Params};
+use advent_of_code_2022::utils::Part;
+
+const INPUT: &str = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000\n";
+
+fn top_indices(inventory: &Inventory, n: usize) -> Vec<usize> {
+    inventory.top(n).iter().map(|elf| elf.index).collect()
+}
+
+#[test]
+fn parses_every_elf() {
+    let inventory = Inventory::parse(INPUT).unwrap();
+    let elves = inventory.elves();
+    assert_eq!(elves.len(), 5);
+    assert_eq!((elves[2].index, elves[2].items.clone(), elves[2].total), (2, vec![5000, 6000], 11000));
+}
+
+#[test]
+fn ranks_the_top_elves() {
+    let inventory = Inventory::parse(INPUT).unwrap();
+    assert_eq!(top_indices(&inventory, 1), vec![3]);
+    assert_eq!(top_indices(&inventory, 3), vec![3, 2, 4]);
+    assert_eq!(top_indices(&inventory, 10), vec![3, 2, 4, 0, 1]);
+    assert!(inventory.top(0).is_empty());
+    assert_eq!(inventory.top_total(3).unwrap(), 45000);
+    let top: Vec<(usize, u64)> = inventory.top(3).iter().map(|elf| (elf.index, elf.total)).collect();
+    assert_eq!(describe_elves(&top), "elf 4 (24000), elf 3 (11000), elf 5 (10000)");
+}
+
+#[test]
+fn earlier_elves_win_ties() {
+    let inventory = Inventory::parse("5\n\n3\n2\n\n1\n\n5\n").unwrap();
+    assert_eq!(top_indices(&inventory, 2), vec![0, 1]);
+    assert_eq!(top_indices(&inventory, 3), vec![0, 1, 3]);
+}
+
+#[test]
+fn needs_enough_elves() {
+    let inventory = Inventory::parse("1\n\n2\n").unwrap();
+    assert!(inventory.top_total(3).unwrap_err().to_string().contains("only 2 elves"));
+    assert!(Inventory::parse("").unwrap().top_total(1).is_err());
+}
+
+#[test]
+fn rejects_what_is_not_a_number() {
+    let err = Inventory::parse("1\n\n2\nabc\n").unwrap_err();
+    assert!(err.to_string().starts_with("4:1: 'abc' is not a valid number"), "{err}");
+    assert!(Inventory::parse("-5\n").is_err());
+}
+
+#[test]
+fn streaming_agrees_with_the_inventory() {
+    for seed in 0..20 {
+        let input = generate(1, seed, Some(200)).unwrap();
+        let inventory = Inventory::parse(&input).unwrap();
+        let tally = Tally::read(input.as_bytes(), 5).unwrap();
+        let expected: Vec<(usize, u64)> = inventory.top(5).iter().map(|elf| (elf.index, elf.total)).collect();
+        assert_eq!(tally.top(), expected, "seed {seed}");
+        assert_eq!(tally.top_total(3).unwrap(), inventory.top_total(3).unwrap());
+        assert_eq!(tally.stats().unwrap().count, inventory.elves().len());
+    }
+}
+
+#[test]
+fn reports_the_top_elves_whether_parsed_or_streamed() {
+    let input = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000\n";
+    let solution = solution_for(1, &Params::default()).unwrap();
+    let parsed = solution.parse_any(input).unwrap();
+    let top = "top 3: elf 4 (24000), elf 3 (11000), elf 5 (10000)";
+    assert_eq!(solution.report_any(parsed.as_ref()).unwrap(), vec![top]);
+    let streamed = solution.stream_any(&mut input.as_bytes()).unwrap().unwrap();
+    assert_eq!((streamed.solve(Part::One).unwrap(), streamed.solve(Part::Two).unwrap()), (Answer::Int(24000), Answer::Int(45000)));
+    assert_eq!(streamed.report(), vec!["stats: 5 elves carrying 11000.0 calories on average, about 10000 in the middle", top]);
+    // Other days have nothing to report and need their whole input
+    let solution = solution_for(6, &Params::default()).unwrap();
+    assert!(solution.report_any(solution.parse_any("abcd").unwrap().as_ref()).unwrap().is_empty());
+    assert!(solution.stream_any(&mut input.as_bytes()).is_none());
+}
+
+#[test]
+fn streams_running_stats() {
+    let mut tally = Tally::new(2);
+    assert_eq!(tally.stats(), None);
+    for line in ["1", "2", "", "  ", "6", "\r"] {
+        tally.push_line(line).unwrap();
+    }
+    assert_eq!(tally.stats(), Some(Stats { count: 2, mean: 4.5, median: 4.5 }));
+    tally.push_line("10").unwrap();
+    // The elf being read is not finished yet
+    assert_eq!(tally.top(), vec![(1, 6), (0, 3)]);
+    tally.finish_elf();
+    assert_eq!(tally.top(), vec![(2, 10), (1, 6)]);
+    assert_eq!(tally.stats().unwrap().median, 6.0);
+    assert_eq!(tally.top_total(2).unwrap(), 16);
+    assert!(tally.top_total(3).unwrap_err().to_string().contains("Only the top 2 elves were kept"));
+    assert!(Tally::new(2).top_total(1).unwrap_err().to_string().contains("only 0 elves"));
+    let err = tally.push_line("1e3").unwrap_err();
+    assert!(err.to_string().starts_with("8:1: '1e3' is not a valid number"), "{err}");
+}
+
+#[test]
+fn estimates_the_median_of_many_elves() {
+    // Elves carrying 1 to 100001 calories in a shuffled order, so that the median is 50001
+    let mut rng = Rng::new(7);
+    let mut totals: Vec<u32> = (1..=100001).collect();
+    rng.shuffle(&mut totals);
+    let input = totals.iter().map(|total| total.to_string()).collect::<Vec<_>>().join("\n\n");
+    let stats = Tally::read(input.as_bytes(), 3).unwrap().stats().unwrap();
+    assert_eq!((stats.count, stats.mean), (100001, 50001.0));
+    assert!((stats.median - 50001.0).abs() < 1000.0, "{stats}");
+}
@@ -0,0 +1,78 @@
+//! Checks reading day settings from config files and how they are combined with the command line.
+
+use advent_of_code_2022::config::Config;
+use advent_of_code_2022::day22::CubeLayout;
+use advent_of_code_2022::input::InputSource;
+use advent_of_code_2022::solution::Params;
+
+const CONFIG: &str = "\
+# Defaults for everybody
+[day11]
+rounds_part1 = 30
+relief = 2
+
+; Alice's input needs fewer rounds
+[day11 alice.txt]
+  rounds_part1=5
+";
+
+fn params(input: &str, config: &str) -> Params {
+    Params::new(11, &InputSource::Path(format!("inputs/{input}")), &Config::parse(config).unwrap())
+}
+
+#[test]
+fn input_sections_win_over_day_sections() {
+    let alice = params("alice.txt", CONFIG);
+    assert_eq!(alice.get::<usize>(11, "rounds_part1").unwrap(), Some(5));
+    assert_eq!(alice.get::<u64>(11, "relief").unwrap(), Some(2));
+    assert_eq!(alice.get::<usize>(11, "rounds_part2").unwrap(), None);
+    assert_eq!(params("bob.txt", CONFIG).get::<usize>(11, "rounds_part1").unwrap(), Some(30));
+}
+
+#[test]
+fn command_line_wins_over_config() {
+    let mut alice = params("alice.txt", CONFIG);
+    alice.overrides.push(("rounds_part1".to_string(), "7".to_string()));
+    assert_eq!(alice.get::<usize>(11, "rounds_part1").unwrap(), Some(7));
+    alice.overrides.push(("relief".to_string(), "x".to_string()));
+    assert!(alice.get::<u64>(11, "relief").unwrap_err().to_string().contains("Invalid value x for relief"));
+}
+
+#[test]
+fn errors_point_at_the_value() {
+    let err = params("bob.txt", "[day11]\nrelief = many\n").get::<u64>(11, "relief").unwrap_err().to_string();
+    assert!(err.starts_with("2:10: Not a valid number"), "{err}");
+    let err = Config::parse("[day11]\nrelief = 1\nrelief = 2").unwrap_err().to_string();
+    assert!(err.starts_with("3:1: relief is set twice"), "{err}");
+    let err = Config::parse("[dayXI]").unwrap_err().to_string();
+    assert!(err.starts_with("1:5: Expected a number"), "{err}");
+}
+
+#[test]
+fn unknown_settings_are_rejected() {
+    let params = params("bob.txt", "[day11]\nrounds = 20\n");
+    let err = advent_of_code_2022::solution::solution_for(11, &params).err().unwrap().to_string();
+    assert!(err.contains("Day 11 has no setting rounds"), "{err}");
+}
+
+#[test]
+fn counts_have_to_be_positive() {
+    let err = params("bob.txt", "[day11]\nrelief = 0\n").get_positive::<u64>(11, "relief").unwrap_err().to_string();
+    assert!(err.starts_with("2:10: relief in [day11] has to be at least 1, found 0"), "{err}");
+    for (day, key) in [(1, "top"), (3, "group_size"), (3, "compartments"), (6, "marker_len"), (11, "rounds_part2"), (22, "face_len")] {
+        let params = Params { overrides: vec![(key.to_string(), "0".to_string())], ..Params::default() };
+        let err = advent_of_code_2022::solution::solution_for(day, &params).err().unwrap().to_string();
+        assert!(err.contains(&format!("{key} in [day{day:02}] has to be at least 1")), "{err}");
+    }
+    assert_eq!(params("bob.txt", "[day11]\nrelief = 1\n").get_positive::<u64>(11, "relief").unwrap(), Some(1));
+}
+
+#[test]
+fn cube_layouts_can_be_spelled_out() {
+    let actual = CubeLayout::actual();
+    assert_eq!(CubeLayout::parse_face_locations("1,0 2,0 1,1 0,2 1,2 0,3").unwrap(), actual.face_locations);
+    let edges = CubeLayout::parse_edges("1/0 2/0 3/2 5/1; 4/2 2/1 0/0 5/0; 1/3 4/0 3/3 0/0; 4/0 5/0 0/2 2/1; 1/2 5/1 3/0 2/0; 4/3 1/0 0/3 3/0").unwrap();
+    assert_eq!(edges, actual.edges[1]);
+    let err = CubeLayout::parse_edges("1/0 2/0 3/2").unwrap_err().to_string();
+    assert!(err.contains("Expected 4 edges, found 3"), "{err}");
+}
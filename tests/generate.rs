@@ -0,0 +1,51 @@
+//! Checks that generated inputs are deterministic and can be solved.
+
+use advent_of_code_2022::generate::{generate, Rng};
+use advent_of_code_2022::solution::{solution_for, Params};
+use advent_of_code_2022::utils::Part;
+
+/// Sizes that keep solving quick. Day 17 needs at least 50 jets for its tower to repeat.
+const SMALL_SIZES: [usize; 25] = [20, 50, 30, 50, 30, 100, 40, 10, 100, 10, 4, 8, 20, 10, 5, 12, 1000, 100, 2, 100, 100, 50, 10, 5, 20];
+
+#[test]
+fn seeds_decide_the_input() {
+    for day in 1..=25 {
+        let size = Some(SMALL_SIZES[day as usize - 1]);
+        assert_eq!(generate(day, 7, size).unwrap(), generate(day, 7, size).unwrap(), "Day {day}");
+        assert_ne!(generate(day, 7, size).unwrap(), generate(day, 8, size).unwrap(), "Day {day}");
+    }
+    assert!(generate(26, 0, None).is_err());
+}
+
+#[test]
+fn generated_inputs_are_solved() {
+    for day in 1..=25 {
+        let solution = solution_for(day, &Params::default()).unwrap();
+        for seed in 0..3 {
+            let input = generate(day, seed, Some(SMALL_SIZES[day as usize - 1])).unwrap();
+            let parsed = solution.parse_any(&input).unwrap_or_else(|err| panic!("Day {day} seed {seed}: {err}\n{input}"));
+            // Part 2 of day 19 takes too long even for small inputs
+            let parts: &[Part] = if day == 19 { &[Part::One] } else { &[Part::One, Part::Two] };
+            for &part in parts {
+                solution.solve_any(parsed.as_ref(), part).unwrap_or_else(|err| panic!("Day {day} part {part} seed {seed}: {err}"));
+            }
+        }
+    }
+}
+
+#[test]
+fn random_numbers_stay_in_range() {
+    let mut rng = Rng::new(1);
+    for _ in 0..1000 {
+        assert!((-3..=5).contains(&rng.range(-3..=5)));
+        assert!(rng.below(7) < 7);
+    }
+    assert_eq!(rng.range(4..=4), 4);
+    assert!((i64::MIN + 1..=i64::MAX).contains(&rng.range(i64::MIN + 1..=i64::MAX)));
+    rng.range(i64::MIN..=i64::MAX);
+    let mut items: Vec<usize> = (0..20).collect();
+    rng.shuffle(&mut items);
+    assert_ne!(items, (0..20).collect::<Vec<_>>());
+    items.sort();
+    assert_eq!(items, (0..20).collect::<Vec<_>>());
+}
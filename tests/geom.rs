@@ -0,0 +1,47 @@
+//! Checks the shared geometry types.
+
+use advent_of_code_2022::geom::{Dir, Vec2, Vec3};
+
+#[test]
+fn distances() {
+    let (a, b) = (Vec2::new(1, -2), Vec2::new(-3, 5));
+    assert_eq!(a.manhattan(b), 11);
+    assert_eq!(a.chebyshev(b), 7);
+    assert_eq!(Vec2::<usize>::new(1, 8).manhattan(Vec2::new(4, 2)), 9);
+    assert_eq!(Vec3::new(0, 0, 0).manhattan(Vec3::new(1, -2, 3)), 6);
+}
+
+#[test]
+fn arithmetic() {
+    let mut p = Vec2::new(2, 3);
+    p += Vec2::new(1, 1);
+    assert_eq!(p - Vec2::new(3, 4), Vec2::default());
+    assert_eq!(-p * 2, Vec2::new(-6, -8));
+    assert_eq!(Vec3::new(1, 1, 1).neighbors().len(), 6);
+}
+
+#[test]
+fn wrapping() {
+    let (min, max) = (Vec2::new(1, 1), Vec2::new(5, 4));
+    assert_eq!(Vec2::new(4, 2).wrapping_add(Dir::Right.offset(), min, max), Vec2::new(1, 2));
+    assert_eq!(Vec2::new(4, 1).wrapping_add(Dir::Up.offset(), min, max), Vec2::new(4, 3));
+    assert_eq!(Vec2::new(2, 2).wrapping_add(Vec2::new(-9, 0), min, max), Vec2::new(1, 2));
+}
+
+#[test]
+fn rotation() {
+    assert_eq!(Dir::Up.turn_right(), Dir::Right);
+    assert_eq!(Dir::Up.turn_left(), Dir::Left);
+    assert_eq!(Dir::Left.opposite(), Dir::Right);
+    assert_eq!(Dir::Down.rotated(-6), Dir::Up);
+    assert_eq!(Dir::Left.offset::<i32>(), Vec2::new(-1, 0));
+}
+
+#[test]
+fn parsing() {
+    assert_eq!("498,4".parse::<Vec2<i32>>().unwrap(), Vec2::new(498, 4));
+    assert_eq!("1,-2,3".parse::<Vec3<i32>>().unwrap(), Vec3::new(1, -2, 3));
+    assert_eq!(Vec2::new(7, 8).to_string().parse::<Vec2<i32>>().unwrap(), Vec2::new(7, 8));
+    assert!("1,2,3".parse::<Vec2<i32>>().unwrap_err().to_string().contains("Expected 2"));
+    assert!("1,x".parse::<Vec2<i32>>().is_err());
+}
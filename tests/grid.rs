@@ -0,0 +1,64 @@
+//! Checks the shared grid types on small hand-drawn maps.
+
+use advent_of_code_2022::utils::{Error, Grid, GrowingGrid};
+
+fn digits(map: &str) -> Grid<u32> {
+    Grid::parse(map, |c| c.to_digit(10).ok_or(Error::parse(format!("Expected a digit, found {c}")))).unwrap()
+}
+
+#[test]
+fn indexing() {
+    let grid = digits("123\n456");
+    assert_eq!((grid.width(), grid.height()), (3, 2));
+    assert_eq!(grid[(2, 1)], 6);
+    assert_eq!(grid.get(3, 0), None);
+    assert_eq!(grid.get_signed(-1, 0), None);
+    assert_eq!(grid.get_signed(0, 1), Some(&4));
+    assert_eq!(grid.row(1), &[4, 5, 6]);
+    assert_eq!(grid.column(1).rev().copied().collect::<Vec<_>>(), vec![5, 2]);
+    assert_eq!(grid.position(|&d| d == 5), Some((1, 1)));
+}
+
+#[test]
+fn neighbors() {
+    let grid = digits("123\n456\n789");
+    let mut corner: Vec<_> = grid.neighbors4(0, 0).collect();
+    corner.sort();
+    assert_eq!(corner, vec![(0, 1), (1, 0)]);
+    assert_eq!(grid.neighbors8(1, 1).count(), 8);
+    assert_eq!(grid.neighbors8(2, 2).count(), 3);
+}
+
+#[test]
+fn parse_errors_point_at_the_cell() {
+    let err = digits_err("12\n1x");
+    assert!(err.starts_with("2:2: Expected a digit"), "{err}");
+    let err = digits_err("12\n123");
+    assert!(err.contains("Expected 2 columns"), "{err}");
+}
+
+fn digits_err(map: &str) -> String {
+    Grid::parse(map, |c| c.to_digit(10).ok_or(Error::parse("Expected a digit"))).unwrap_err().to_string()
+}
+
+#[test]
+fn display() {
+    assert_eq!(digits("12\n34").to_string(), "12\n34");
+    assert_eq!(digits("12\n34").map(|d| d * 5).to_string(), " 5 10\n15 20");
+    assert_eq!(digits("10\n01").render(|&d| if d == 1 {'#'} else {'.'}), "#.\n.#");
+}
+
+#[test]
+fn growing() {
+    let mut grid = GrowingGrid::new('.');
+    grid.set(0, 0, '#');
+    grid.set(-3, 2, '#');
+    grid.set(10, -5, '#');
+    assert_eq!(*grid.get(0, 0), '#');
+    assert_eq!(*grid.get(-3, 2), '#');
+    assert_eq!(*grid.get(10, -5), '#');
+    assert_eq!(*grid.get(1, 1), '.');
+    assert_eq!(*grid.get(100, 100), '.');
+    assert!(grid.x_range().contains(&-3) && grid.x_range().contains(&10));
+    assert_eq!(grid.grid().iter().filter(|(_, &c)| c == '#').count(), 3);
+}
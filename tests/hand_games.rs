@@ -0,0 +1,90 @@
+//! Checks the cyclic hand games of day 2 beyond rock-paper-scissors.
+
+use advent_of_code_2022::day02::{analyze, Encoding, Game, Interpretation, Outcome, Symbol};
+use advent_of_code_2022::solution::{solution_for, Answer, Params};
+use advent_of_code_2022::utils::Part;
+
+fn rpsls() -> Game {
+    "Rock Spock Paper Lizard Scissors".parse().unwrap()
+}
+
+fn symbol(game: &Game, name: &str) -> Symbol {
+    game.symbols().find(|&symbol| game.name(symbol) == name).unwrap()
+}
+
+fn solve(settings: &[(&str, &str)], input: &str) -> [Answer; 2] {
+    let params = Params { overrides: settings.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(), ..Params::default() };
+    let solution = solution_for(2, &params).unwrap();
+    let parsed = solution.parse_any(input).unwrap();
+    [Part::One, Part::Two].map(|part| solution.solve_any(parsed.as_ref(), part).unwrap())
+}
+
+#[test]
+fn every_symbol_beats_half_of_the_others() {
+    for game in [Game::rock_paper_scissors(), rpsls(), "a b c d e f g".parse().unwrap()] {
+        for ours in game.symbols() {
+            let wins = game.symbols().filter(|&theirs| game.play(theirs, ours) == Outcome::Win).count();
+            assert_eq!(wins, game.size() / 2);
+            for theirs in game.symbols() {
+                let outcome = game.play(theirs, ours);
+                assert_eq!(game.play(ours, theirs), match outcome { Outcome::Win => Outcome::Loss, Outcome::Loss => Outcome::Win, draw => draw });
+                assert_eq!(game.play(theirs, game.inverse_play(theirs, outcome)), outcome);
+            }
+        }
+    }
+    let game = rpsls();
+    assert_eq!(game.play(symbol(&game, "Lizard"), symbol(&game, "Spock")), Outcome::Loss);
+    assert_eq!(game.play(symbol(&game, "Scissors"), symbol(&game, "Spock")), Outcome::Win);
+    assert_eq!(game.play(symbol(&game, "Paper"), symbol(&game, "Lizard")), Outcome::Win);
+}
+
+#[test]
+fn games_need_an_odd_number_of_distinct_symbols() {
+    assert!("Rock Paper".parse::<Game>().is_err());
+    assert!("a b c d".parse::<Game>().is_err());
+    assert!("Rock Paper Rock".parse::<Game>().unwrap_err().to_string().contains("Rock is in the game twice"));
+}
+
+#[test]
+fn columns_default_to_the_letters_of_the_puzzle() {
+    let encoding = Encoding::letters(&rpsls()).unwrap();
+    assert_eq!(encoding.first, vec!['A', 'B', 'C', 'D', 'E']);
+    assert_eq!(encoding.second, vec!['V', 'W', 'X', 'Y', 'Z']);
+    assert_eq!(encoding.outcome('Z'), Some(Outcome::Win));
+    assert!(Encoding::new(&rpsls(), "ABC", "VWXYZ", "XYZ").is_err());
+    assert!(Encoding::new(&rpsls(), "ABCDE", "VWXYZ", "XYX").is_err());
+}
+
+#[test]
+fn scores_any_variant() {
+    assert_eq!(solve(&[], "A Y\nB X\nC Z\n"), [Answer::Int(15), Answer::Int(12)]);
+    let settings = [("symbols", "Rock Paper Scissors"), ("outcome_scores", "0 1 2"),
+                    ("first_column", "R P S"), ("second_column", "rps"), ("outcome_column", "rsp")];
+    // Paper beats rock for 2 + 2, rock loses to paper for 1 + 0 and scissors draw for 3 + 1, both ways of reading the guide
+    assert_eq!(solve(&settings, "R p\nP r\nS s\n"), [Answer::Int(4 + 1 + 4), Answer::Int(4 + 1 + 4)]);
+    // Lizard beats Spock, Paper and Scissors draw and Scissors lose to Rock, or a Spock draw, Spock losing to Paper,
+    // Rock beating Scissors and Spock beating Rock
+    assert_eq!(solve(&[("symbols", "Rock Spock Paper Lizard Scissors")], "B Y\nC X\nE Z\nA Z\n"),
+               [Answer::Int(10 + 6 + 8 + 5), Answer::Int(5 + 2 + 7 + 8)]);
+}
+
+#[test]
+fn ranks_every_reading_of_the_guide() {
+    let game = Game::rock_paper_scissors();
+    let guide = [(Symbol(0), 'Y'), (Symbol(1), 'X'), (Symbol(2), 'Z')];
+    let ranking = analyze(&game, &guide);
+    // Three characters can be three symbols in 6 ways and three outcomes in 6 ways
+    assert_eq!(ranking.len(), 12);
+    assert!(ranking.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+    assert_eq!(ranking[0].1, 24);
+    assert_eq!(ranking[0].0.describe(&game), "X=Scissors, Y=Paper, Z=Rock");
+    let total_of = |description: &str| ranking.iter().find(|(i, _)| i.describe(&game) == description).map(|&(_, total)| total);
+    assert_eq!(total_of("X=Rock, Y=Paper, Z=Scissors"), Some(15));
+    assert_eq!(total_of("X=Loss, Y=Draw, Z=Win"), Some(12));
+
+    let rounds = ranking[0].0.play(&game, &guide).unwrap();
+    assert_eq!(rounds[1].describe(&game), "Scissors against Paper: Win for 9");
+    assert_eq!(Interpretation::Symbols(vec![('X', Symbol(0))]).play(&game, &guide), None);
+    // Only characters that occur are interpreted
+    assert_eq!(analyze(&game, &[(Symbol(0), 'Q')]).len(), 3 + 3);
+}
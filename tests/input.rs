@@ -0,0 +1,28 @@
+//! Checks how inputs are located and recognized as samples.
+
+use advent_of_code_2022::input::{is_sample_path, InputSource};
+use advent_of_code_2022::utils::Part;
+
+#[test]
+fn samples_are_recognized_by_name() {
+    assert!(is_sample_path("input/day15_sample.txt"));
+    assert!(is_sample_path("/home/alice/aoc/day09_sample_2.txt"));
+    assert!(!is_sample_path("input/day15.txt"));
+    assert!(!is_sample_path("samples/day15.txt"));
+    assert!(InputSource::Path("day22_sample.txt".to_string()).is_sample());
+    assert!(!InputSource::Stdin.is_sample());
+}
+
+#[test]
+fn dash_means_stdin() {
+    assert_eq!(InputSource::from_arg("-".to_string()), InputSource::Stdin);
+    assert_eq!(InputSource::from_arg("in.txt".to_string()), InputSource::Path("in.txt".to_string()));
+    assert_eq!(InputSource::Path("in.txt".to_string()).path(3, Part::Two).unwrap(), "in.txt");
+}
+
+#[test]
+fn part_two_samples_are_preferred_if_present() {
+    assert!(InputSource::Sample.path(9, Part::Two).unwrap().ends_with("day09_sample_2.txt"));
+    assert!(InputSource::Sample.path(9, Part::One).unwrap().ends_with("day09_sample.txt"));
+    assert!(InputSource::Sample.path(8, Part::Two).unwrap().ends_with("day08_sample.txt"));
+}
@@ -0,0 +1,23 @@
+//! Checks where the input directory is looked for. This changes the working directory and the environment
+//! of the whole process, so it is the only test in here.
+
+use std::{env, fs};
+use advent_of_code_2022::input::{input_dir, InputSource, INPUT_DIR_VAR};
+use advent_of_code_2022::utils::Part;
+
+#[test]
+fn input_dir_is_the_variable_or_the_working_directory() {
+    let dir = env::temp_dir().join(format!("aoc_input_dir_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    env::set_current_dir(&dir).unwrap();
+    env::remove_var(INPUT_DIR_VAR);
+    let err = input_dir().unwrap_err().to_string();
+    assert!(err.contains(INPUT_DIR_VAR) && err.contains("./input"), "{err}");
+    assert!(InputSource::Actual.read(1, Part::One).is_err());
+
+    fs::create_dir(dir.join("input")).unwrap();
+    assert_eq!(input_dir().unwrap(), "input");
+    env::set_var(INPUT_DIR_VAR, "elsewhere");
+    assert_eq!(InputSource::Sample.path(3, Part::One).unwrap(), "elsewhere/day03_sample.txt");
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,21 @@
+//! Checks how verbosity options map to log levels.
+
+use advent_of_code_2022::log::{enabled, set_level, Level};
+
+#[test]
+fn verbosity_raises_the_level() {
+    assert_eq!(Level::from_verbosity(0), Level::Error);
+    assert_eq!(Level::from_verbosity(2), Level::Debug);
+    assert_eq!(Level::from_verbosity(7), Level::Trace);
+    assert_eq!("info".parse::<Level>().unwrap(), Level::Info);
+    assert!("loud".parse::<Level>().is_err());
+}
+
+#[test]
+fn levels_include_the_ones_before() {
+    set_level(Level::Debug);
+    assert!(enabled(Level::Error) && enabled(Level::Info) && enabled(Level::Debug));
+    assert!(!enabled(Level::Trace));
+    set_level(Level::Error);
+    assert!(!enabled(Level::Info));
+}
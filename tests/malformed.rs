@@ -0,0 +1,70 @@
+//! Checks that broken inputs are reported as errors pointing at the input, instead of panicking or
+//! failing without saying where.
+
+use advent_of_code_2022::solution::{solution_for, Params};
+use advent_of_code_2022::utils::{Error, Part};
+
+/// Parses and solves both parts, returning the first error.
+fn solve(day: u8, params: &Params, input: &str) -> Error {
+    let solution = solution_for(day, params).unwrap();
+    let parsed = match solution.parse_any(input) {
+        Ok(parsed) => parsed,
+        Err(err) => return err
+    };
+    [Part::One, Part::Two].into_iter().find_map(|part| solution.solve_any(parsed.as_ref(), part).err())
+        .unwrap_or_else(|| panic!("Day {day} solved {input:?}"))
+}
+
+fn assert_error(err: Error, expected: &str) {
+    let text = err.to_string();
+    assert!(text.starts_with(expected), "Expected {expected}, found:\n{text}");
+}
+
+#[test]
+fn day02_points_at_the_second_column() {
+    assert_error(solve(2, &Params::default(), "A Y\nB Q\n"), "2:3: Expected X, Y or Z, found Q");
+    // Characters only one part can read fail that part
+    let params = Params { overrides: vec![("second_column".to_string(), "VWZ".to_string())], ..Params::default() };
+    assert_error(solve(2, &params, "A Z\nC Y\n"), "2:3: Expected V, W or Z, found Y");
+    // The caret points into the actual line, whatever the first column is
+    let params = Params { overrides: vec![("first_column".to_string(), "äöü".to_string()), ("second_column".to_string(), "VWZ".to_string())], ..Params::default() };
+    let err = solve(2, &params, "ä Z\nü Y\n");
+    assert_error(err, "2:3: Expected V, W or Z, found Y\n  |\n2 | ü Y\n  |   ^");
+}
+
+#[test]
+fn day21_needs_every_monkey_and_no_division_by_zero() {
+    assert_error(solve(21, &Params::default(), "root: aaaa + bbbb\naaaa: 5\nbbbb: aaaa / cccc\n"), "3:14: There is no monkey cccc");
+    assert_error(solve(21, &Params::default(), "root: aaaa + bbbb\naaaa: 5\nbbbb: aaaa / humn\nhumn: 0\n"), "Invalid input: Cannot divide 5 by 0");
+}
+
+#[test]
+fn day24_needs_walls_around_the_valley() {
+    assert_error(solve(24, &Params::default(), "#.#\n#.#\n"), "1:1: The valley needs at least 3 by 3 tiles");
+    assert_error(solve(24, &Params::default(), "#.\n#.\n..\n"), "1:1: The valley needs at least 3 by 3 tiles");
+}
+
+#[test]
+fn day11_needs_two_monkeys_and_nonzero_tests() {
+    let monkey = |n: usize, test: u64| format!("Monkey {n}:\n  Starting items: 79, 98\n  Operation: new = old * 19\n  Test: divisible by {test}\n    If true: throw to monkey 1\n    If false: throw to monkey 0\n");
+    assert_error(solve(11, &Params::default(), ""), "Parse error: Monkey business needs at least 2 monkeys, found 0");
+    assert_error(solve(11, &Params::default(), &format!("\n{}", monkey(0, 3))), "2:1: Monkey business needs at least 2 monkeys, found 1");
+    assert_error(solve(11, &Params::default(), &format!("{}\n{}", monkey(0, 3), monkey(1, 0))), "11:22: Monkeys cannot test divisibility by 0");
+}
+
+#[test]
+fn day16_needs_every_valve() {
+    let input = "Valve AA has flow rate=0; tunnels lead to valves BB, CC\nValve BB has flow rate=13; tunnel leads to valve AA\n";
+    assert_error(solve(16, &Params::default(), input), "1:54: There is no valve CC");
+    assert_error(solve(16, &Params::default(), "Valve BB has flow rate=13; tunnel leads to valve BB\n"), "Invalid input: There is no valve AA");
+}
+
+#[test]
+fn day17_needs_jets() {
+    assert_error(solve(17, &Params::default(), "\n<<>>\n"), "1:1: The jet pattern is empty");
+}
+
+#[test]
+fn day20_needs_two_numbers_to_mix() {
+    assert_error(solve(20, &Params::default(), "0\n"), "Invalid input: Mixing needs at least 2 numbers, found 1");
+}
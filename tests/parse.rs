@@ -0,0 +1,60 @@
+//! Checks the line patterns and list parsers the days build their parsers from.
+
+use advent_of_code_2022::utils::{char_map, parse_list, parse_num, parse_nums, Error, Field, Pattern};
+
+#[test]
+fn extracts_typed_fields() {
+    let valve = Pattern::new("Valve {id} has flow rate={u32}; tunnel{_} lead{_} to valve{_} {}");
+    let (id, rate, tunnels): (&str, u32, &str) = valve.parse("Valve AA has flow rate=0; tunnels lead to valves DD, II, BB").unwrap();
+    assert_eq!((id, rate, tunnels), ("AA", 0, "DD, II, BB"));
+    let (_, _, tunnels): (&str, u32, &str) = valve.parse("Valve HH has flow rate=22; tunnel leads to valve GG").unwrap();
+    assert_eq!(tunnels, "GG");
+
+    let sensor = Pattern::new("x={i32}, y={i32}");
+    assert_eq!(sensor.parse::<(i32, i32)>("x=-2, y=15").unwrap(), (-2, 15));
+    assert_eq!(Pattern::new("{char} {u8}").parse::<(char, u8)>("R 4").unwrap(), ('R', 4));
+}
+
+#[test]
+fn fields_know_where_they_are() {
+    let fields = Pattern::new("move {u16} from {usize} to {usize}").fields("move 12 from 3 to 1").unwrap();
+    assert_eq!(fields[1], Field { text: "3", offset: 13 });
+    let line = "  Starting items: 79, x8";
+    let (items,): (Field,) = Pattern::new("  Starting items: {}").parse(line).unwrap();
+    let err = items.list(", ", parse_num::<u32>).unwrap_err();
+    assert_eq!(first_line(err, line), "1:23: 'x8' is not a valid number");
+}
+
+#[test]
+fn errors_point_at_the_mismatch() {
+    let pattern = Pattern::new("addx {i32}");
+    let error = |line| first_line(pattern.fields(line).unwrap_err(), line);
+    assert_eq!(error("adx 3"), "1:3: Expected 'dx ' (in 'addx {i32}')");
+    assert_eq!(error("addx x"), "1:6: Expected a number (in 'addx {i32}')");
+    assert_eq!(error("addx 3 4"), "1:7: Unexpected text at the end (in 'addx {i32}')");
+    assert!(!pattern.matches("noop"));
+    let overflow = Pattern::new("x={u8}").parse::<(u8,)>("x=300").unwrap_err();
+    assert!(first_line(overflow, "x=300").starts_with("1:3: "));
+}
+
+/// The `line:column: message` part of how an error is shown for `line`.
+fn first_line(err: Error, line: &str) -> String {
+    err.on_line(0, line).to_string().lines().next().unwrap().to_string()
+}
+
+#[test]
+fn lists() {
+    assert_eq!(parse_nums::<i64>("1,-2,3", ",").unwrap(), vec![1, -2, 3]);
+    let line = "1 -> 2 -> x";
+    let err = parse_list(line, " -> ", parse_num::<u32>).unwrap_err();
+    assert_eq!(first_line(err, line), "1:11: 'x' is not a valid number");
+}
+
+#[test]
+fn char_maps() {
+    let tile = char_map(&[('.', false), ('#', true)]);
+    assert!(tile('#').unwrap());
+    assert_eq!(tile('x').unwrap_err().to_string(), "Parse error: Expected . or #, found x");
+    let digit = char_map(&[('2', 2), ('1', 1), ('0', 0)]);
+    assert_eq!(digit('-').unwrap_err().to_string(), "Parse error: Expected 2, 1 or 0, found -");
+}
@@ -0,0 +1,135 @@
+//! Checks laws that have to hold for any value, on many random ones. Every case is made from its own seed,
+//! which failures name so that they can be reproduced.
+
+use std::cmp::Ordering;
+use std::fmt::Debug;
+use advent_of_code_2022::day13::PacketEntry;
+use advent_of_code_2022::day14::RockStrip;
+use advent_of_code_2022::day15::{Ranges, Sensor};
+use advent_of_code_2022::day25::Snafu;
+use advent_of_code_2022::generate::Rng;
+use advent_of_code_2022::geom::{Vec2, Vec3};
+
+/// How many random cases each property is checked with.
+const CASES: u64 = 500;
+
+/// Checks `property` on values made by `generate`, panicking with the seed and value of the first that fails.
+fn for_all<T: Debug>(generate: impl Fn(&mut Rng) -> T, property: impl Fn(&T) -> Result<(), String>) {
+    for seed in 0..CASES {
+        let value = generate(&mut Rng::new(seed));
+        if let Err(message) = property(&value) {
+            panic!("Failed for seed {seed} with {value:?}: {message}")
+        }
+    }
+}
+
+/// Passes if `value` is parsed back from what it displays as.
+fn round_trips<T: ToString + std::str::FromStr<Err: Debug> + PartialEq + Debug>(value: &T) -> Result<(), String> {
+    let text = value.to_string();
+    match text.parse::<T>() {
+        Ok(parsed) if parsed == *value => Ok(()),
+        Ok(parsed) => Err(format!("{text} was parsed as {parsed:?}")),
+        Err(err) => Err(format!("{text} could not be parsed: {err:?}"))
+    }
+}
+
+fn vec2(rng: &mut Rng, max: i64) -> Vec2<i32> {
+    Vec2::new(rng.range(-max..=max) as i32, rng.range(-max..=max) as i32)
+}
+
+fn packet(rng: &mut Rng, depth: usize) -> PacketEntry {
+    if depth > 0 && rng.chance(40) {
+        PacketEntry::Number(rng.range(0..=12) as u8)
+    } else {
+        // Few different numbers and short lists, so that packets often share a prefix or are equal
+        PacketEntry::List((0..rng.range(0..=3)).map(|_| packet(rng, depth + 1)).collect())
+    }
+}
+
+#[test]
+fn snafu_round_trips() {
+    for_all(|rng| Snafu::from(rng.range(-(i64::MAX / 4)..=i64::MAX / 4)), round_trips);
+    for_all(|rng| Snafu::from(rng.range(-3000..=3000)), round_trips);
+    // Canonical SNAFU numbers have no leading zeros, so they are written back the same way
+    for_all(|rng| {
+        let digits = ['=', '-', '0', '1', '2'];
+        let rest: String = (0..rng.below(20)).map(|_| *rng.pick(&digits)).collect();
+        format!("{}{rest}", rng.pick(&['1', '2']))
+    }, |text| {
+        let written = text.parse::<Snafu>().map_err(|err| err.to_string())?.to_string();
+        if written == *text { Ok(()) } else { Err(format!("written as {written}")) }
+    });
+}
+
+#[test]
+fn packets_round_trip() {
+    for_all(|rng| packet(rng, 0), round_trips);
+}
+
+#[test]
+fn points_round_trip() {
+    for_all(|rng| Vec2::new(rng.range(i64::MIN + 1..=i64::MAX), rng.range(-5..=5)), round_trips);
+    for_all(|rng| Vec3::new(rng.range(-99..=99), rng.range(-99..=99), rng.range(-99..=99)), round_trips);
+    for_all(|rng| RockStrip::new((0..rng.range(1..=6)).map(|_| vec2(rng, 1000)).collect()), round_trips);
+}
+
+#[test]
+fn sensors_round_trip() {
+    for_all(|rng| Sensor::new(vec2(rng, i32::MAX as i64), vec2(rng, 5000000)), round_trips);
+}
+
+#[test]
+fn packet_order_is_antisymmetric() {
+    for_all(|rng| (packet(rng, 0), packet(rng, 0)), |(a, b)| {
+        if a.cmp(b) == b.cmp(a).reverse() { Ok(()) } else { Err(format!("{a} is {:?} {b}, but {b} is {:?} {a}", a.cmp(b), b.cmp(a))) }
+    });
+    for_all(|rng| packet(rng, 0), |a| if a.cmp(a) == Ordering::Equal { Ok(()) } else { Err("not equal to itself".to_string()) });
+}
+
+#[test]
+fn packet_order_is_transitive() {
+    for_all(|rng| {
+        let mut packets = [packet(rng, 0), packet(rng, 0), packet(rng, 0)];
+        packets.sort();
+        packets
+    }, |[a, b, c]| {
+        // Sorting only compares neighbors, so the outer two are still left to check
+        if a <= b && b <= c && a <= c { Ok(()) } else { Err(format!("{a} <= {b} <= {c} does not hold")) }
+    });
+}
+
+#[test]
+fn ranges_match_a_set() {
+    const MAX: i32 = 40;
+    for_all(|rng| {
+        (0..rng.range(1..=12)).map(|_| {
+            let from = rng.range(0..=MAX as i64) as i32;
+            (rng.chance(70), (from, from + rng.range(0..=10) as i32))
+        }).collect::<Vec<_>>()
+    }, |operations| {
+        let mut ranges = Ranges::new();
+        let mut set = [false; MAX as usize + 11];
+        for &(add, (from, to)) in operations {
+            ranges = if add { ranges.with((from, to)) } else { ranges.sub(&(from, to)) };
+            set[from as usize..=to as usize].fill(add);
+        }
+        let intervals = ranges.intervals();
+        if intervals.iter().any(|(from, to)| from > to) {
+            return Err(format!("{intervals:?} has empty intervals"))
+        }
+        if intervals.windows(2).any(|pair| pair[0].1 >= pair[1].0) {
+            return Err(format!("{intervals:?} is not sorted and disjoint"))
+        }
+        if let Some(x) = (0..set.len() as i32).find(|&x| ranges.contains(x) != set[x as usize]) {
+            return Err(format!("{intervals:?} disagrees about {x}"))
+        }
+        if ranges.num() != set.iter().filter(|&&contained| contained).count() {
+            return Err(format!("{intervals:?} has the wrong size {}", ranges.num()))
+        }
+        let free: Vec<i32> = ranges.free_in(0, MAX).all().collect();
+        if free != (0..=MAX).filter(|&x| !set[x as usize]).collect::<Vec<_>>() {
+            return Err(format!("{intervals:?} leaves {free:?} free"))
+        }
+        Ok(())
+    });
+}
@@ -0,0 +1,30 @@
+//! Checks how inputs are split into blank-line separated records.
+
+use advent_of_code_2022::utils::{parse_num, records};
+
+#[test]
+fn splits_on_blank_lines() {
+    let records = records("1\n2\n\n3\n");
+    assert_eq!(records.len(), 2);
+    assert_eq!((records[0].start, records[0].lines.clone()), (0, vec!["1", "2"]));
+    assert_eq!((records[1].start, records[1].lines.clone()), (3, vec!["3"]));
+}
+
+#[test]
+fn tolerates_sloppy_whitespace() {
+    let records = records("\r\n1 \r\n2\t\r\n  \r\n\r\n\r\n3\r\n\r\n\r\n");
+    assert_eq!(records.iter().map(|r| r.lines.clone()).collect::<Vec<_>>(), vec![vec!["1", "2"], vec!["3"]]);
+    assert_eq!(records[1].start, 6);
+}
+
+#[test]
+fn blank_input_has_no_records() {
+    assert!(records("\n \n").is_empty());
+}
+
+#[test]
+fn errors_point_at_the_line() {
+    let records = records("1\n\n2\nx");
+    let err = records[1].parse_lines(parse_num::<u32>).unwrap_err();
+    assert!(err.to_string().starts_with("4:1: 'x' is not a valid number"), "{err}");
+}
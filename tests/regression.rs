@@ -0,0 +1,170 @@
+//! Pins the answers of every day, so that refactorings can't silently change them.
+//! Samples are checked against the published answers. Since everyone gets different puzzle inputs,
+//! answers for `input/dayNN.txt` are read from [ANSWERS_FILE] next to them instead, if it exists.
+//! Point `AOC_INPUT_DIR` at another directory to check somebody else's inputs and answers.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use advent_of_code_2022::input::{input_dir, InputSource};
+use advent_of_code_2022::solution::solution_for;
+use advent_of_code_2022::solution::Params;
+use advent_of_code_2022::utils::{Error, Part};
+use advent_of_code_2022::utils::Part::{One, Two};
+
+/// One line per answer, formatted as `DD.P: answer`. Multi-line answers (like the CRT of day 10)
+/// start on the following line, with every row indented by two spaces. Lines starting with `#` are ignored.
+const ANSWERS_FILE: &str = "answers.txt";
+
+const SAMPLE_ANSWERS: [(&str, &str); 25] = [
+    ("24000", "45000"),
+    ("15", "12"),
+    ("157", "70"),
+    ("2", "4"),
+    ("CMZ", "MCD"),
+    ("7", "19"),
+    ("95437", "24933642"),
+    ("21", "8"),
+    ("13", "36"),
+    ("13140", "\
+##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######....."),
+    ("10605", "2713310158"),
+    ("31", "29"),
+    ("13", "140"),
+    ("24", "93"),
+    ("26", "56000011"),
+    ("1651", "1707"),
+    ("3068", "1514285714288"),
+    ("64", "58"),
+    ("33", "3472"),
+    ("3", "1623178306"),
+    ("152", "301"),
+    ("6032", "5031"),
+    ("110", "20"),
+    ("18", "54"),
+    ("2=-1=0", "Merry Christmas!")
+];
+
+fn parse_answers(text: &str) -> Result<HashMap<(u8, Part), String>, Error> {
+    let mut answers: HashMap<(u8, Part), String> = HashMap::new();
+    let mut last_key = None;
+    for line in text.lines().filter(|l| !l.trim().is_empty() && !l.starts_with('#')) {
+        if let Some(row) = line.strip_prefix("  ") {
+            let key = last_key.ok_or(Error::parse(format!("Answer row without a day: {line}")))?;
+            let answer = answers.entry(key).or_default();
+            if !answer.is_empty() { answer.push('\n') }
+            answer.push_str(row);
+            continue;
+        }
+        let (key, answer) = line.split_once(':').ok_or(Error::parse(format!("Missing ':' in {line}")))?;
+        let (day, part) = key.split_once('.').ok_or(Error::parse(format!("Expected DD.P, got {key}")))?;
+        let part = match part {
+            "1" => One,
+            "2" => Two,
+            _ => return Err(Error::parse(format!("There is no part {part}")))
+        };
+        let key = (day.parse()?, part);
+        answers.insert(key, answer.trim().to_string());
+        last_key = Some(key);
+    }
+    Ok(answers)
+}
+
+/// Line-wise diff, prefixing removed lines with `-` and added ones with `+`.
+fn diff(expected: &str, actual: &str) -> String {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+    let mut result = String::new();
+    for i in 0..expected.len().max(actual.len()) {
+        match (expected.get(i), actual.get(i)) {
+            (Some(e), Some(a)) if e == a => result += &format!("  {e}\n"),
+            (e, a) => {
+                if let Some(e) = e { result += &format!("- {e}\n") }
+                if let Some(a) = a { result += &format!("+ {a}\n") }
+            }
+        }
+    }
+    result
+}
+
+/// Solves the given parts and panics with a diff of every answer that differs from the expected one.
+/// Parts without an expected answer are skipped.
+fn check(day: u8, source: InputSource, parts: &[Part], expected: impl Fn(Part) -> Option<String>) {
+    let solution = solution_for(day, &Params { sample: source.is_sample(), ..Params::default() })
+        .unwrap_or_else(|e| panic!("{e}"));
+    let mut failures = Vec::new();
+    for &part in parts {
+        let Some(expected) = expected(part) else { continue };
+        let path = source.path(day, part).unwrap_or_else(|e| panic!("{e}"));
+        let text = fs::read_to_string(&path).unwrap_or_else(|e| panic!("Failed to read {path}: {e}"));
+        let actual = solution.parse_any(&text)
+            .and_then(|input| solution.solve_any(input.as_ref(), part))
+            .map_or_else(|e| format!("Error: {e}"), |answer| answer.to_string());
+        if actual != expected {
+            failures.push(format!("Day {day} part {part} ({path}) changed:\n{}", diff(&expected, &actual)));
+        }
+    }
+    if !failures.is_empty() {
+        panic!("\n{}", failures.join("\n"))
+    }
+}
+
+fn check_sample(day: u8, parts: &[Part]) {
+    let (part1, part2) = SAMPLE_ANSWERS[day as usize - 1];
+    check(day, InputSource::Sample, parts, |part| Some(match part {
+        One => part1,
+        Two => part2
+    }.to_string()))
+}
+
+fn check_actual(day: u8, parts: &[Part]) {
+    let answers_file = format!("{}/{ANSWERS_FILE}", input_dir().unwrap_or_else(|e| panic!("{e}")));
+    if !Path::new(&answers_file).exists() { return }
+    let text = fs::read_to_string(&answers_file).unwrap_or_else(|e| panic!("Failed to read {answers_file}: {e}"));
+    let answers = parse_answers(&text).unwrap_or_else(|e| panic!("Invalid {answers_file}: {e}"));
+    check(day, InputSource::Actual, parts, |part| answers.get(&(day, part)).cloned())
+}
+
+macro_rules! regression_tests {
+    ($($name: ident: $day: literal),* $(,)?) => {
+        mod sample {$(
+            #[test]
+            fn $name() { super::check_sample($day, &[super::One, super::Two]) }
+        )*}
+        mod actual {$(
+            #[test]
+            fn $name() { super::check_actual($day, &[super::One, super::Two]) }
+        )*}
+    }
+}
+
+regression_tests! {
+    day01: 1, day02: 2, day03: 3, day04: 4, day05: 5, day06: 6, day07: 7, day08: 8, day09: 9, day10: 10,
+    day11: 11, day12: 12, day13: 13, day14: 14, day15: 15, day16: 16, day18: 18, day20: 20,
+    day21: 21, day22: 22, day23: 23, day24: 24, day25: 25
+}
+
+#[test]
+fn sample_day17_part1() { check_sample(17, &[One]) }
+
+#[test]
+#[ignore = "the tower is only cut off at completely filled rows, which the sample never produces"]
+fn sample_day17_part2() { check_sample(17, &[Two]) }
+
+#[test]
+fn actual_day17() { check_actual(17, &[One, Two]) }
+
+#[test]
+fn sample_day19_part1() { check_sample(19, &[One]) }
+
+#[test]
+#[ignore = "takes more than a minute even with optimizations"]
+fn sample_day19_part2() { check_sample(19, &[Two]) }
+
+#[test]
+fn actual_day19() { check_actual(19, &[One, Two]) }
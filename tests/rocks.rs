@@ -0,0 +1,13 @@
+//! Checks the falling rocks of day 17 beyond the puzzle's inputs.
+
+use advent_of_code_2022::solution::{solution_for, Params};
+use advent_of_code_2022::utils::Part;
+
+#[test]
+fn towers_that_never_repeat_are_still_measured() {
+    // Pushed to the left wall, no row ever fills, and the tower grows past the height generated patterns may reach
+    let params = Params { overrides: vec![("rocks_part1".to_string(), "40000".to_string())], ..Params::default() };
+    let solution = solution_for(17, &params).unwrap();
+    let parsed = solution.parse_any("<").unwrap();
+    assert_eq!(solution.solve_any(parsed.as_ref(), Part::One).unwrap().to_string(), "88000");
+}
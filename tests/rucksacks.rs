@@ -0,0 +1,80 @@
+//! Checks rucksacks of day 3 with other group sizes, compartments and items than the puzzle's.
+
+use advent_of_code_2022::day03::{badges, Day03, ItemSet, Priorities};
+use advent_of_code_2022::solution::{Answer, Solution};
+
+fn day(compartments: usize, group_size: usize, priorities: &str) -> Day03 {
+    Day03 { compartments, group_size, priorities: priorities.parse().unwrap() }
+}
+
+#[test]
+fn priority_tables_expand_ranges() {
+    let letters = Priorities::letters();
+    assert_eq!((letters.len(), letters.index('a'), letters.index('Z'), letters.index('0')), (52, Some(0), Some(51), None));
+    let table: Priorities = "0-9 +- äö".parse().unwrap();
+    assert_eq!(table.len(), 14);
+    assert_eq!(table.index('-'), Some(11));
+    assert_eq!(table.priority(table.index('ö').unwrap()), 14);
+    assert!("aba".parse::<Priorities>().unwrap_err().to_string().contains("a has two priorities"));
+    assert!("z-a".parse::<Priorities>().is_err());
+}
+
+#[test]
+fn item_sets_grow_past_64_items() {
+    let (mut a, mut b) = (ItemSet::default(), ItemSet::default());
+    for index in [3, 64, 200] { a.insert(index) }
+    for index in [64, 130] { b.insert(index) }
+    assert!(a.contains(200) && !a.contains(199) && !b.contains(1000));
+    assert_eq!(a.intersection(&b).iter().collect::<Vec<_>>(), vec![64]);
+    assert_eq!(b.union(&a).iter().collect::<Vec<_>>(), vec![3, 64, 130, 200]);
+    assert!(a.intersection(&ItemSet::default()).is_empty());
+}
+
+#[test]
+fn names_the_shared_items() {
+    let day = day(2, 3, "a-zA-Z");
+    let rucksacks = day.parse("vJrwpWtwJgWrhcsFMMfFFhFp\njqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL\nPmmdzqPrVvPwwTWBwg").unwrap();
+    let shared: Vec<String> = rucksacks.iter().map(|rucksack| day.priorities.describe(&rucksack.shared())).collect();
+    assert_eq!(shared, vec!["p", "L", "P"]);
+    let group_badges = badges(&rucksacks, 3).unwrap();
+    assert_eq!(day.priorities.describe(&group_badges[0]), "r");
+    assert!(badges(&rucksacks, 2).is_err());
+}
+
+#[test]
+fn describes_every_rucksack_and_group_of_the_sample() {
+    let day = day(2, 3, "a-zA-Z");
+    let sample = "vJrwpWtwJgWrhcsFMMfFFhFp\njqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL\nPmmdzqPrVvPwwTWBwg\n\
+        wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn\nttgJtRGJQctTZtZT\nCrZsJsPPZsGzwwsLwLmpwMDw";
+    let lines = day.describe(&day.parse(sample).unwrap()).unwrap();
+    assert_eq!(lines, vec![
+        "rucksack 1: p (priority 16)",
+        "rucksack 2: L (priority 38)",
+        "rucksack 3: P (priority 42)",
+        "rucksack 4: v (priority 22)",
+        "rucksack 5: t (priority 20)",
+        "rucksack 6: s (priority 19)",
+        "group 1: badge r (priority 18)",
+        "group 2: badge Z (priority 52)"
+    ]);
+}
+
+#[test]
+fn any_compartments_groups_and_items() {
+    // Digits beyond the 64 bits of a word, in three compartments sharing 7, and groups of two sharing the badge
+    let priorities = "a-z A-Z α-ω 0-9";
+    let day = day(3, 2, priorities);
+    let table: Priorities = priorities.parse().unwrap();
+    assert!(table.len() > 64);
+    let input = "17a27b73c\n5x75y78z7\n";
+    let rucksacks = day.parse(input).unwrap();
+    let seven = table.priority(table.index('7').unwrap());
+    assert_eq!(day.part1(&rucksacks).unwrap(), Answer::Int(2 * seven as i64));
+    assert_eq!(day.part2(&rucksacks).unwrap(), Answer::Int(seven as i64));
+
+    let err = day.parse("ab\n").unwrap_err();
+    assert!(err.to_string().contains("2 items cannot be split into 3 compartments"), "{err}");
+    let err = day.parse("aab\nb!b\n").unwrap_err();
+    assert!(err.to_string().starts_with("2:2: Invalid item !"), "{err}");
+    assert!(day.part1(&day.parse("abc\n").unwrap()).unwrap_err().to_string().contains("Rucksack 1 has no item in common"));
+}
@@ -0,0 +1,57 @@
+//! Checks the search algorithms on a small weighted graph and a number line.
+
+use advent_of_code_2022::search::{a_star, bfs, dijkstra};
+
+/// a -1- b -1- c -1- d, plus a shortcut a -5- d.
+fn edges(node: &char) -> Vec<(char, u64)> {
+    match node {
+        'a' => vec![('b', 1), ('d', 5)],
+        'b' => vec![('a', 1), ('c', 1)],
+        'c' => vec![('b', 1), ('d', 1)],
+        'd' => vec![('c', 1), ('a', 5)],
+        _ => vec![]
+    }
+}
+
+#[test]
+fn dijkstra_finds_the_cheapest_path() {
+    let path = dijkstra('a', edges, |&n| n == 'd').unwrap();
+    assert_eq!(path.cost, 3);
+    assert_eq!(path.states, vec!['a', 'b', 'c', 'd']);
+}
+
+#[test]
+fn bfs_finds_the_fewest_steps() {
+    let path = bfs('a', |n| edges(n).into_iter().map(|(next, _)| next), |&n| n == 'd').unwrap();
+    assert_eq!(path.cost, 1);
+    assert_eq!(path.states, vec!['a', 'd']);
+}
+
+#[test]
+fn a_star_with_heuristic() {
+    let path = a_star(0i64, |&n| [(n - 1, 1), (n + 1, 1), (n + 3, 2)], |&n| (10 - n).unsigned_abs() / 3, |&n| n == 10).unwrap();
+    assert_eq!(path.cost, 7);
+    assert_eq!(path.states.first(), Some(&0));
+    assert_eq!(path.states.last(), Some(&10));
+}
+
+#[test]
+fn a_star_reopens_states_with_inconsistent_heuristics() {
+    // b never overestimates the 4 left to g, but lets c be searched via a first, for 4 instead of 3
+    let neighbors = |n: &char| match n {
+        's' => vec![('a', 1), ('b', 2)],
+        'a' => vec![('c', 3)],
+        'b' => vec![('c', 1)],
+        'c' => vec![('g', 3)],
+        _ => vec![]
+    };
+    let path = a_star('s', neighbors, |&n| if n == 'b' {4} else {0}, |&n| n == 'g').unwrap();
+    assert_eq!(path.cost, 6);
+    assert_eq!(path.states, vec!['s', 'b', 'c', 'g']);
+}
+
+#[test]
+fn unreachable_goal() {
+    assert_eq!(dijkstra('a', edges, |&n| n == 'z'), None);
+    assert_eq!(bfs(0u8, |&n| if n < 5 { vec![n + 1] } else { vec![] }, |&n| n == 6), None);
+}
@@ -0,0 +1,49 @@
+//! Checks the frame recorder the simulations draw into.
+
+use std::fs;
+use std::time::Duration;
+use advent_of_code_2022::utils::Grid;
+use advent_of_code_2022::visualize::{to_ppm, Output, Recorder, Visualize};
+
+#[test]
+fn outputs_are_parsed() {
+    assert_eq!("ppm:frames".parse::<Output>().unwrap(), Output::Ppm("frames".to_string()));
+    assert_eq!("text:/tmp/x".parse::<Output>().unwrap(), Output::Text("/tmp/x".to_string()));
+    assert_eq!("terminal".parse::<Output>().unwrap(), Output::Terminal { delay: Duration::from_millis(50) });
+    assert_eq!("terminal:5".parse::<Output>().unwrap(), Output::Terminal { delay: Duration::from_millis(5) });
+    assert!("terminal:fast".parse::<Output>().is_err());
+    assert!("ppm".parse::<Output>().is_err());
+    assert!("gif:frames".parse::<Output>().is_err());
+}
+
+#[test]
+fn images_scale_the_cells() {
+    let frame = Grid::new(3, 2, '#');
+    let image = to_ppm(&frame, 2);
+    let header = "P6\n6 4\n255\n";
+    assert!(image.starts_with(header.as_bytes()));
+    assert_eq!(image.len(), header.len() + 6 * 4 * 3);
+}
+
+#[test]
+fn skipped_ticks_are_not_drawn() {
+    let dir = std::env::temp_dir().join(format!("aoc_frames_{}", std::process::id()));
+    let visualize = Visualize { output: Output::Text(dir.to_string_lossy().to_string()), every: 3, scale: 1 };
+    let mut recorder = Recorder::start(Some(&visualize), "test").unwrap();
+    let mut drawn = 0;
+    for _ in 0..7 {
+        recorder.tick(|| { drawn += 1; Grid::new(2, 1, 'o') }).unwrap();
+    }
+    assert_eq!((recorder.frames(), drawn), (3, 3));
+    assert_eq!(fs::read_to_string(dir.join("test/frame_00002.txt")).unwrap(), "oo\n");
+    assert!(!dir.join("test/frame_00003.txt").exists());
+    drop(recorder);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn disabled_recorders_never_draw() {
+    let mut recorder = Recorder::start(None, "test").unwrap();
+    recorder.tick(|| panic!("Nothing is recorded")).unwrap();
+    assert_eq!(recorder.frames(), 0);
+}